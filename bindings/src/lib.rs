@@ -0,0 +1,24 @@
+// Typed bindings for the Solidity contracts in `contracts/`.
+//
+// The ABI files under `abi/` are exported from the Hardhat artifacts with
+// `npm run export-abi`. Re-run it after changing a contract so that any
+// drift breaks the build instead of failing at runtime.
+
+pub mod cognitive_registry {
+    ethers::contract::abigen!(
+        CognitiveRegistry,
+        "./abi/CognitiveRegistry.json",
+        derives(serde::Deserialize, serde::Serialize)
+    );
+}
+
+pub mod proof_of_reasoning {
+    ethers::contract::abigen!(
+        ProofOfReasoning,
+        "./abi/ProofOfReasoning.json",
+        derives(serde::Deserialize, serde::Serialize)
+    );
+}
+
+pub use cognitive_registry::{CognitiveRegistry, CognitiveRegistryEvents};
+pub use proof_of_reasoning::{ProofOfReasoning, ProofOfReasoningEvents};
@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod tests {
+    use bindings::cognitive_registry::{CognitiveRegistryEvents, TaskAssignedFilter, TaskCreatedFilter};
+    use bindings::proof_of_reasoning::ProofSubmittedFilter;
+    use ethers::abi::{AbiEncode, RawLog};
+    use ethers::contract::{EthEvent, EthLogDecode};
+    use ethers::types::{Address, H256, U256};
+
+    #[test]
+    fn test_event_signatures_match_contracts() {
+        assert_eq!(TaskCreatedFilter::abi_signature(), "TaskCreated(uint256,string,uint256)");
+        assert_eq!(TaskAssignedFilter::abi_signature(), "TaskAssigned(uint256,address)");
+        assert_eq!(ProofSubmittedFilter::abi_signature(), "ProofSubmitted(bytes32,address)");
+    }
+
+    #[test]
+    fn test_decode_task_assigned_log() {
+        let node = Address::repeat_byte(0x42);
+        
+        let log = RawLog {
+            topics: vec![
+                TaskAssignedFilter::signature(),
+                H256::from_low_u64_be(7),
+                H256::from(node),
+            ],
+            data: vec![],
+        };
+        
+        match CognitiveRegistryEvents::decode_log(&log).unwrap() {
+            CognitiveRegistryEvents::TaskAssignedFilter(event) => {
+                assert_eq!(event.task_id, U256::from(7));
+                assert_eq!(event.node, node);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_task_created_log() {
+        let log = RawLog {
+            topics: vec![TaskCreatedFilter::signature(), H256::from_low_u64_be(3)],
+            data: ("market_prediction".to_string(), U256::from(1_000)).encode(),
+        };
+        
+        let event = <TaskCreatedFilter as EthEvent>::decode_log(&log).unwrap();
+        assert_eq!(event.task_id, U256::from(3));
+        assert_eq!(event.task_type, "market_prediction");
+        assert_eq!(event.reward, U256::from(1_000));
+    }
+}
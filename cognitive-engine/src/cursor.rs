@@ -0,0 +1,65 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct CursorState {
+    last_block: u64,
+}
+
+// Last block whose TaskCreated logs have been scanned, kept on disk so a
+// restart resumes the scan instead of replaying the whole chain.
+pub struct BlockCursor {
+    path: PathBuf,
+}
+
+impl BlockCursor {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+    
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    
+    pub fn load(&self) -> Result<Option<u64>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        
+        let raw = std::fs::read_to_string(&self.path)?;
+        let state: CursorState = serde_json::from_str(&raw)?;
+        Ok(Some(state.last_block))
+    }
+    
+    pub fn save(&self, last_block: u64) -> Result<()> {
+        write_atomic(&self.path, &serde_json::to_vec(&CursorState { last_block })?)?;
+        Ok(())
+    }
+}
+
+// Replaces `path` with `bytes`. They go to a sibling file first, which is
+// synced and renamed over `path`, so a crash leaves either the old or the
+// new contents, never a torn file.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty());
+    if let Some(dir) = dir {
+        std::fs::create_dir_all(dir)?;
+    }
+    
+    let tmp = path.with_extension("tmp");
+    let mut file = File::create(&tmp)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    std::fs::rename(&tmp, path)?;
+    
+    // Make the rename itself durable
+    #[cfg(unix)]
+    if let Some(dir) = dir {
+        File::open(dir)?.sync_all()?;
+    }
+    
+    Ok(())
+}
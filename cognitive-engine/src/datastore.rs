@@ -0,0 +1,446 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::time::Duration;
+use tracing::debug;
+
+use shared::canonical::{to_canonical_bytes, CanonicalError};
+use shared::config::DataConfig;
+use shared::hashing::{parse_hash, task_data_hash, to_hex, Hash32};
+use shared::types::ReasoningTask;
+
+// Payloads are numeric series and transaction samples; anything larger than
+// this is refused before it is buffered.
+pub const MAX_TASK_DATA_BYTES: usize = 4 * 1024 * 1024;
+
+// Largest payload `IpfsDataStore` accepts: Kubo's largest chunk, so the
+// payload is stored as a single raw block whose CID is `raw_cid`
+pub const MAX_IPFS_BLOCK_BYTES: usize = 1024 * 1024;
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Error)]
+pub enum DataStoreError {
+    #[error("task data {0} not found")]
+    NotFound(String),
+    #[error("task data does not match dataHash: expected {expected}, got {actual}")]
+    HashMismatch { expected: String, actual: String },
+    #[error("task data is not valid JSON: {0}")]
+    InvalidJson(#[from] serde_json::Error),
+    #[error("task data is larger than {MAX_TASK_DATA_BYTES} bytes")]
+    TooLarge,
+    #[error("task data of {0} bytes does not fit in one IPFS block of {MAX_IPFS_BLOCK_BYTES} bytes")]
+    TooLargeForIpfs(usize),
+    #[error("IPFS stored task data as {actual}, expected {expected}")]
+    CidMismatch { expected: String, actual: String },
+    #[error("{store} store unavailable: {reason}")]
+    Unavailable { store: &'static str, reason: String },
+    #[error("invalid dataHash `{0}`")]
+    InvalidHash(String),
+    #[error("{0} store is read-only")]
+    ReadOnly(&'static str),
+    #[error(transparent)]
+    Canonical(#[from] CanonicalError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+}
+
+impl DataStoreError {
+    // Whether a later fetch may succeed: the payload may not have reached
+    // the store yet, or the store could not be reached
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::NotFound(_) | Self::Unavailable { .. } | Self::Io(_) | Self::Http(_))
+    }
+}
+
+// Where task payloads live off chain. Every backend is addressed by the
+// on-chain `dataHash` (SHA-256 of the canonical JSON), so a store never has to
+// be trusted: `fetch` re-hashes whatever comes back.
+#[async_trait]
+pub trait TaskDataStore: Send + Sync {
+    fn name(&self) -> &'static str;
+    
+    // Raw bytes stored under `data_hash`, unverified
+    async fn get_raw(&self, data_hash: &Hash32) -> Result<Vec<u8>, DataStoreError>;
+    
+    // Stores the canonical bytes of `data` and returns its dataHash
+    async fn put(&self, data: &Value) -> Result<Hash32, DataStoreError>;
+    
+    async fn fetch(&self, data_hash: &Hash32) -> Result<Value, DataStoreError> {
+        let bytes = self.get_raw(data_hash).await?;
+        verify(data_hash, &bytes)
+    }
+}
+
+// Parses fetched bytes and checks them against the on-chain dataHash
+pub fn verify(data_hash: &Hash32, bytes: &[u8]) -> Result<Value, DataStoreError> {
+    if bytes.len() > MAX_TASK_DATA_BYTES {
+        return Err(DataStoreError::TooLarge);
+    }
+    
+    let data: Value = serde_json::from_slice(bytes)?;
+    let actual = task_data_hash(&data)?;
+    
+    if actual != *data_hash {
+        return Err(DataStoreError::HashMismatch {
+            expected: to_hex(data_hash),
+            actual: to_hex(&actual),
+        });
+    }
+    
+    Ok(data)
+}
+
+// Fills in `task.data` from the store unless the task already carries it
+pub async fn resolve_task_data(store: &dyn TaskDataStore, task: &mut ReasoningTask) -> Result<(), DataStoreError> {
+    if !task.data.is_null() {
+        return Ok(());
+    }
+    
+    let data_hash = parse_hash(&task.data_hash).ok_or_else(|| DataStoreError::InvalidHash(task.data_hash.clone()))?;
+    task.data = store.fetch(&data_hash).await?;
+    Ok(())
+}
+
+// CIDv1 of a raw block (codec 0x55) with a sha2-256 multihash. Canonical
+// bytes of up to `MAX_IPFS_BLOCK_BYTES` added to IPFS with `raw-leaves` fit
+// in one block, so the CID can be derived from the dataHash without any
+// lookup.
+pub fn raw_cid(data_hash: &Hash32) -> String {
+    let mut bytes = vec![0x01, 0x55, 0x12, 0x20];
+    bytes.extend_from_slice(data_hash);
+    format!("b{}", base32_lower(&bytes))
+}
+
+// RFC 4648 base32, lowercase, unpadded (the multibase `b` encoding)
+fn base32_lower(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+    
+    let mut out = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    
+    out
+}
+
+async fn read_limited(response: reqwest::Response) -> Result<Vec<u8>, DataStoreError> {
+    if response.content_length().is_some_and(|len| len > MAX_TASK_DATA_BYTES as u64) {
+        return Err(DataStoreError::TooLarge);
+    }
+    
+    let mut response = response;
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        bytes.extend_from_slice(&chunk);
+        if bytes.len() > MAX_TASK_DATA_BYTES {
+            return Err(DataStoreError::TooLarge);
+        }
+    }
+    
+    Ok(bytes)
+}
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(HTTP_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+}
+
+// Content-addressed directory: `<root>/<hex dataHash>.json`
+pub struct LocalDataStore {
+    root: PathBuf,
+}
+
+impl LocalDataStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+    
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+    
+    pub fn path_for(&self, data_hash: &Hash32) -> PathBuf {
+        self.root.join(format!("{}.json", hex::encode(data_hash)))
+    }
+}
+
+#[async_trait]
+impl TaskDataStore for LocalDataStore {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+    
+    async fn get_raw(&self, data_hash: &Hash32) -> Result<Vec<u8>, DataStoreError> {
+        match tokio::fs::read(self.path_for(data_hash)).await {
+            Ok(bytes) => Ok(bytes),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(DataStoreError::NotFound(to_hex(data_hash))),
+            Err(e) => Err(e.into()),
+        }
+    }
+    
+    async fn put(&self, data: &Value) -> Result<Hash32, DataStoreError> {
+        let bytes = to_canonical_bytes(data)?;
+        let data_hash = task_data_hash(data)?;
+        let path = self.path_for(&data_hash);
+        
+        tokio::fs::create_dir_all(&self.root).await?;
+        
+        // Same write-then-rename as BlockCursor, so readers never see a partial file
+        let tmp = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, &bytes).await?;
+        tokio::fs::rename(&tmp, &path).await?;
+        
+        Ok(data_hash)
+    }
+}
+
+// Read-only HTTP gateway. The URL template may contain `{hash}` (hex dataHash
+// without 0x) and/or `{cid}` (raw CIDv1); without either, `/{hash}` is appended.
+pub struct GatewayDataStore {
+    template: String,
+    client: reqwest::Client,
+}
+
+impl GatewayDataStore {
+    pub fn new(template: impl Into<String>) -> Self {
+        let template = template.into();
+        let template = if template.contains("{hash}") || template.contains("{cid}") {
+            template
+        } else {
+            format!("{}/{{hash}}", template.trim_end_matches('/'))
+        };
+        
+        Self {
+            template,
+            client: http_client(),
+        }
+    }
+    
+    pub fn url_for(&self, data_hash: &Hash32) -> String {
+        self.template
+            .replace("{hash}", &hex::encode(data_hash))
+            .replace("{cid}", &raw_cid(data_hash))
+    }
+}
+
+#[async_trait]
+impl TaskDataStore for GatewayDataStore {
+    fn name(&self) -> &'static str {
+        "gateway"
+    }
+    
+    async fn get_raw(&self, data_hash: &Hash32) -> Result<Vec<u8>, DataStoreError> {
+        let response = self.client.get(self.url_for(data_hash)).send().await?;
+        
+        match response.status() {
+            status if status.is_success() => read_limited(response).await,
+            reqwest::StatusCode::NOT_FOUND => Err(DataStoreError::NotFound(to_hex(data_hash))),
+            status => Err(DataStoreError::Unavailable {
+                store: self.name(),
+                reason: format!("HTTP {}", status),
+            }),
+        }
+    }
+    
+    async fn put(&self, _data: &Value) -> Result<Hash32, DataStoreError> {
+        Err(DataStoreError::ReadOnly(self.name()))
+    }
+}
+
+// Kubo-compatible RPC API (`/api/v0/cat`, `/api/v0/add`)
+pub struct IpfsDataStore {
+    api_url: String,
+    client: reqwest::Client,
+}
+
+impl IpfsDataStore {
+    pub fn new(api_url: impl Into<String>) -> Self {
+        Self {
+            api_url: api_url.into().trim_end_matches('/').to_string(),
+            client: http_client(),
+        }
+    }
+}
+
+#[async_trait]
+impl TaskDataStore for IpfsDataStore {
+    fn name(&self) -> &'static str {
+        "ipfs"
+    }
+    
+    async fn get_raw(&self, data_hash: &Hash32) -> Result<Vec<u8>, DataStoreError> {
+        let response = self
+            .client
+            .post(format!("{}/api/v0/cat", self.api_url))
+            .query(&[("arg", raw_cid(data_hash))])
+            .send()
+            .await?;
+        
+        let status = response.status();
+        if status.is_success() {
+            return read_limited(response).await;
+        }
+        
+        // Kubo answers 500 with a JSON message for every error, a missing
+        // block included; only that one means the data is not there
+        let body = read_limited(response).await.unwrap_or_default();
+        let message = serde_json::from_slice::<Value>(&body)
+            .ok()
+            .and_then(|body| body["Message"].as_str().map(str::to_string))
+            .unwrap_or_default();
+        debug!("ipfs cat {} failed with {}: {}", to_hex(data_hash), status, message);
+        
+        if message.contains("not found") {
+            return Err(DataStoreError::NotFound(to_hex(data_hash)));
+        }
+        Err(DataStoreError::Unavailable {
+            store: self.name(),
+            reason: format!("HTTP {} {}", status, message).trim_end().to_string(),
+        })
+    }
+    
+    async fn put(&self, data: &Value) -> Result<Hash32, DataStoreError> {
+        let bytes = to_canonical_bytes(data)?;
+        let data_hash = task_data_hash(data)?;
+        
+        // Larger payloads would be split into several blocks, and readers
+        // could not find them by `raw_cid`
+        if bytes.len() > MAX_IPFS_BLOCK_BYTES {
+            return Err(DataStoreError::TooLargeForIpfs(bytes.len()));
+        }
+        
+        // Hand-rolled multipart body with a single file part
+        let boundary = format!("polyneurons-{}", hex::encode(&data_hash[..8]));
+        let mut body = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"data.json\"\r\nContent-Type: application/octet-stream\r\n\r\n"
+        )
+        .into_bytes();
+        body.extend_from_slice(&bytes);
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+        
+        let response: Value = self
+            .client
+            .post(format!("{}/api/v0/add", self.api_url))
+            .query(&[
+                ("cid-version", "1"),
+                ("raw-leaves", "true"),
+                ("chunker", &format!("size-{}", MAX_IPFS_BLOCK_BYTES)),
+                ("pin", "true"),
+            ])
+            .header("Content-Type", format!("multipart/form-data; boundary={}", boundary))
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        
+        // Stored any other way, readers would not find it
+        let expected = raw_cid(&data_hash);
+        let actual = response["Hash"].as_str().unwrap_or_default();
+        if actual != expected {
+            return Err(DataStoreError::CidMismatch {
+                expected,
+                actual: actual.to_string(),
+            });
+        }
+        
+        Ok(data_hash)
+    }
+}
+
+// Tries each store in order and returns the first payload that verifies.
+// `put` goes to every store that accepts writes.
+#[derive(Default, Clone)]
+pub struct DataStoreChain {
+    stores: Vec<Arc<dyn TaskDataStore>>,
+}
+
+impl DataStoreChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn with_store(mut self, store: Arc<dyn TaskDataStore>) -> Self {
+        self.stores.push(store);
+        self
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.stores.is_empty()
+    }
+    
+    // Local directory first, then the optional HTTP gateway and IPFS API
+    pub fn from_config(config: &DataConfig) -> Self {
+        let mut chain = Self::new().with_store(Arc::new(LocalDataStore::new(config.dir.clone())));
+        
+        if let Some(gateway) = &config.gateway {
+            chain = chain.with_store(Arc::new(GatewayDataStore::new(gateway.clone())));
+        }
+        
+        if let Some(api_url) = &config.ipfs_api_url {
+            chain = chain.with_store(Arc::new(IpfsDataStore::new(api_url.clone())));
+        }
+        
+        chain
+    }
+}
+
+#[async_trait]
+impl TaskDataStore for DataStoreChain {
+    fn name(&self) -> &'static str {
+        "chain"
+    }
+    
+    async fn get_raw(&self, data_hash: &Hash32) -> Result<Vec<u8>, DataStoreError> {
+        Ok(serde_json::to_vec(&self.fetch(data_hash).await?)?)
+    }
+    
+    async fn put(&self, data: &Value) -> Result<Hash32, DataStoreError> {
+        let mut stored = None;
+        
+        for store in &self.stores {
+            match store.put(data).await {
+                Ok(data_hash) => stored = Some(data_hash),
+                Err(DataStoreError::ReadOnly(_)) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        
+        stored.ok_or(DataStoreError::ReadOnly(self.name()))
+    }
+    
+    async fn fetch(&self, data_hash: &Hash32) -> Result<Value, DataStoreError> {
+        let mut last_error = DataStoreError::NotFound(to_hex(data_hash));
+        
+        for store in &self.stores {
+            match store.fetch(data_hash).await {
+                Ok(data) => return Ok(data),
+                Err(e) => {
+                    debug!("{} store could not serve {}: {}", store.name(), to_hex(data_hash), e);
+                    last_error = e;
+                }
+            }
+        }
+        
+        Err(last_error)
+    }
+}
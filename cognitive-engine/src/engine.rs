@@ -1,87 +1,469 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use ethers::prelude::*;
+use std::net::SocketAddr;
+use std::str::FromStr;
 use std::sync::Arc;
+use tokio::sync::Semaphore;
 use tokio::time::{interval, Duration};
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 
+use crate::cursor::BlockCursor;
+use crate::datastore::{resolve_task_data, DataStoreChain};
+use crate::ingest::{EventSubscriber, WorkQueue};
+use crate::jobs::{unix_now, Job, JobState, JobStore};
+use crate::metrics::{task_type_label, EngineMetrics, Stage};
+use crate::reasoning::ModuleRegistry;
+use crate::registry::{EngineClient, RegistryClient, RegistryError};
+use crate::signer::NodeSigner;
 use crate::tasks::TaskProcessor;
-use shared::types::{ReasoningTask, ReasoningResult};
+use shared::admission::AdmissionPolicy;
+use shared::config::NodeConfig;
+use shared::hashing::{result_hash, to_hex, Hash32};
+use shared::telemetry::{MeteredProvider, Telemetry};
+use shared::types::ReasoningTask;
+
+// Sends of a result transaction that may be dropped before the job is left
+// for the next restart
+const MAX_SUBMIT_ATTEMPTS: u32 = 3;
 
 pub struct CognitiveEngine {
-    _provider: Arc<Provider<Http>>,
-    _wallet: LocalWallet,
-    _registry_address: Address,
+    provider: Arc<MeteredProvider>,
+    node_address: Address,
+    signer_address: Address,
+    registry: Arc<RegistryClient<EngineClient>>,
+    cursor: Arc<BlockCursor>,
+    start_block: u64,
+    ws_url: Option<String>,
+    poll_interval: Duration,
+    queue: Arc<WorkQueue>,
+    data_store: DataStoreChain,
+    jobs: Arc<JobStore>,
+    admission: AdmissionPolicy,
     task_processor: TaskProcessor,
+    // Tasks handled at the same time
+    concurrency: usize,
+    // Upper bound on a single reasoning run; the task's deadline may cut it shorter
+    max_task_timeout: Duration,
+    metrics: EngineMetrics,
+    metrics_addr: Option<SocketAddr>,
 }
 
 impl CognitiveEngine {
-    pub async fn new() -> Result<Self> {
-        let rpc_url = std::env::var("POLYGON_RPC_URL")
-            .unwrap_or_else(|_| "http://localhost:8545".to_string());
+    pub async fn new(config: &NodeConfig) -> Result<Self> {
+        let telemetry = Arc::new(Telemetry::new(config.engine.max_lag_blocks));
+        let metrics = EngineMetrics::new(telemetry.clone());
         
-        let provider = Provider::<Http>::try_from(rpc_url)?;
+        let provider = Provider::new(telemetry.metered(Http::from_str(&config.chain.rpc_url)?));
         let provider = Arc::new(provider);
         
-        let private_key = std::env::var("PRIVATE_KEY")
-            .unwrap_or_else(|_| "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".to_string());
-        let wallet: LocalWallet = private_key.parse()?;
+        let chain_id = config.chain.check_chain_id(provider.get_chainid().await?.as_u64())?;
+        let signer = NodeSigner::from_config(&config.signer, chain_id).await?;
+        let signer_address = signer.address();
+        info!("🔑 Signing as {:?} with {}", signer_address, signer.describe());
+        
+        let client = SignerMiddleware::new((*provider).clone(), signer);
+        let client = Arc::new(NonceManagerMiddleware::new(client, signer_address));
+        
+        let registry_address = config
+            .chain
+            .registry_address
+            .ok_or_else(|| anyhow!("chain.registry_address must be set"))?;
+        
+        let registry = RegistryClient::new(registry_address, client)
+            .with_confirmations(config.chain.confirmations);
+        
+        // An operator key claims and completes tasks for the node that staked
+        let node_address = registry.node_of(signer_address).await?;
+        if node_address != signer_address {
+            info!("🧑‍🔧 Operating for node {:?}", node_address);
+        }
+        
+        let cursor = BlockCursor::new(&config.engine.cursor_path);
+        
+        let data_store = DataStoreChain::from_config(&config.data);
         
-        let registry_address = std::env::var("REGISTRY_ADDRESS")
-            .unwrap_or_else(|_| "0x0000000000000000000000000000000000000000".to_string())
-            .parse()?;
+        let jobs = JobStore::open(&config.engine.jobs_path)?;
         
-        let task_processor = TaskProcessor::new();
+        let mut modules = ModuleRegistry::with_builtin_modules();
+        if !config.modules.enabled.is_empty() {
+            modules.retain(&config.modules.enabled)?;
+        }
+        let task_processor = TaskProcessor::with_modules(modules).with_latency(metrics.module_latency.clone());
         
         Ok(Self {
-            _provider: provider,
-            _wallet: wallet,
-            _registry_address: registry_address,
+            provider,
+            node_address,
+            signer_address,
+            registry: Arc::new(registry),
+            cursor: Arc::new(cursor),
+            start_block: config.chain.start_block,
+            // Without a WebSocket endpoint the engine falls back to polling over HTTP
+            ws_url: config.chain.ws_url.clone(),
+            poll_interval: Duration::from_secs(config.engine.poll_interval_secs),
+            queue: Arc::new(WorkQueue::new()),
+            data_store,
+            jobs: Arc::new(jobs),
+            admission: config.engine.admission.clone(),
             task_processor,
+            concurrency: config.engine.concurrency,
+            max_task_timeout: Duration::from_secs(config.engine.max_task_timeout_secs),
+            metrics,
+            metrics_addr: config.engine.metrics_addr,
         })
     }
     
-    pub async fn run(&self) -> Result<()> {
-        let mut ticker = interval(Duration::from_secs(10));
+    pub async fn run(self: Arc<Self>) -> Result<()> {
+        let telemetry = self.metrics.telemetry.clone();
+        if let Some(addr) = self.metrics_addr {
+            let addr = telemetry.clone().serve(addr)?;
+            info!("📈 Serving /metrics, /healthz and /readyz on {}", addr);
+        }
+        telemetry.spawn_probe(self.provider.clone(), self.signer_address);
+        
+        match &self.ws_url {
+            Some(ws_url) => self.spawn_event_subscriber(ws_url.clone()),
+            None => self.clone().spawn_polling(),
+        }
+        
+        self.run_workers().await
+    }
+    
+    // Hands queued tasks to at most `concurrency` workers. A permit is taken
+    // before popping, so a freed worker always gets the best task queued at
+    // that moment. Errors and panics stay within their own task.
+    async fn run_workers(self: Arc<Self>) -> Result<()> {
+        info!("👷 Running up to {} task(s) at a time", self.concurrency);
+        
+        let workers = Arc::new(Semaphore::new(self.concurrency));
+        
+        for job in self.jobs.unfinished() {
+            let permit = workers.clone().acquire_owned().await?;
+            let engine = self.clone();
+            
+            tokio::spawn(async move {
+                let _permit = permit;
+                let task_id = job.task.task_id;
+                if let Err(e) = engine.resume_job(job).await {
+                    warn!("Could not resume task {}: {}", task_id, e);
+                }
+            });
+        }
         
         loop {
-            ticker.tick().await;
+            let permit = workers.clone().acquire_owned().await?;
+            let task = self.queue.pop().await;
+            let engine = self.clone();
             
-            if let Err(e) = self.process_pending_tasks().await {
-                warn!("Error processing tasks: {}", e);
-            }
+            tokio::spawn(async move {
+                let _permit = permit;
+                let task_id = task.task_id;
+                if let Err(e) = engine.handle_task(task).await {
+                    warn!("Error processing task {}: {}", task_id, e);
+                }
+            });
         }
     }
     
-    async fn process_pending_tasks(&self) -> Result<()> {
-        info!("🔍 Checking for pending tasks...");
+    fn spawn_polling(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut ticker = interval(self.poll_interval);
+            
+            loop {
+                ticker.tick().await;
+                info!("🔍 Checking for pending tasks...");
+                
+                match self.fetch_pending_tasks().await {
+                    Ok(tasks) => {
+                        for task in tasks {
+                            self.queue.push(task);
+                        }
+                        if let Ok(Some(last)) = self.cursor.load() {
+                            self.metrics.telemetry.processed(last);
+                        }
+                    }
+                    Err(e) => warn!("Error fetching tasks: {}", e),
+                }
+            }
+        });
+    }
+    
+    fn spawn_event_subscriber(&self, ws_url: String) {
+        info!("📡 Event-driven mode, following registry over WebSocket");
         
-        let tasks = self.fetch_pending_tasks().await?;
+        let subscriber = EventSubscriber::new(
+            ws_url,
+            self.registry.clone(),
+            self.cursor.clone(),
+            self.jobs.clone(),
+            self.start_block,
+            self.queue.clone(),
+        )
+        .with_telemetry(self.metrics.telemetry.clone());
+        tokio::spawn(async move { subscriber.run().await });
+    }
+    
+    async fn handle_task(&self, mut task: ReasoningTask) -> Result<()> {
+        let task_id = task.task_id;
         
-        for task in tasks {
-            info!("📋 Processing task: {} (type: {})", task.task_id, task.task_type);
-            
-            match self.task_processor.process(&task).await {
-                Ok(result) => {
-                    info!("✅ Task {} completed", task.task_id);
-                    self.submit_result(task.task_id, result).await?;
+        // Backfill, polling and live events can all report the same task
+        let Some(from) = self.jobs.take_up(&task).await? else {
+            debug!("Task {} already handled", task_id);
+            return Ok(());
+        };
+        if from == JobState::Discovered {
+            self.metrics.discovered.inc(&[self.task_label(&task.task_type)]);
+        }
+        
+        if !self.task_processor.modules().supports(&task) {
+            info!("🚫 Not taking task {}: no module for task type {}", task_id, task.task_type);
+            return self
+                .fail(task_id, Stage::Admission, format!("no module for task type {}", task.task_type))
+                .await;
+        }
+        
+        let gas_price = match self.provider.get_gas_price().await {
+            Ok(gas_price) => gas_price.min(U256::from(u128::MAX)).as_u128(),
+            Err(e) => {
+                warn!("⛽ Could not get gas price for task {}: {}", task_id, e);
+                return self.defer(task, format!("gas price unavailable: {}", e)).await;
+            }
+        };
+        if let Err(reason) = self.admission.admit(&task, gas_price, unix_now()) {
+            // Retried until gas gets cheaper or the deadline comes too close
+            if reason.is_transient() {
+                info!("⏸️  Not taking task {} yet: {}", task_id, reason);
+                return self.defer(task, format!("not admitted yet: {}", reason)).await;
+            }
+            info!("🚫 Not taking task {}: {}", task_id, reason);
+            return self.fail(task_id, Stage::Admission, format!("not admitted: {}", reason)).await;
+        }
+        
+        // Only claim tasks whose payload we can actually get hold of
+        if let Err(e) = resolve_task_data(&self.data_store, &mut task).await {
+            if e.is_transient() {
+                warn!("📭 Data for task {} not available yet: {}", task_id, e);
+                return self.defer(task, format!("task data unavailable: {}", e)).await;
+            }
+            warn!("📭 Skipping task {}: {}", task_id, e);
+            return self
+                .fail(task_id, Stage::Admission, format!("task data unavailable: {}", e))
+                .await;
+        }
+        if let Err(reason) = self.admission.admit_payload(&task) {
+            info!("🚫 Not taking task {}: {}", task_id, reason);
+            return self.fail(task_id, Stage::Admission, format!("not admitted: {}", reason)).await;
+        }
+        self.jobs.set_task(&task).await?;
+        
+        match self.claim_task(task_id).await {
+            Ok(tx_hash) => {
+                self.jobs.mark_claimed(task_id, format!("{:?}", tx_hash)).await?;
+                self.metrics.claimed.inc(&[self.task_label(&task.task_type)]);
+            }
+            // Somebody else has the task, or nobody can have it any more
+            Err(
+                e @ (RegistryError::AlreadyAssigned(_)
+                | RegistryError::AlreadyCompleted(_)
+                | RegistryError::TaskExpired(_)),
+            ) => {
+                info!("⏭️  Skipping task {}: {}", task_id, e);
+                return self.fail(task_id, Stage::Claim, &e).await;
+            }
+            // RPC trouble or a problem with this node; the task may still be
+            // ours to claim, until admission finds its deadline has passed
+            Err(e) => {
+                warn!("❌ Could not claim task {}: {}", task_id, e);
+                return self.defer(task, format!("claim failed: {}", e)).await;
+            }
+        }
+        
+        self.compute_and_submit(task).await
+    }
+    
+    // Picks up a job interrupted by a crash or restart. Claimed tasks are
+    // computed again, computed results are sent without recomputing, and
+    // sent transactions are awaited rather than sent a second time.
+    async fn resume_job(&self, job: Job) -> Result<()> {
+        let task_id = job.task.task_id;
+        
+        match job.state {
+            // The claim may have been mined just before the engine stopped;
+            // otherwise admission starts over
+            JobState::Discovered | JobState::Admitting | JobState::Deferred => {
+                let on_chain = self.registry.get_task(task_id).await?;
+                if !on_chain.completed && on_chain.assigned_node == self.node_address {
+                    info!("🔒 Task {} was claimed before the restart", task_id);
+                    self.jobs.recover_claim(task_id).await?;
+                    return self.compute_and_submit(job.task).await;
+                }
+                
+                if job.state == JobState::Admitting {
+                    self.jobs.defer(task_id, "interrupted before the claim").await?;
+                }
+                self.queue.push(job.task);
+                Ok(())
+            }
+            JobState::Claimed => {
+                let on_chain = self.registry.get_task(task_id).await?;
+                if on_chain.completed || on_chain.assigned_node != self.node_address {
+                    return self
+                        .fail(task_id, Stage::Claim, "task no longer assigned to this node")
+                        .await;
                 }
-                Err(e) => {
-                    warn!("❌ Task {} failed: {}", task.task_id, e);
+                
+                self.compute_and_submit(job.task).await
+            }
+            JobState::Computed => {
+                let result = job.result.ok_or_else(|| anyhow!("job {} has no stored result", task_id))?;
+                self.submit_result(task_id, result_hash(&result)?).await
+            }
+            JobState::Submitted => {
+                let tx_hash: TxHash = job
+                    .submit_tx
+                    .ok_or_else(|| anyhow!("job {} has no result transaction", task_id))?
+                    .parse()?;
+                
+                if self.await_confirmation(task_id, tx_hash).await? {
+                    return Ok(());
                 }
+                
+                let result = job.result.ok_or_else(|| anyhow!("job {} has no stored result", task_id))?;
+                self.submit_result(task_id, result_hash(&result)?).await
             }
+            JobState::Confirmed | JobState::Failed => Ok(()),
         }
+    }
+    
+    async fn compute_and_submit(&self, task: ReasoningTask) -> Result<()> {
+        let task_id = task.task_id;
+        info!("📋 Processing task: {} (type: {})", task_id, task.task_type);
         
-        Ok(())
+        let Some(limit) = self.time_limit(&task) else {
+            warn!("⌛ Deadline of task {} passed before it could run", task_id);
+            return self.fail(task_id, Stage::Compute, "deadline passed").await;
+        };
+        
+        match self.task_processor.process_with_timeout(&task, limit).await {
+            Ok(result) => {
+                info!("✅ Task {} completed", task_id);
+                
+                let result_hash = result_hash(&result)?;
+                self.jobs.mark_computed(task_id, result, to_hex(&result_hash)).await?;
+                self.submit_result(task_id, result_hash).await
+            }
+            Err(e) => {
+                warn!("❌ Task {} failed: {}", task_id, e);
+                self.fail(task_id, Stage::Compute, &e).await
+            }
+        }
+    }
+    
+    // Time left until the task's deadline, capped at `max_task_timeout`
+    fn time_limit(&self, task: &ReasoningTask) -> Option<Duration> {
+        let left = task.deadline.checked_sub(unix_now()).filter(|left| *left > 0)?;
+        
+        Some(Duration::from_secs(left).min(self.max_task_timeout))
     }
     
     async fn fetch_pending_tasks(&self) -> Result<Vec<ReasoningTask>> {
-        // Simulasi - dalam implementasi nyata, query dari smart contract
-        Ok(vec![])
+        self.registry
+            .fetch_open_tasks(&self.cursor, &self.jobs, self.start_block)
+            .await
     }
     
-    async fn submit_result(&self, task_id: u64, _result: ReasoningResult) -> Result<()> {
-        info!("📤 Submitting result for task {}", task_id);
-        // Submit to smart contract
+    async fn claim_task(&self, task_id: u64) -> Result<TxHash, RegistryError> {
+        let receipt = self.registry.assign_task(task_id).await?;
+        info!("🔒 Task {} assigned to {:?} (tx {:?})", task_id, self.node_address, receipt.transaction_hash);
+        Ok(receipt.transaction_hash)
+    }
+    
+    // The job is marked submitted as soon as the transaction is sent, so a
+    // restart waits for that transaction instead of sending another
+    async fn submit_result(&self, task_id: u64, result_hash: Hash32) -> Result<()> {
+        for attempt in 1..=MAX_SUBMIT_ATTEMPTS {
+            let tx_hash = match self.registry.send_task_result(task_id, result_hash).await {
+                Ok(tx_hash) => tx_hash,
+                Err(e) => return self.submit_failed(task_id, e).await,
+            };
+            self.jobs.mark_submitted(task_id, format!("{:?}", tx_hash)).await?;
+            
+            if self.await_confirmation(task_id, tx_hash).await? {
+                return Ok(());
+            }
+            warn!("🕳️  Result for task {} dropped (attempt {}/{})", task_id, attempt, MAX_SUBMIT_ATTEMPTS);
+        }
+        
+        Err(anyhow!("result for task {} dropped {} times", task_id, MAX_SUBMIT_ATTEMPTS))
+    }
+    
+    // Whether the job is settled; false if the transaction was dropped and
+    // the result should be sent again
+    async fn await_confirmation(&self, task_id: u64, tx_hash: TxHash) -> Result<bool> {
+        match self.registry.confirm(task_id, tx_hash).await {
+            Ok(receipt) => {
+                info!("💰 Result for task {} accepted (tx {:?})", task_id, receipt.transaction_hash);
+                self.confirmed(task_id).await?;
+                Ok(true)
+            }
+            Err(RegistryError::Dropped(_)) => {
+                self.jobs.mark_resubmittable(task_id).await?;
+                Ok(false)
+            }
+            Err(e) => {
+                self.submit_failed(task_id, e).await?;
+                Ok(true)
+            }
+        }
+    }
+    
+    async fn submit_failed(&self, task_id: u64, e: RegistryError) -> Result<()> {
+        match e {
+            // Only the assigned node can submit, so this is our own earlier result
+            RegistryError::AlreadyCompleted(_) => {
+                info!("💰 Result for task {} already on chain", task_id);
+                self.confirmed(task_id).await
+            }
+            RegistryError::DeadlinePassed(_) => {
+                warn!("⌛ {}", e);
+                self.fail(task_id, Stage::Submit, &e).await
+            }
+            RegistryError::NotAssigned(_) | RegistryError::NodeInactive(_) | RegistryError::Reverted { .. } => {
+                warn!("❌ Result for task {} rejected: {}", task_id, e);
+                self.fail(task_id, Stage::Submit, &e).await
+            }
+            // RPC trouble; the job keeps its state and is resumed on restart
+            e => Err(e.into()),
+        }
+    }
+    
+    // Leaves an unclaimed task to be admitted again after `poll_interval`
+    async fn defer(&self, task: ReasoningTask, error: impl ToString) -> Result<()> {
+        self.jobs.defer(task.task_id, error).await?;
+        
+        let queue = self.queue.clone();
+        let delay = self.poll_interval;
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            queue.push(task);
+        });
         Ok(())
     }
+    
+    async fn fail(&self, task_id: u64, stage: Stage, error: impl ToString) -> Result<()> {
+        if let Some(job) = self.jobs.get(task_id) {
+            self.metrics.failure(self.task_label(&job.task.task_type), stage);
+        }
+        self.jobs.mark_failed(task_id, error).await
+    }
+    
+    async fn confirmed(&self, task_id: u64) -> Result<()> {
+        if let Some(job) = self.jobs.get(task_id) {
+            self.metrics.completed.inc(&[self.task_label(&job.task.task_type)]);
+        }
+        self.jobs.mark_confirmed(task_id).await
+    }
+    
+    fn task_label<'a>(&self, task_type: &'a str) -> &'a str {
+        task_type_label(self.task_processor.modules(), task_type)
+    }
 }
@@ -0,0 +1,245 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+use tokio::time::{sleep, Duration};
+use tracing::{debug, info, warn};
+
+use crate::cursor::BlockCursor;
+use crate::jobs::JobStore;
+use crate::registry::{saturating_u64, RegistryClient};
+use bindings::{CognitiveRegistry, CognitiveRegistryEvents};
+use shared::telemetry::Telemetry;
+use shared::types::ReasoningTask;
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+// Tasks waiting to be claimed, highest reward first and, among equal
+// rewards, the one expiring soonest. Each id is queued at most once, and a
+// task that somebody else claims in the meantime is dropped before we touch it.
+#[derive(Default)]
+pub struct WorkQueue {
+    inner: Mutex<QueueState>,
+    notify: Notify,
+}
+
+type Priority = (Reverse<u64>, u64, u64);
+
+#[derive(Default)]
+struct QueueState {
+    tasks: BTreeMap<Priority, ReasoningTask>,
+    queued: HashMap<u64, Priority>,
+}
+
+fn priority(task: &ReasoningTask) -> Priority {
+    (Reverse(task.reward), task.deadline, task.task_id)
+}
+
+impl WorkQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn push(&self, task: ReasoningTask) -> bool {
+        let mut state = self.inner.lock().unwrap();
+        if state.queued.contains_key(&task.task_id) {
+            return false;
+        }
+        
+        let key = priority(&task);
+        state.queued.insert(task.task_id, key);
+        state.tasks.insert(key, task);
+        drop(state);
+        
+        self.notify.notify_one();
+        true
+    }
+    
+    pub fn remove(&self, task_id: u64) -> bool {
+        let mut state = self.inner.lock().unwrap();
+        let Some(key) = state.queued.remove(&task_id) else {
+            return false;
+        };
+        
+        state.tasks.remove(&key);
+        true
+    }
+    
+    pub fn try_pop(&self) -> Option<ReasoningTask> {
+        let mut state = self.inner.lock().unwrap();
+        let (_, task) = state.tasks.pop_first()?;
+        state.queued.remove(&task.task_id);
+        Some(task)
+    }
+    
+    pub async fn pop(&self) -> ReasoningTask {
+        loop {
+            // Register interest before checking so a concurrent push is not missed
+            let notified = self.notify.notified();
+            
+            if let Some(task) = self.try_pop() {
+                return task;
+            }
+            
+            notified.await;
+        }
+    }
+    
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().tasks.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// Follows TaskCreated/TaskAssigned over a WebSocket subscription. After
+// every (re)connect the blocks missed since the cursor are backfilled with
+// eth_getLogs before live events are consumed.
+pub struct EventSubscriber<M> {
+    ws_url: String,
+    registry: Arc<RegistryClient<M>>,
+    cursor: Arc<BlockCursor>,
+    jobs: Arc<JobStore>,
+    start_block: u64,
+    queue: Arc<WorkQueue>,
+    telemetry: Option<Arc<Telemetry>>,
+}
+
+impl<M: Middleware + 'static> EventSubscriber<M> {
+    pub fn new(
+        ws_url: String,
+        registry: Arc<RegistryClient<M>>,
+        cursor: Arc<BlockCursor>,
+        jobs: Arc<JobStore>,
+        start_block: u64,
+        queue: Arc<WorkQueue>,
+    ) -> Self {
+        Self {
+            ws_url,
+            registry,
+            cursor,
+            jobs,
+            start_block,
+            queue,
+            telemetry: None,
+        }
+    }
+    
+    // Reports the subscription's progress, so readiness reflects a dropped
+    // or stalled WebSocket even when no registry events arrive
+    pub fn with_telemetry(mut self, telemetry: Arc<Telemetry>) -> Self {
+        self.telemetry = Some(telemetry);
+        self
+    }
+    
+    pub async fn run(&self) {
+        let mut backoff = INITIAL_BACKOFF;
+        
+        loop {
+            match Provider::<Ws>::connect(self.ws_url.as_str()).await {
+                Ok(ws) => {
+                    info!("🔌 WebSocket connected, subscribing to registry events");
+                    backoff = INITIAL_BACKOFF;
+                    
+                    if let Err(e) = self.follow(&ws).await {
+                        warn!("WebSocket subscription error: {}", e);
+                    } else {
+                        warn!("WebSocket subscription closed");
+                    }
+                }
+                Err(e) => {
+                    warn!("WebSocket connect failed: {}", e);
+                }
+            }
+            
+            info!("⏳ Reconnecting in {}s", backoff.as_secs());
+            sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+    
+    async fn follow(&self, ws: &Provider<Ws>) -> Result<()> {
+        let contract = CognitiveRegistry::new(self.registry.address(), Arc::new(ws.clone()));
+        let events = contract.events();
+        
+        // Subscribe before backfilling so nothing falls between the two
+        let mut stream = events.subscribe_with_meta().await?;
+        let mut blocks = ws.subscribe_blocks().await?;
+        
+        self.backfill().await?;
+        if let Some(last) = self.cursor.load()? {
+            self.progress(last);
+        }
+        
+        loop {
+            tokio::select! {
+                item = stream.next() => {
+                    let Some(item) = item else { break };
+                    let (event, meta) = item?;
+                    
+                    match event {
+                        CognitiveRegistryEvents::TaskCreatedFilter(created) => {
+                            self.on_task_created(saturating_u64(created.task_id)).await?;
+                        }
+                        CognitiveRegistryEvents::TaskAssignedFilter(assigned) => {
+                            let task_id = saturating_u64(assigned.task_id);
+                            if self.queue.remove(task_id) {
+                                debug!("Task {} was claimed by {:?}, dropped from queue", task_id, assigned.node);
+                            }
+                        }
+                        _ => continue,
+                    }
+                    
+                    // Everything before this block has been seen
+                    let seen = meta.block_number.as_u64().saturating_sub(1);
+                    if self.cursor.load()?.is_none_or(|last| seen > last) {
+                        self.cursor.save(seen)?;
+                    }
+                }
+                block = blocks.next() => {
+                    let Some(block) = block else { break };
+                    if let Some(number) = block.number {
+                        self.progress(number.as_u64().saturating_sub(1));
+                    }
+                }
+            }
+        }
+        
+        Ok(())
+    }
+    
+    fn progress(&self, block: u64) {
+        if let Some(telemetry) = &self.telemetry {
+            telemetry.processed(block);
+        }
+    }
+    
+    async fn backfill(&self) -> Result<()> {
+        let tasks = self
+            .registry
+            .fetch_open_tasks(&self.cursor, &self.jobs, self.start_block)
+            .await?;
+        
+        for task in tasks {
+            self.queue.push(task);
+        }
+        
+        Ok(())
+    }
+    
+    // Recorded as a job before the cursor moves past the event. Failing to
+    // do so ends the subscription, and the reconnect backfills the task.
+    async fn on_task_created(&self, task_id: u64) -> Result<()> {
+        let task = self.registry.get_task(task_id).await?.into_reasoning_task();
+        info!("🆕 Task {} created (type: {})", task.task_id, task.task_type);
+        
+        self.jobs.discover(&task).await?;
+        self.queue.push(task);
+        Ok(())
+    }
+}
@@ -0,0 +1,290 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::task;
+
+use crate::cursor::write_atomic;
+use shared::types::{ReasoningResult, ReasoningTask};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    // Seen on chain, waiting for a worker
+    Discovered,
+    // Taken up by a worker, which is deciding whether to claim it
+    Admitting,
+    // Not claimed because of an error unrelated to the task, such as an RPC
+    // or data store outage; admitted again later
+    Deferred,
+    Claimed,
+    // Result computed but not yet sent
+    Computed,
+    // Result transaction sent, waiting for confirmation
+    Submitted,
+    Confirmed,
+    Failed,
+}
+
+impl JobState {
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Confirmed | Self::Failed)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub task: ReasoningTask,
+    pub state: JobState,
+    #[serde(default)]
+    pub result: Option<ReasoningResult>,
+    #[serde(default)]
+    pub result_hash: Option<String>,
+    #[serde(default)]
+    pub claim_tx: Option<String>,
+    #[serde(default)]
+    pub submit_tx: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+    pub updated_at: u64,
+}
+
+impl Job {
+    fn new(task: ReasoningTask) -> Self {
+        Self {
+            task,
+            state: JobState::Discovered,
+            result: None,
+            result_hash: None,
+            claim_tx: None,
+            submit_tx: None,
+            error: None,
+            updated_at: unix_now(),
+        }
+    }
+}
+
+// Lifecycle of every task the engine has seen, so a restart picks up where
+// it left off instead of redoing work or submitting a result twice. Every
+// transition is written to disk before it returns. Finished jobs are dropped
+// once their task's deadline has passed and it cannot come back.
+pub struct JobStore {
+    path: Option<PathBuf>,
+    jobs: Mutex<BTreeMap<u64, Job>>,
+    // Held while a snapshot is taken and written, so writes land in order
+    writer: tokio::sync::Mutex<()>,
+}
+
+impl JobStore {
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        
+        let jobs = if path.exists() {
+            serde_json::from_str(&std::fs::read_to_string(&path)?)?
+        } else {
+            BTreeMap::new()
+        };
+        
+        Ok(Self {
+            path: Some(path),
+            jobs: Mutex::new(jobs),
+            writer: tokio::sync::Mutex::new(()),
+        })
+    }
+    
+    // Nothing survives a restart; for tests
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            jobs: Mutex::new(BTreeMap::new()),
+            writer: tokio::sync::Mutex::new(()),
+        }
+    }
+    
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+    
+    pub fn get(&self, task_id: u64) -> Option<Job> {
+        self.jobs.lock().unwrap().get(&task_id).cloned()
+    }
+    
+    // Records a newly seen task. Returns false, changing nothing, if it is
+    // already known.
+    pub async fn discover(&self, task: &ReasoningTask) -> Result<bool> {
+        {
+            let mut jobs = self.jobs.lock().unwrap();
+            if jobs.contains_key(&task.task_id) {
+                return Ok(false);
+            }
+            jobs.insert(task.task_id, Job::new(task.clone()));
+        }
+        
+        self.save().await?;
+        Ok(true)
+    }
+    
+    // Hands a discovered or deferred task (recording it first if it is new)
+    // to one worker. Returns the state it was taken up from, or None if it is
+    // already being handled or done.
+    pub async fn take_up(&self, task: &ReasoningTask) -> Result<Option<JobState>> {
+        let from = {
+            let mut jobs = self.jobs.lock().unwrap();
+            let job = jobs.entry(task.task_id).or_insert_with(|| Job::new(task.clone()));
+            
+            let from = job.state;
+            if !matches!(from, JobState::Discovered | JobState::Deferred) {
+                return Ok(None);
+            }
+            
+            job.state = JobState::Admitting;
+            job.updated_at = unix_now();
+            from
+        };
+        
+        self.save().await?;
+        Ok(Some(from))
+    }
+    
+    pub async fn defer(&self, task_id: u64, error: impl ToString) -> Result<()> {
+        let error = error.to_string();
+        self.update(task_id, &[JobState::Admitting], |job| {
+            job.state = JobState::Deferred;
+            job.error = Some(error);
+        })
+        .await
+    }
+    
+    // Keeps the payload once it has been resolved from the data store
+    pub async fn set_task(&self, task: &ReasoningTask) -> Result<()> {
+        self.update(task.task_id, &[JobState::Admitting], |job| job.task = task.clone())
+            .await
+    }
+    
+    pub async fn mark_claimed(&self, task_id: u64, claim_tx: String) -> Result<()> {
+        self.update(task_id, &[JobState::Admitting], |job| {
+            job.state = JobState::Claimed;
+            job.claim_tx = Some(claim_tx);
+        })
+        .await
+    }
+    
+    // The claim was mined but the engine stopped before recording it, so the
+    // transaction is not known
+    pub async fn recover_claim(&self, task_id: u64) -> Result<()> {
+        self.update(
+            task_id,
+            &[JobState::Discovered, JobState::Admitting, JobState::Deferred],
+            |job| job.state = JobState::Claimed,
+        )
+        .await
+    }
+    
+    pub async fn mark_computed(&self, task_id: u64, result: ReasoningResult, result_hash: String) -> Result<()> {
+        self.update(task_id, &[JobState::Claimed], |job| {
+            job.state = JobState::Computed;
+            job.result = Some(result);
+            job.result_hash = Some(result_hash);
+        })
+        .await
+    }
+    
+    pub async fn mark_submitted(&self, task_id: u64, submit_tx: String) -> Result<()> {
+        self.update(task_id, &[JobState::Computed], |job| {
+            job.state = JobState::Submitted;
+            job.submit_tx = Some(submit_tx);
+        })
+        .await
+    }
+    
+    // The submit transaction was dropped and the task is still open; the
+    // stored result can be sent again
+    pub async fn mark_resubmittable(&self, task_id: u64) -> Result<()> {
+        self.update(task_id, &[JobState::Submitted], |job| {
+            job.state = JobState::Computed;
+            job.submit_tx = None;
+        })
+        .await
+    }
+    
+    pub async fn mark_confirmed(&self, task_id: u64) -> Result<()> {
+        self.update(task_id, &[JobState::Computed, JobState::Submitted], |job| {
+            job.state = JobState::Confirmed;
+            job.error = None;
+        })
+        .await
+    }
+    
+    pub async fn mark_failed(&self, task_id: u64, error: impl ToString) -> Result<()> {
+        let error = error.to_string();
+        self.update(
+            task_id,
+            &[JobState::Admitting, JobState::Claimed, JobState::Computed, JobState::Submitted],
+            |job| {
+                job.state = JobState::Failed;
+                job.error = Some(error);
+            },
+        )
+        .await
+    }
+    
+    // Jobs that were interrupted before they finished, oldest task first
+    pub fn unfinished(&self) -> Vec<Job> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|job| !job.state.is_terminal())
+            .cloned()
+            .collect()
+    }
+    
+    pub fn jobs(&self) -> Vec<Job> {
+        self.jobs.lock().unwrap().values().cloned().collect()
+    }
+    
+    async fn update(&self, task_id: u64, from: &[JobState], apply: impl FnOnce(&mut Job)) -> Result<()> {
+        {
+            let mut jobs = self.jobs.lock().unwrap();
+            let job = jobs.get_mut(&task_id).ok_or_else(|| anyhow!("unknown job {}", task_id))?;
+            
+            if !from.contains(&job.state) {
+                return Err(anyhow!("job {} is {:?}, expected one of {:?}", task_id, job.state, from));
+            }
+            
+            apply(job);
+            job.updated_at = unix_now();
+        }
+        
+        self.save().await
+    }
+    
+    // Writes a snapshot taken after the caller's change, dropping finished
+    // jobs past their deadline. The file is written on a blocking thread,
+    // without holding the lock the workers need.
+    async fn save(&self) -> Result<()> {
+        let _writer = self.writer.lock().await;
+        let (path, bytes) = {
+            let mut jobs = self.jobs.lock().unwrap();
+            let now = unix_now();
+            jobs.retain(|_, job| !job.state.is_terminal() || job.task.deadline >= now);
+            
+            let Some(path) = self.path.clone() else {
+                return Ok(());
+            };
+            (path, serde_json::to_vec(&*jobs)?)
+        };
+        
+        task::spawn_blocking(move || write_atomic(&path, &bytes)).await??;
+        Ok(())
+    }
+}
+
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
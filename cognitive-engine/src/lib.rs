@@ -0,0 +1,11 @@
+pub mod cursor;
+pub mod datastore;
+pub mod engine;
+pub mod ingest;
+pub mod jobs;
+pub mod metering;
+pub mod metrics;
+pub mod reasoning;
+pub mod registry;
+pub mod signer;
+pub mod tasks;
@@ -1,20 +1,20 @@
 use anyhow::Result;
+use clap::Parser;
+use std::sync::Arc;
 use tracing::info;
-use tracing_subscriber;
 
-mod engine;
-mod tasks;
-mod reasoning;
-
-use engine::CognitiveEngine;
+use cognitive_engine::engine::CognitiveEngine;
+use shared::config::{Cli, Role};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     tracing_subscriber::fmt::init();
     
+    let config = Cli::parse().load(Role::Engine)?;
+    
     info!("🧠 Starting PolyNeurons Cognitive Engine...");
     
-    let engine = CognitiveEngine::new().await?;
+    let engine = Arc::new(CognitiveEngine::new(&config).await?);
     
     info!("✅ Cognitive Engine initialized");
     info!("📡 Listening for reasoning tasks...");
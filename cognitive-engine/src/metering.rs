@@ -0,0 +1,48 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+// CPU time consumed by the calling thread so far
+#[cfg(unix)]
+pub fn thread_cpu_time() -> Duration {
+    let mut now = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    // SAFETY: `now` is a valid timespec for the call to write to
+    if unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut now) } != 0 {
+        return Duration::ZERO;
+    }
+    Duration::new(now.tv_sec as u64, now.tv_nsec as u32)
+}
+
+#[cfg(not(unix))]
+pub fn thread_cpu_time() -> Duration {
+    Duration::ZERO
+}
+
+// Adds up the CPU time spent inside each poll of `F`. Counting per poll
+// keeps the figure right when the future moves between worker threads and
+// leaves out time other tasks spend on the same thread while it waits.
+pub struct CpuTimed<F> {
+    inner: Pin<Box<F>>,
+    cpu_time: Duration,
+}
+
+pub fn cpu_timed<F: Future>(future: F) -> CpuTimed<F> {
+    CpuTimed {
+        inner: Box::pin(future),
+        cpu_time: Duration::ZERO,
+    }
+}
+
+impl<F: Future> Future for CpuTimed<F> {
+    type Output = (F::Output, Duration);
+    
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let started = thread_cpu_time();
+        let poll = self.inner.as_mut().poll(cx);
+        let spent = thread_cpu_time().saturating_sub(started);
+        self.cpu_time += spent;
+        
+        poll.map(|output| (output, self.cpu_time))
+    }
+}
@@ -0,0 +1,92 @@
+use shared::metrics::{Counter, Histogram, LATENCY_BUCKETS};
+use shared::telemetry::Telemetry;
+use std::sync::Arc;
+
+use crate::reasoning::ModuleRegistry;
+
+// Step at which the engine gave up on a task
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    // No module, not admitted or no payload; the task was never claimed
+    Admission,
+    Claim,
+    Compute,
+    Submit,
+}
+
+impl Stage {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Admission => "admission",
+            Self::Claim => "claim",
+            Self::Compute => "compute",
+            Self::Submit => "submit",
+        }
+    }
+}
+
+// `task_type` label of tasks no module is registered for. Task types come
+// from the chain, so anyone could otherwise add label values at will.
+pub const UNKNOWN_TASK_TYPE: &str = "unknown";
+
+// The label for `task_type`: the module it matched, or `UNKNOWN_TASK_TYPE`
+pub fn task_type_label<'a>(modules: &ModuleRegistry, task_type: &'a str) -> &'a str {
+    match modules.get(task_type) {
+        Some(_) => task_type,
+        None => UNKNOWN_TASK_TYPE,
+    }
+}
+
+// Per-module run time, shared by the engine and the validator plugin
+pub fn module_latency() -> Histogram {
+    Histogram::new(
+        "polyneurons_module_duration_seconds",
+        "Run time of reasoning modules",
+        &["task_type"],
+        LATENCY_BUCKETS,
+    )
+}
+
+pub struct EngineMetrics {
+    pub telemetry: Arc<Telemetry>,
+    pub discovered: Arc<Counter>,
+    pub claimed: Arc<Counter>,
+    pub completed: Arc<Counter>,
+    pub failed: Arc<Counter>,
+    pub module_latency: Arc<Histogram>,
+}
+
+impl EngineMetrics {
+    pub fn new(telemetry: Arc<Telemetry>) -> Self {
+        let registry = telemetry.registry();
+        
+        Self {
+            discovered: registry.register(Counter::new(
+                "polyneurons_tasks_discovered_total",
+                "Tasks seen for the first time",
+                &["task_type"],
+            )),
+            claimed: registry.register(Counter::new(
+                "polyneurons_tasks_claimed_total",
+                "Tasks assigned to this node",
+                &["task_type"],
+            )),
+            completed: registry.register(Counter::new(
+                "polyneurons_tasks_completed_total",
+                "Tasks whose result was accepted on chain",
+                &["task_type"],
+            )),
+            failed: registry.register(Counter::new(
+                "polyneurons_tasks_failed_total",
+                "Tasks given up on, by the step that failed",
+                &["task_type", "stage"],
+            )),
+            module_latency: registry.register(module_latency()),
+            telemetry,
+        }
+    }
+    
+    pub fn failure(&self, task_type: &str, stage: Stage) {
+        self.failed.inc(&[task_type, stage.as_str()]);
+    }
+}
@@ -1,8 +1,14 @@
 use anyhow::Result;
+use async_trait::async_trait;
 use serde_json::Value;
-use shared::types::ReasoningResult;
+use shared::types::{
+    AnomalyDetectionInput, AnomalyDetectionOutput, ReasoningResult, Severity, TaskPayload, TransactionSample,
+};
 use tracing::info;
 
+use super::ReasoningModule;
+
+#[derive(Default)]
 pub struct AnomalyDetector;
 
 impl AnomalyDetector {
@@ -13,14 +19,22 @@ impl AnomalyDetector {
     pub async fn detect(&self, data: &Value) -> Result<ReasoningResult> {
         info!("🔍 Running anomaly detection...");
         
-        let transactions = data["transactions"].as_array()
-            .ok_or_else(|| anyhow::anyhow!("Invalid transaction data"))?;
+        let input = AnomalyDetectionInput::from_value(data)?;
+        let (output, confidence) = self.analyze(&input);
         
+        Ok(ReasoningResult {
+            prediction: serde_json::to_value(&output)?,
+            confidence_score: confidence,
+            ..ReasoningResult::default()
+        })
+    }
+    
+    pub fn analyze(&self, input: &AnomalyDetectionInput) -> (AnomalyDetectionOutput, f64) {
         let mut anomalies = Vec::new();
         let mut anomaly_scores = Vec::new();
         
-        for (idx, tx) in transactions.iter().enumerate() {
-            let score = self.calculate_anomaly_score(tx)?;
+        for (idx, tx) in input.transactions.iter().enumerate() {
+            let score = self.calculate_anomaly_score(tx);
             
             if score > 0.7 {
                 anomalies.push(idx);
@@ -34,25 +48,59 @@ impl AnomalyDetector {
             0.95
         };
         
-        Ok(ReasoningResult {
-            prediction: serde_json::json!({
-                "anomalies_detected": anomalies.len(),
-                "anomaly_indices": anomalies,
-                "severity": if anomalies.len() > 5 { "high" } else { "low" }
-            }),
-            confidence_score: avg_confidence,
-            computation_time_ms: 200,
-        })
+        let output = AnomalyDetectionOutput {
+            anomalies_detected: anomalies.len(),
+            severity: if anomalies.len() > 5 { Severity::High } else { Severity::Low },
+            anomaly_indices: anomalies,
+        };
+        
+        (output, avg_confidence)
     }
     
-    fn calculate_anomaly_score(&self, tx: &Value) -> Result<f64> {
-        let value = tx["value"].as_f64().unwrap_or(0.0);
-        let gas = tx["gas"].as_f64().unwrap_or(0.0);
-        
+    fn calculate_anomaly_score(&self, tx: &TransactionSample) -> f64 {
         // Simple heuristic: unusual value or gas
-        let value_score = if value > 1000.0 { 0.8 } else { 0.2 };
-        let gas_score = if gas > 500000.0 { 0.7 } else { 0.1 };
+        let value_score = if tx.value > 1000.0 { 0.8 } else { 0.2 };
+        let gas_score = if tx.gas > 500000.0 { 0.7 } else { 0.1 };
         
-        Ok((value_score + gas_score) / 2.0)
+        (value_score + gas_score) / 2.0
+    }
+}
+
+#[async_trait]
+impl ReasoningModule for AnomalyDetector {
+    fn task_type(&self) -> &str {
+        "anomaly_detection"
+    }
+    
+    fn input_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "required": ["transactions"],
+            "properties": {
+                "transactions": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "value": { "type": "number" },
+                            "gas": { "type": "number" }
+                        }
+                    }
+                }
+            }
+        })
+    }
+    
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+    
+    async fn run(&self, data: &Value) -> Result<ReasoningResult> {
+        self.detect(data).await
+    }
+    
+    // Value and gas are checked for every transaction
+    fn work_units(&self, data: &Value) -> u64 {
+        AnomalyDetectionInput::from_value(data).map_or(0, |input| 2 * input.transactions.len() as u64)
     }
 }
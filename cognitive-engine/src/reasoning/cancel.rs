@@ -0,0 +1,57 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::Notify;
+
+// Asks a module run to stop. `TaskProcessor` runs each module on a blocking
+// thread of its own and raises the flag once the run is out of time. Runs
+// that await are dropped at that point; loops that compute without yielding
+// check `requested` and stop early, since their output is thrown away.
+
+thread_local! {
+    static CURRENT: RefCell<Option<CancelFlag>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone, Default)]
+pub struct CancelFlag {
+    inner: Arc<(AtomicBool, Notify)>,
+}
+
+impl CancelFlag {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn cancel(&self) {
+        self.inner.0.store(true, Ordering::SeqCst);
+        self.inner.1.notify_waiters();
+    }
+    
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.0.load(Ordering::SeqCst)
+    }
+    
+    pub async fn cancelled(&self) {
+        loop {
+            // Register interest before checking so a concurrent cancel is not missed
+            let notified = self.inner.1.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+    
+    // Runs `f` with this flag as the one `requested` reads on this thread
+    pub fn scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        let previous = CURRENT.with(|current| current.replace(Some(self.clone())));
+        let output = f();
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+        output
+    }
+}
+
+// Whether the run on this thread has been asked to stop
+pub fn requested() -> bool {
+    CURRENT.with(|current| current.borrow().as_ref().is_some_and(CancelFlag::is_cancelled))
+}
@@ -0,0 +1,205 @@
+use shared::types::ForecastModel;
+
+use super::cancel;
+
+// Number of prices the moving averages span
+pub const WINDOW: usize = 7;
+
+// How many of the most recent origins `backtest` replays
+pub const BACKTEST_ORIGINS: usize = 50;
+
+// Candidate smoothing factors Holt's method is fitted over
+const GRID: [f64; 9] = [0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9];
+
+// Point forecast of one model fitted on a price history
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fit {
+    // Expected price 1..=horizon steps after the last one
+    pub path: Vec<f64>,
+    // Change per step the model currently estimates
+    pub slope: f64,
+}
+
+// Fits `model` on `prices`, which must hold at least `model.min_history()`
+// values
+pub fn fit(model: ForecastModel, prices: &[f64], horizon: usize) -> Fit {
+    match model {
+        ForecastModel::Sma => sma(prices, horizon),
+        ForecastModel::Ema => ema(prices, horizon),
+        ForecastModel::Holt => holt(prices, horizon),
+        ForecastModel::LinearRegression => log_linear(prices, horizon),
+        ForecastModel::Arima => arima(prices, horizon),
+    }
+}
+
+// Refits the model on every prefix of the history it can and forecasts
+// from there. `errors[k]` holds actual minus forecast price k + 1 steps
+// ahead, for every origin with an actual price that far out.
+pub fn backtest(model: ForecastModel, prices: &[f64], horizon: usize) -> Vec<Vec<f64>> {
+    let mut errors = vec![Vec::new(); horizon];
+    let first = model.min_history().max(prices.len().saturating_sub(BACKTEST_ORIGINS));
+    
+    for origin in first..prices.len() {
+        if cancel::requested() {
+            break;
+        }
+        
+        let fit = fit(model, &prices[..origin], horizon.min(prices.len() - origin));
+        for (step, forecast) in fit.path.iter().enumerate() {
+            errors[step].push(prices[origin + step] - forecast);
+        }
+    }
+    
+    errors
+}
+
+// Operations one fit of `model` on `len` prices takes: one per price each
+// pass visits (per pair of smoothing factors for Holt) and one per step
+// forecast. Counted rather than timed, so every node gets the same figure.
+pub fn fit_work(model: ForecastModel, len: usize, horizon: usize) -> u64 {
+    let len = len as u64;
+    let passes = match model {
+        ForecastModel::Sma => return 2 * len.min(WINDOW as u64) + horizon as u64,
+        ForecastModel::Ema => 1,
+        ForecastModel::Holt => (GRID.len() * GRID.len()) as u64,
+        ForecastModel::LinearRegression => 2,
+        ForecastModel::Arima => 3,
+    };
+    
+    passes * len + horizon as u64
+}
+
+// Operations `backtest` takes: a fit at every origin it replays
+pub fn backtest_work(model: ForecastModel, len: usize, horizon: usize) -> u64 {
+    let first = model.min_history().max(len.saturating_sub(BACKTEST_ORIGINS));
+    
+    (first..len)
+        .map(|origin| fit_work(model, origin, horizon.min(len - origin)))
+        .sum()
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn sma(prices: &[f64], horizon: usize) -> Fit {
+    let average = |prices: &[f64]| mean(&prices[prices.len().saturating_sub(WINDOW)..]);
+    
+    let level = average(prices);
+    let slope = match prices.len() {
+        1 => 0.0,
+        n => level - average(&prices[..n - 1]),
+    };
+    
+    Fit {
+        path: vec![level; horizon],
+        slope,
+    }
+}
+
+fn ema(prices: &[f64], horizon: usize) -> Fit {
+    let alpha = 2.0 / (WINDOW as f64 + 1.0);
+    
+    let mut level = prices[0];
+    let mut slope = 0.0;
+    for price in &prices[1..] {
+        let next = alpha * price + (1.0 - alpha) * level;
+        slope = next - level;
+        level = next;
+    }
+    
+    Fit {
+        path: vec![level; horizon],
+        slope,
+    }
+}
+
+// Level and trend after smoothing all of `prices`, and the sum of squared
+// one-step errors on the way
+fn holt_smooth(prices: &[f64], alpha: f64, beta: f64) -> (f64, f64, f64) {
+    let mut level = prices[0];
+    let mut trend = prices[1] - prices[0];
+    let mut sse = 0.0;
+    
+    for price in &prices[1..] {
+        let forecast = level + trend;
+        sse += (price - forecast).powi(2);
+        
+        let next = alpha * price + (1.0 - alpha) * forecast;
+        trend = beta * (next - level) + (1.0 - beta) * trend;
+        level = next;
+    }
+    
+    (level, trend, sse)
+}
+
+fn holt(prices: &[f64], horizon: usize) -> Fit {
+    // Smoothing factors with the smallest in-sample error; the first
+    // candidate wins ties so every node picks the same pair
+    let mut best = (f64::INFINITY, 0.0, 0.0);
+    for alpha in GRID {
+        for beta in GRID {
+            let (level, trend, sse) = holt_smooth(prices, alpha, beta);
+            if sse < best.0 {
+                best = (sse, level, trend);
+            }
+        }
+    }
+    let (_, level, trend) = best;
+    
+    Fit {
+        path: (1..=horizon).map(|step| level + step as f64 * trend).collect(),
+        slope: trend,
+    }
+}
+
+fn log_linear(prices: &[f64], horizon: usize) -> Fit {
+    let logs: Vec<f64> = prices.iter().map(|price| price.ln()).collect();
+    let n = logs.len() as f64;
+    let x_mean = (n - 1.0) / 2.0;
+    let y_mean = mean(&logs);
+    
+    let (mut sxy, mut sxx) = (0.0, 0.0);
+    for (x, y) in logs.iter().enumerate() {
+        sxy += (x as f64 - x_mean) * (y - y_mean);
+        sxx += (x as f64 - x_mean).powi(2);
+    }
+    let growth = sxy / sxx;
+    let line = |x: f64| (y_mean + growth * (x - x_mean)).exp();
+    
+    let last = n - 1.0;
+    Fit {
+        path: (1..=horizon).map(|step| line(last + step as f64)).collect(),
+        slope: line(last) * growth.exp_m1(),
+    }
+}
+
+fn arima(prices: &[f64], horizon: usize) -> Fit {
+    let changes: Vec<f64> = prices.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    let drift = mean(&changes);
+    
+    // Lag-one autocorrelation of the changes, kept away from a unit root
+    let deviations: Vec<f64> = changes.iter().map(|change| change - drift).collect();
+    let variance: f64 = deviations.iter().map(|d| d * d).sum();
+    let phi = if deviations.len() < 3 || variance == 0.0 {
+        0.0
+    } else {
+        let covariance: f64 = deviations.windows(2).map(|pair| pair[0] * pair[1]).sum();
+        (covariance / variance).clamp(-0.95, 0.95)
+    };
+    
+    let mut price = prices[prices.len() - 1];
+    let mut deviation = deviations[deviations.len() - 1];
+    let path: Vec<f64> = (0..horizon)
+        .map(|_| {
+            deviation *= phi;
+            price += drift + deviation;
+            price
+        })
+        .collect();
+    
+    Fit {
+        slope: (path[horizon - 1] - prices[prices.len() - 1]) / horizon as f64,
+        path,
+    }
+}
@@ -0,0 +1,192 @@
+use shared::types::Candle;
+
+use super::cancel;
+
+// Every function returns one value per input price, `None` until its window
+// has filled. Rolling windows are summed afresh at each price instead of
+// adding one value and dropping another, so no rounding error builds up
+// along a long series.
+
+pub type Line = Vec<Option<f64>>;
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+// Standard deviation around `mean`, divided by `values.len() - ddof`
+fn std_dev(values: &[f64], mean: f64, ddof: usize) -> f64 {
+    (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - ddof) as f64).sqrt()
+}
+
+// `f` over each full window of `period` values, aligned to its last value
+fn rolling(values: &[f64], period: usize, f: impl Fn(&[f64]) -> f64) -> Line {
+    let mut line = vec![None; values.len()];
+    for end in period..=values.len() {
+        if cancel::requested() {
+            break;
+        }
+        line[end - 1] = Some(f(&values[end - period..end]));
+    }
+    line
+}
+
+// Smooths `values` from index `start` on: the first `period` values are
+// averaged, then each step moves `alpha` of the way to the next value
+fn smooth(values: &[f64], start: usize, period: usize, alpha: f64) -> Line {
+    let mut line = vec![None; values.len()];
+    let seeded = start + period;
+    if seeded > values.len() {
+        return line;
+    }
+    
+    let mut current = mean(&values[start..seeded]);
+    line[seeded - 1] = Some(current);
+    for idx in seeded..values.len() {
+        current += alpha * (values[idx] - current);
+        line[idx] = Some(current);
+    }
+    line
+}
+
+// Exponential moving average seeded with the simple one
+pub fn ema(values: &[f64], period: usize) -> Line {
+    smooth(values, 0, period, 2.0 / (period as f64 + 1.0))
+}
+
+pub fn rsi(closes: &[f64], period: usize) -> Line {
+    let mut gains = vec![0.0; closes.len()];
+    let mut losses = vec![0.0; closes.len()];
+    for idx in 1..closes.len() {
+        let change = closes[idx] - closes[idx - 1];
+        gains[idx] = change.max(0.0);
+        losses[idx] = (-change).max(0.0);
+    }
+    
+    // Wilder's smoothing, starting after the first price which has no change
+    let alpha = 1.0 / period as f64;
+    let gains = smooth(&gains, 1, period, alpha);
+    let losses = smooth(&losses, 1, period, alpha);
+    
+    gains
+        .iter()
+        .zip(&losses)
+        .map(|(gain, loss)| {
+            let (gain, loss) = ((*gain)?, (*loss)?);
+            Some(match (gain == 0.0, loss == 0.0) {
+                // No movement at all
+                (true, true) => 50.0,
+                (false, true) => 100.0,
+                _ => 100.0 - 100.0 / (1.0 + gain / loss),
+            })
+        })
+        .collect()
+}
+
+pub struct Macd {
+    pub macd: Line,
+    pub signal: Line,
+    pub histogram: Line,
+}
+
+pub fn macd(closes: &[f64], fast: usize, slow: usize, signal: usize) -> Macd {
+    let fast = ema(closes, fast);
+    let slow_line = ema(closes, slow);
+    
+    let macd: Line = fast.iter().zip(&slow_line).map(|(f, s)| Some((*f)? - (*s)?)).collect();
+    
+    // The signal line smooths the MACD line from where it starts
+    let start = slow - 1;
+    let values: Vec<f64> = macd.iter().map(|value| value.unwrap_or_default()).collect();
+    let signal = smooth(&values, start, signal, 2.0 / (signal as f64 + 1.0));
+    
+    let histogram = macd.iter().zip(&signal).map(|(m, s)| Some((*m)? - (*s)?)).collect();
+    Macd {
+        macd,
+        signal,
+        histogram,
+    }
+}
+
+pub struct Bands {
+    pub middle: Line,
+    pub upper: Line,
+    pub lower: Line,
+}
+
+pub fn bollinger(closes: &[f64], period: usize, width: f64) -> Bands {
+    let middle = rolling(closes, period, mean);
+    let deviation = rolling(closes, period, |window| std_dev(window, mean(window), 0));
+    
+    let band = |sign: f64| -> Line {
+        middle
+            .iter()
+            .zip(&deviation)
+            .map(|(m, d)| Some((*m)? + sign * width * (*d)?))
+            .collect()
+    };
+    
+    Bands {
+        upper: band(1.0),
+        lower: band(-1.0),
+        middle,
+    }
+}
+
+pub fn atr(candles: &[Candle], period: usize) -> Line {
+    let ranges: Vec<f64> = candles
+        .iter()
+        .enumerate()
+        .map(|(idx, candle)| {
+            let range = candle.high - candle.low;
+            match idx.checked_sub(1).map(|previous| candles[previous].close) {
+                Some(close) => range.max((candle.high - close).abs()).max((candle.low - close).abs()),
+                None => range,
+            }
+        })
+        .collect();
+    
+    smooth(&ranges, 0, period, 1.0 / period as f64)
+}
+
+// `None` for windows that traded no volume. Without a period every window
+// starts at the first candle, so the sums only ever grow and are kept running.
+pub fn vwap(candles: &[Candle], period: Option<usize>) -> Line {
+    let typical = |candle: &Candle| (candle.high + candle.low + candle.close) / 3.0;
+    let vwap = |volume: f64, traded: f64| (volume > 0.0).then(|| traded / volume);
+    
+    match period {
+        Some(period) => (0..candles.len())
+            .map(|end| {
+                if end + 1 < period {
+                    return None;
+                }
+                let window = &candles[end + 1 - period..=end];
+                let volume: f64 = window.iter().map(|candle| candle.volume).sum();
+                let traded: f64 = window.iter().map(|candle| typical(candle) * candle.volume).sum();
+                vwap(volume, traded)
+            })
+            .collect(),
+        None => {
+            let (mut volume, mut traded) = (0.0, 0.0);
+            candles
+                .iter()
+                .map(|candle| {
+                    volume += candle.volume;
+                    traded += typical(candle) * candle.volume;
+                    vwap(volume, traded)
+                })
+                .collect()
+        }
+    }
+}
+
+// Sample standard deviation of the last `period` log returns
+pub fn volatility(closes: &[f64], period: usize, periods_per_year: Option<u64>) -> Line {
+    let returns: Vec<f64> = closes.windows(2).map(|pair| (pair[1] / pair[0]).ln()).collect();
+    let scale = periods_per_year.map_or(1.0, |periods| (periods as f64).sqrt());
+    
+    let mut line = vec![None];
+    line.extend(rolling(&returns, period, |window| std_dev(window, mean(window), 1) * scale));
+    line.truncate(closes.len());
+    line
+}
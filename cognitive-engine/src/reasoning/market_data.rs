@@ -0,0 +1,99 @@
+use shared::types::{AssetSeries, Candle, Gap, Interval};
+
+// Candles of one asset on an even grid, ready to be forecast
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreparedSeries {
+    pub interval: Interval,
+    pub candles: Vec<Candle>,
+    pub gaps: Vec<Gap>,
+}
+
+impl PreparedSeries {
+    pub fn closes(&self) -> Vec<f64> {
+        self.candles.iter().map(|candle| candle.close).collect()
+    }
+    
+    // Opening time of the candle `step` intervals after the last one
+    pub fn timestamp_after(&self, step: u64) -> u64 {
+        self.candles[self.candles.len() - 1].timestamp + step * self.interval.secs()
+    }
+}
+
+// Fills the gaps of `series`, then merges it into `resample` candles if
+// given. The payload has been validated, so the series is on its grid and
+// `resample` is a multiple of its interval.
+pub fn prepare(series: &AssetSeries, resample: Option<Interval>) -> PreparedSeries {
+    let (candles, gaps) = fill_gaps(&series.candles, series.interval);
+    
+    match resample {
+        Some(to) if to != series.interval => PreparedSeries {
+            interval: to,
+            candles: self::resample(&candles, to),
+            gaps,
+        },
+        _ => PreparedSeries {
+            interval: series.interval,
+            candles,
+            gaps,
+        },
+    }
+}
+
+// Inserts a flat, zero-volume candle at the previous close for every
+// missing step and reports the runs it filled
+pub fn fill_gaps(candles: &[Candle], interval: Interval) -> (Vec<Candle>, Vec<Gap>) {
+    let step = interval.secs();
+    let mut filled: Vec<Candle> = Vec::with_capacity(candles.len());
+    let mut gaps = Vec::new();
+    
+    for candle in candles {
+        if let Some(previous) = filled.last().cloned() {
+            let missing = (candle.timestamp - previous.timestamp) / step - 1;
+            if missing > 0 {
+                gaps.push(Gap {
+                    from: previous.timestamp + step,
+                    to: candle.timestamp - step,
+                    missing,
+                });
+            }
+            
+            for n in 1..=missing {
+                filled.push(Candle {
+                    timestamp: previous.timestamp + n * step,
+                    open: previous.close,
+                    high: previous.close,
+                    low: previous.close,
+                    close: previous.close,
+                    volume: 0.0,
+                });
+            }
+        }
+        filled.push(candle.clone());
+    }
+    
+    (filled, gaps)
+}
+
+// Merges consecutive candles into buckets of `to`, counted from the Unix
+// epoch. The first and last bucket may be partial.
+pub fn resample(candles: &[Candle], to: Interval) -> Vec<Candle> {
+    let mut merged: Vec<Candle> = Vec::new();
+    
+    for candle in candles {
+        let bucket = candle.timestamp / to.secs() * to.secs();
+        match merged.last_mut() {
+            Some(current) if current.timestamp == bucket => {
+                current.high = current.high.max(candle.high);
+                current.low = current.low.min(candle.low);
+                current.close = candle.close;
+                current.volume += candle.volume;
+            }
+            _ => merged.push(Candle {
+                timestamp: bucket,
+                ..candle.clone()
+            }),
+        }
+    }
+    
+    merged
+}
@@ -1,8 +1,26 @@
 use anyhow::Result;
+use async_trait::async_trait;
 use serde_json::Value;
-use shared::types::ReasoningResult;
+use shared::types::{
+    AssetPrediction, Backtest, BasketPredictionOutput, ForecastModel, ForecastStep, MarketPredictionInput,
+    MarketPredictionOutput, ReasoningResult, TaskPayload, Trend, MAX_CANDLES, MAX_HORIZON,
+};
 use tracing::info;
 
+use super::{forecast, market_data, ReasoningModule};
+
+// Prediction intervals cover 95% of a normally distributed error
+const INTERVAL_LEVEL: f64 = 0.95;
+const Z_95: f64 = 1.959964;
+
+// Relative change per step below which the trend is sideways
+const TREND_THRESHOLD: f64 = 0.001;
+
+// Backtest samples that count for as much as the error itself: five
+// samples give at most half confidence
+const CONFIDENCE_PRIOR: f64 = 5.0;
+
+#[derive(Default)]
 pub struct MarketPredictor;
 
 impl MarketPredictor {
@@ -13,45 +31,245 @@ impl MarketPredictor {
     pub async fn predict(&self, data: &Value) -> Result<ReasoningResult> {
         info!("🔮 Running market prediction analysis...");
         
-        // Ekstrak data historis
-        let prices = data["prices"].as_array()
-            .ok_or_else(|| anyhow::anyhow!("Invalid price data"))?;
+        let input = MarketPredictionInput::from_value(data)?;
         
-        // Simple moving average prediction
-        let prediction = self.calculate_sma(prices, 7)?;
+        if input.assets.is_empty() {
+            let output = self.forecast(&input);
+            
+            return Ok(ReasoningResult {
+                prediction: serde_json::to_value(&output)?,
+                confidence_score: output.confidence,
+                ..ReasoningResult::default()
+            });
+        }
         
-        let confidence = self.calculate_confidence(prices)?;
+        // A basket is only as reliable as its weakest forecast
+        let output = self.forecast_basket(&input);
+        let confidence = output
+            .assets
+            .iter()
+            .map(|asset| asset.forecast.confidence)
+            .fold(f64::INFINITY, f64::min);
         
         Ok(ReasoningResult {
-            prediction: serde_json::json!({
-                "predicted_price": prediction,
-                "confidence": confidence,
-                "trend": if prediction > 0.0 { "bullish" } else { "bearish" }
-            }),
+            prediction: serde_json::to_value(&output)?,
             confidence_score: confidence,
-            computation_time_ms: 150,
+            ..ReasoningResult::default()
         })
     }
     
-    fn calculate_sma(&self, prices: &[Value], period: usize) -> Result<f64> {
-        let recent_prices: Vec<f64> = prices
+    // Forecast of a bare `prices` input
+    pub fn forecast(&self, input: &MarketPredictionInput) -> MarketPredictionOutput {
+        self.forecast_prices(&input.prices, input.model, input.horizon)
+    }
+    
+    // Forecast of every asset of an `assets` input, on its closing prices
+    pub fn forecast_basket(&self, input: &MarketPredictionInput) -> BasketPredictionOutput {
+        let assets = input
+            .assets
             .iter()
-            .rev()
-            .take(period)
-            .filter_map(|v| v.as_f64())
+            .map(|asset| {
+                let series = market_data::prepare(asset, input.resample);
+                
+                let mut forecast = self.forecast_prices(&series.closes(), input.model, input.horizon);
+                for step in &mut forecast.forecast {
+                    step.timestamp = Some(series.timestamp_after(step.step));
+                }
+                
+                AssetPrediction {
+                    symbol: asset.symbol.clone(),
+                    interval: series.interval,
+                    candles: series.candles.len(),
+                    gaps: series.gaps,
+                    forecast,
+                }
+            })
             .collect();
         
-        if recent_prices.is_empty() {
-            return Ok(0.0);
+        BasketPredictionOutput { assets }
+    }
+    
+    // Work units of a run on `input`: fitting and backtesting every series,
+    // and for a basket one unit per candle filled in or merged
+    pub fn work(&self, input: &MarketPredictionInput) -> u64 {
+        let horizon = input.horizon as usize;
+        let series = |len: usize| {
+            forecast::fit_work(input.model, len, horizon) + forecast::backtest_work(input.model, len, horizon)
+        };
+        
+        if input.assets.is_empty() {
+            return series(input.prices.len());
         }
         
-        let sum: f64 = recent_prices.iter().sum();
-        Ok(sum / recent_prices.len() as f64)
+        input
+            .assets
+            .iter()
+            .map(|asset| {
+                let steps = asset.steps(input.resample);
+                asset.steps(None) + steps + series(steps as usize)
+            })
+            .sum()
+    }
+    
+    fn forecast_prices(&self, prices: &[f64], model: ForecastModel, horizon: u64) -> MarketPredictionOutput {
+        let steps = horizon as usize;
+        let fit = forecast::fit(model, prices, steps);
+        let errors = forecast::backtest(model, prices, steps);
+        let spread = self.interval_spread(&errors);
+        
+        let forecast: Vec<ForecastStep> = fit
+            .path
+            .iter()
+            .zip(&spread)
+            .enumerate()
+            .map(|(step, (price, spread))| ForecastStep {
+                step: step as u64 + 1,
+                timestamp: None,
+                price: *price,
+                lower: price - spread,
+                upper: price + spread,
+            })
+            .collect();
+        
+        let backtest = self.summarize(&errors);
+        let last = prices[prices.len() - 1];
+        
+        MarketPredictionOutput {
+            predicted_price: fit.path[steps - 1],
+            confidence: self.calculate_confidence(&backtest, prices),
+            trend: self.classify_trend(fit.slope, last),
+            model,
+            horizon,
+            slope: fit.slope,
+            interval_level: INTERVAL_LEVEL,
+            forecast,
+            backtest,
+        }
+    }
+    
+    // Half-width of the interval at each step, from the backtest errors that
+    // far ahead. Steps the history is too short to test borrow the error of
+    // the furthest tested one, widened like a random walk.
+    fn interval_spread(&self, errors: &[Vec<f64>]) -> Vec<f64> {
+        let mut tested: Option<(usize, f64)> = None;
+        
+        errors
+            .iter()
+            .enumerate()
+            .map(|(step, errors)| {
+                if !errors.is_empty() {
+                    let rmse = (errors.iter().map(|e| e * e).sum::<f64>() / errors.len() as f64).sqrt();
+                    tested = Some((step + 1, rmse));
+                }
+                match tested {
+                    Some((at, rmse)) => Z_95 * rmse * ((step + 1) as f64 / at as f64).sqrt(),
+                    None => 0.0,
+                }
+            })
+            .collect()
+    }
+    
+    fn summarize(&self, errors: &[Vec<f64>]) -> Backtest {
+        let errors: Vec<f64> = errors.iter().flatten().copied().collect();
+        if errors.is_empty() {
+            return Backtest { samples: 0, mae: 0.0, rmse: 0.0 };
+        }
+        
+        let n = errors.len() as f64;
+        Backtest {
+            samples: errors.len(),
+            mae: errors.iter().map(|e| e.abs()).sum::<f64>() / n,
+            rmse: (errors.iter().map(|e| e * e).sum::<f64>() / n).sqrt(),
+        }
+    }
+    
+    // High when the backtest errors are small next to the prices and there
+    // are enough of them to go by; zero without any
+    fn calculate_confidence(&self, backtest: &Backtest, prices: &[f64]) -> f64 {
+        if backtest.samples == 0 {
+            return 0.0;
+        }
+        
+        let scale = prices.iter().map(|price| price.abs()).sum::<f64>() / prices.len() as f64;
+        let relative_error = if backtest.mae == 0.0 { 0.0 } else { backtest.mae / scale };
+        let evidence = backtest.samples as f64 / (backtest.samples as f64 + CONFIDENCE_PRIOR);
+        
+        evidence / (1.0 + 10.0 * relative_error)
+    }
+    
+    fn classify_trend(&self, slope: f64, last: f64) -> Trend {
+        let relative = slope / last.abs().max(f64::EPSILON);
+        
+        if relative > TREND_THRESHOLD {
+            Trend::Bullish
+        } else if relative < -TREND_THRESHOLD {
+            Trend::Bearish
+        } else {
+            Trend::Sideways
+        }
+    }
+}
+
+#[async_trait]
+impl ReasoningModule for MarketPredictor {
+    fn task_type(&self) -> &str {
+        "market_prediction"
+    }
+    
+    fn input_schema(&self) -> Value {
+        let interval = serde_json::json!({ "type": "string", "pattern": "^[0-9]+[smhdw]$" });
+        let candle = serde_json::json!({
+            "type": "object",
+            "required": ["timestamp", "open", "high", "low", "close", "volume"],
+            "properties": {
+                "timestamp": { "type": "integer", "minimum": 0 },
+                "open": { "type": "number" },
+                "high": { "type": "number" },
+                "low": { "type": "number" },
+                "close": { "type": "number" },
+                "volume": { "type": "number", "minimum": 0 }
+            }
+        });
+        
+        serde_json::json!({
+            "type": "object",
+            "oneOf": [{ "required": ["prices"] }, { "required": ["assets"] }],
+            "properties": {
+                "symbol": { "type": "string" },
+                "prices": { "type": "array", "items": { "type": "number" }, "minItems": 1 },
+                "assets": {
+                    "type": "array",
+                    "minItems": 1,
+                    "items": {
+                        "type": "object",
+                        "required": ["symbol", "interval", "candles"],
+                        "properties": {
+                            "symbol": { "type": "string", "minLength": 1 },
+                            "interval": interval,
+                            "candles": { "type": "array", "items": candle, "minItems": 1, "maxItems": MAX_CANDLES }
+                        }
+                    }
+                },
+                "resample": interval,
+                "model": {
+                    "enum": ForecastModel::ALL.iter().map(|model| model.as_str()).collect::<Vec<_>>(),
+                    "default": ForecastModel::default().as_str()
+                },
+                "horizon": { "type": "integer", "minimum": 1, "maximum": MAX_HORIZON, "default": 1 }
+            }
+        })
+    }
+    
+    fn version(&self) -> &str {
+        "2.1.0"
+    }
+    
+    async fn run(&self, data: &Value) -> Result<ReasoningResult> {
+        self.predict(data).await
     }
     
-    fn calculate_confidence(&self, prices: &[Value]) -> Result<f64> {
-        // Simplified confidence based on data quality
-        let valid_count = prices.iter().filter(|v| v.is_number()).count();
-        Ok((valid_count as f64 / prices.len() as f64) * 0.85)
+    fn work_units(&self, data: &Value) -> u64 {
+        // Inputs that do not parse fail before any work is done
+        MarketPredictionInput::from_value(data).map_or(0, |input| self.work(&input))
     }
 }
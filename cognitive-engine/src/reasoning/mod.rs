@@ -1,3 +1,11 @@
+pub mod cancel;
+pub mod forecast;
+pub mod indicators;
+pub mod market_data;
 pub mod market_prediction;
 pub mod anomaly_detection;
 pub mod risk_scoring;
+pub mod technical_indicators;
+pub mod module;
+
+pub use module::{value_count, ModuleRegistry, ReasoningModule};
@@ -0,0 +1,98 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+use shared::types::{ReasoningResult, ReasoningTask};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use super::{
+    anomaly_detection::AnomalyDetector,
+    market_prediction::MarketPredictor,
+    risk_scoring::RiskScorer,
+    technical_indicators::TechnicalAnalyzer,
+};
+
+#[async_trait]
+pub trait ReasoningModule: Send + Sync {
+    // Value of `ReasoningTask.task_type` this module handles
+    fn task_type(&self) -> &str;
+    
+    // JSON Schema describing the expected `ReasoningTask.data`
+    fn input_schema(&self) -> Value;
+    
+    fn version(&self) -> &str;
+    
+    async fn run(&self, data: &Value) -> Result<ReasoningResult>;
+    
+    // Deterministic cost of a run on `data`, committed on chain as the
+    // proof's computationCost and recomputed by validators, so it may only
+    // depend on the input. One unit per input value unless overridden.
+    fn work_units(&self, data: &Value) -> u64 {
+        value_count(data)
+    }
+}
+
+// Scalar values in `value`, at any depth
+pub fn value_count(value: &Value) -> u64 {
+    match value {
+        Value::Array(items) => items.iter().map(value_count).sum(),
+        Value::Object(fields) => fields.values().map(value_count).sum(),
+        _ => 1,
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct ModuleRegistry {
+    modules: BTreeMap<String, Arc<dyn ReasoningModule>>,
+}
+
+impl ModuleRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn with_builtin_modules() -> Self {
+        let mut registry = Self::new();
+        
+        registry.register(MarketPredictor::new()).expect("builtin task types are unique");
+        registry.register(AnomalyDetector::new()).expect("builtin task types are unique");
+        registry.register(RiskScorer::new()).expect("builtin task types are unique");
+        registry.register(TechnicalAnalyzer::new()).expect("builtin task types are unique");
+        
+        registry
+    }
+    
+    pub fn register(&mut self, module: impl ReasoningModule + 'static) -> Result<()> {
+        let task_type = module.task_type().to_string();
+        
+        if self.modules.contains_key(&task_type) {
+            anyhow::bail!("A module for task type '{}' is already registered", task_type);
+        }
+        
+        self.modules.insert(task_type, Arc::new(module));
+        Ok(())
+    }
+    
+    // Drops every module not listed in `task_types`. Naming a task type
+    // with no module is an error, so a typo does not go unnoticed.
+    pub fn retain(&mut self, task_types: &BTreeSet<String>) -> Result<()> {
+        if let Some(unknown) = task_types.iter().find(|task_type| !self.modules.contains_key(*task_type)) {
+            anyhow::bail!("No module for task type '{}'", unknown);
+        }
+        
+        self.modules.retain(|task_type, _| task_types.contains(task_type));
+        Ok(())
+    }
+    
+    pub fn get(&self, task_type: &str) -> Option<Arc<dyn ReasoningModule>> {
+        self.modules.get(task_type).cloned()
+    }
+    
+    pub fn supports(&self, task: &ReasoningTask) -> bool {
+        self.modules.contains_key(&task.task_type)
+    }
+    
+    pub fn task_types(&self) -> impl Iterator<Item = &str> {
+        self.modules.keys().map(String::as_str)
+    }
+}
@@ -1,8 +1,12 @@
 use anyhow::Result;
+use async_trait::async_trait;
 use serde_json::Value;
-use shared::types::ReasoningResult;
+use shared::types::{ReasoningResult, RiskFactors, RiskLevel, RiskScoringInput, RiskScoringOutput, TaskPayload};
 use tracing::info;
 
+use super::ReasoningModule;
+
+#[derive(Default)]
 pub struct RiskScorer;
 
 impl RiskScorer {
@@ -13,32 +17,31 @@ impl RiskScorer {
     pub async fn score(&self, data: &Value) -> Result<ReasoningResult> {
         info!("⚠️  Running risk scoring analysis...");
         
-        let contract_address = data["contract_address"].as_str()
-            .ok_or_else(|| anyhow::anyhow!("Missing contract address"))?;
-        
-        let code_complexity = data["code_complexity"].as_f64().unwrap_or(0.5);
-        let audit_status = data["audited"].as_bool().unwrap_or(false);
-        let transaction_volume = data["tx_volume"].as_f64().unwrap_or(0.0);
-        
-        let risk_score = self.calculate_risk(code_complexity, audit_status, transaction_volume);
-        let risk_level = self.categorize_risk(risk_score);
+        let input = RiskScoringInput::from_value(data)?;
+        let output = self.assess(&input);
         
         Ok(ReasoningResult {
-            prediction: serde_json::json!({
-                "contract": contract_address,
-                "risk_score": risk_score,
-                "risk_level": risk_level,
-                "factors": {
-                    "code_complexity": code_complexity,
-                    "audited": audit_status,
-                    "tx_volume": transaction_volume
-                }
-            }),
+            prediction: serde_json::to_value(&output)?,
             confidence_score: 0.88,
-            computation_time_ms: 180,
+            ..ReasoningResult::default()
         })
     }
     
+    pub fn assess(&self, input: &RiskScoringInput) -> RiskScoringOutput {
+        let risk_score = self.calculate_risk(input.code_complexity, input.audited, input.tx_volume);
+        
+        RiskScoringOutput {
+            contract: input.contract_address.clone(),
+            risk_score,
+            risk_level: self.categorize_risk(risk_score),
+            factors: RiskFactors {
+                code_complexity: input.code_complexity,
+                audited: input.audited,
+                tx_volume: input.tx_volume,
+            },
+        }
+    }
+    
     fn calculate_risk(&self, complexity: f64, audited: bool, volume: f64) -> f64 {
         let mut risk = complexity * 0.4;
         
@@ -53,12 +56,45 @@ impl RiskScorer {
         risk.min(1.0)
     }
     
-    fn categorize_risk(&self, score: f64) -> &str {
+    fn categorize_risk(&self, score: f64) -> RiskLevel {
         match score {
-            s if s < 0.3 => "low",
-            s if s < 0.6 => "medium",
-            s if s < 0.8 => "high",
-            _ => "critical"
+            s if s < 0.3 => RiskLevel::Low,
+            s if s < 0.6 => RiskLevel::Medium,
+            s if s < 0.8 => RiskLevel::High,
+            _ => RiskLevel::Critical
         }
     }
 }
+
+#[async_trait]
+impl ReasoningModule for RiskScorer {
+    fn task_type(&self) -> &str {
+        "risk_scoring"
+    }
+    
+    fn input_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "required": ["contract_address"],
+            "properties": {
+                "contract_address": { "type": "string" },
+                "code_complexity": { "type": "number", "minimum": 0, "maximum": 1 },
+                "audited": { "type": "boolean" },
+                "tx_volume": { "type": "number", "minimum": 0 }
+            }
+        })
+    }
+    
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+    
+    async fn run(&self, data: &Value) -> Result<ReasoningResult> {
+        self.score(data).await
+    }
+    
+    // One unit per risk factor weighed, whatever the input
+    fn work_units(&self, _data: &Value) -> u64 {
+        3
+    }
+}
@@ -0,0 +1,199 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+use shared::types::{
+    Indicator, IndicatorResult, ReasoningResult, TaskPayload, TechnicalIndicatorsInput, TechnicalIndicatorsOutput,
+    MAX_CANDLES, MAX_INDICATORS,
+};
+use std::collections::BTreeMap;
+use tracing::info;
+
+use super::{indicators, ReasoningModule};
+
+#[derive(Default)]
+pub struct TechnicalAnalyzer;
+
+impl TechnicalAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+    
+    pub async fn analyze(&self, data: &Value) -> Result<ReasoningResult> {
+        info!("📈 Computing technical indicators...");
+        
+        let input = TechnicalIndicatorsInput::from_value(data)?;
+        let output = self.compute(&input);
+        
+        Ok(ReasoningResult {
+            prediction: serde_json::to_value(&output)?,
+            confidence_score: self.calculate_confidence(&output),
+            ..ReasoningResult::default()
+        })
+    }
+    
+    pub fn compute(&self, input: &TechnicalIndicatorsInput) -> TechnicalIndicatorsOutput {
+        let closes = input.closes();
+        
+        let indicators = input
+            .indicators
+            .iter()
+            .map(|indicator| {
+                let series = self.lines(indicator, &closes, input);
+                let latest = series
+                    .iter()
+                    .map(|(name, line)| (name.clone(), line.last().copied().flatten()))
+                    .collect();
+                
+                IndicatorResult {
+                    indicator: *indicator,
+                    latest,
+                    series,
+                }
+            })
+            .collect();
+        
+        TechnicalIndicatorsOutput {
+            symbol: input.symbol.clone(),
+            indicators,
+        }
+    }
+    
+    fn lines(
+        &self,
+        indicator: &Indicator,
+        closes: &[f64],
+        input: &TechnicalIndicatorsInput,
+    ) -> BTreeMap<String, indicators::Line> {
+        let lines = match *indicator {
+            Indicator::Rsi { period } => vec![("rsi", indicators::rsi(closes, period as usize))],
+            Indicator::Macd { fast, slow, signal } => {
+                let macd = indicators::macd(closes, fast as usize, slow as usize, signal as usize);
+                vec![("macd", macd.macd), ("signal", macd.signal), ("histogram", macd.histogram)]
+            }
+            Indicator::Bollinger { period, std_dev } => {
+                let bands = indicators::bollinger(closes, period as usize, std_dev);
+                vec![("middle", bands.middle), ("upper", bands.upper), ("lower", bands.lower)]
+            }
+            Indicator::Atr { period } => vec![("atr", indicators::atr(&input.candles, period as usize))],
+            Indicator::Vwap { period } => {
+                vec![("vwap", indicators::vwap(&input.candles, period.map(|period| period as usize)))]
+            }
+            Indicator::Volatility {
+                period,
+                periods_per_year,
+            } => vec![("volatility", indicators::volatility(closes, period as usize, periods_per_year))],
+        };
+        
+        lines.into_iter().map(|(name, line)| (name.to_string(), line)).collect()
+    }
+    
+    // Work units of a run on `input`: the operations each indicator takes
+    // over the series, counted the way `indicators` computes it
+    pub fn work(&self, input: &TechnicalIndicatorsInput) -> u64 {
+        let len = input.closes().len() as u64;
+        // Values summed over all full windows of `period` in `len` values
+        let windowed = |len: u64, period: u64| (len + 1).saturating_sub(period).saturating_mul(period);
+        
+        let indicators: u64 = input
+            .indicators
+            .iter()
+            .map(|indicator| match *indicator {
+                // Changes, then gains and losses smoothed
+                Indicator::Rsi { .. } => 3 * len,
+                // Two EMAs, their difference and the signal line
+                Indicator::Macd { .. } => 4 * len,
+                // Mean of every window and its deviation, then both bands
+                Indicator::Bollinger { period, .. } => 3 * windowed(len, period) + 2 * len,
+                Indicator::Atr { .. } => 2 * len,
+                Indicator::Vwap { period: Some(period) } => 2 * windowed(len, period),
+                // Running sums of volume and traded value
+                Indicator::Vwap { period: None } => 2 * len,
+                // Log returns, then mean and deviation of every window
+                Indicator::Volatility { period, .. } => len + 2 * windowed(len.saturating_sub(1), period),
+            })
+            .fold(0, u64::saturating_add);
+        
+        len.saturating_add(indicators)
+    }
+    
+    // Share of indicators with a value at the last price. Validation makes
+    // sure every window fits, so only a VWAP window without volume misses.
+    fn calculate_confidence(&self, output: &TechnicalIndicatorsOutput) -> f64 {
+        let complete = output
+            .indicators
+            .iter()
+            .filter(|result| result.latest.values().all(Option::is_some))
+            .count();
+        
+        complete as f64 / output.indicators.len() as f64
+    }
+}
+
+#[async_trait]
+impl ReasoningModule for TechnicalAnalyzer {
+    fn task_type(&self) -> &str {
+        "technical_indicators"
+    }
+    
+    fn input_schema(&self) -> Value {
+        let window = serde_json::json!({ "type": "integer", "minimum": 1 });
+        
+        serde_json::json!({
+            "type": "object",
+            "required": ["indicators"],
+            "oneOf": [{ "required": ["prices"] }, { "required": ["candles"] }],
+            "properties": {
+                "symbol": { "type": "string" },
+                "prices": { "type": "array", "items": { "type": "number" }, "minItems": 1, "maxItems": MAX_CANDLES },
+                "candles": {
+                    "type": "array",
+                    "minItems": 1,
+                    "maxItems": MAX_CANDLES,
+                    "items": {
+                        "type": "object",
+                        "required": ["timestamp", "open", "high", "low", "close", "volume"],
+                        "properties": {
+                            "timestamp": { "type": "integer", "minimum": 0 },
+                            "open": { "type": "number" },
+                            "high": { "type": "number" },
+                            "low": { "type": "number" },
+                            "close": { "type": "number" },
+                            "volume": { "type": "number", "minimum": 0 }
+                        }
+                    }
+                },
+                "indicators": {
+                    "type": "array",
+                    "minItems": 1,
+                    "maxItems": MAX_INDICATORS,
+                    "items": {
+                        "type": "object",
+                        "required": ["name"],
+                        "properties": {
+                            "name": { "enum": ["rsi", "macd", "bollinger", "atr", "vwap", "volatility"] },
+                            "period": window,
+                            "fast": window,
+                            "slow": window,
+                            "signal": window,
+                            "std_dev": { "type": "number", "exclusiveMinimum": 0 },
+                            "periods_per_year": window
+                        }
+                    }
+                }
+            }
+        })
+    }
+    
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+    
+    async fn run(&self, data: &Value) -> Result<ReasoningResult> {
+        self.analyze(data).await
+    }
+    
+    fn work_units(&self, data: &Value) -> u64 {
+        // Inputs that do not parse fail before any work is done
+        TechnicalIndicatorsInput::from_value(data).map_or(0, |input| self.work(&input))
+    }
+}
@@ -0,0 +1,383 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use thiserror::Error;
+use tracing::{debug, info};
+
+use crate::cursor::BlockCursor;
+use crate::jobs::JobStore;
+use crate::signer::NodeSigner;
+use bindings::CognitiveRegistry;
+use shared::hashing::to_hex;
+use shared::telemetry::MeteredProvider;
+use shared::types::ReasoningTask;
+
+// Most public RPC endpoints reject eth_getLogs over larger ranges
+const DEFAULT_MAX_BLOCK_RANGE: u64 = 2_000;
+
+type TaskData = (U256, String, [u8; 32], Address, U256, U256, bool, Address);
+
+// Signing client used by the engine. The nonce manager sits on top so that
+// back-to-back assignTask/submitTaskResult calls get sequential nonces
+// without waiting for the previous transaction to be mined.
+pub type EngineClient = NonceManagerMiddleware<SignerMiddleware<MeteredProvider, NodeSigner>>;
+
+#[derive(Debug, Error)]
+pub enum RegistryError {
+    #[error("task {0} is already assigned")]
+    AlreadyAssigned(u64),
+    #[error("task {0} is already completed")]
+    AlreadyCompleted(u64),
+    #[error("task {0} expired before it could be assigned")]
+    TaskExpired(u64),
+    #[error("deadline passed for task {0}")]
+    DeadlinePassed(u64),
+    #[error("task {0} is not assigned to this node")]
+    NotAssigned(u64),
+    #[error("node cannot take tasks: {0}")]
+    NodeInactive(String),
+    #[error("transaction for task {task_id} reverted: {reason}")]
+    Reverted { task_id: u64, reason: String },
+    #[error("transaction {0:?} was dropped before confirmation")]
+    Dropped(TxHash),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl RegistryError {
+    // Maps the require() messages of CognitiveRegistry onto typed errors
+    pub fn from_revert(task_id: u64, reason: &str) -> Self {
+        match reason {
+            "Task already assigned" => Self::AlreadyAssigned(task_id),
+            "Task completed" | "Already completed" => Self::AlreadyCompleted(task_id),
+            "Task expired" => Self::TaskExpired(task_id),
+            "Deadline passed" => Self::DeadlinePassed(task_id),
+            "Not assigned to you" => Self::NotAssigned(task_id),
+            "Not registered" | "Node not active" => Self::NodeInactive(reason.to_string()),
+            _ => Self::Reverted {
+                task_id,
+                reason: reason.to_string(),
+            },
+        }
+    }
+    
+    fn from_contract_error<M: Middleware>(task_id: u64, err: ContractError<M>) -> Self {
+        if let Some(reason) = err.decode_revert::<String>() {
+            return Self::from_revert(task_id, &reason);
+        }
+        
+        // Some nodes only put the reason in the error message
+        let message = err.to_string();
+        for reason in KNOWN_REVERTS {
+            if message.contains(reason) {
+                return Self::from_revert(task_id, reason);
+            }
+        }
+        
+        Self::Other(anyhow::anyhow!("task {}: {}", task_id, message))
+    }
+}
+
+const KNOWN_REVERTS: [&str; 8] = [
+    "Task already assigned",
+    "Task completed",
+    "Already completed",
+    "Task expired",
+    "Deadline passed",
+    "Not assigned to you",
+    "Not registered",
+    "Node not active",
+];
+
+#[derive(Debug, Clone)]
+pub struct OnChainTask {
+    pub task_id: U256,
+    pub task_type: String,
+    pub data_hash: [u8; 32],
+    pub requester: Address,
+    pub reward: U256,
+    pub deadline: U256,
+    pub completed: bool,
+    pub assigned_node: Address,
+}
+
+impl From<TaskData> for OnChainTask {
+    fn from(data: TaskData) -> Self {
+        let (task_id, task_type, data_hash, requester, reward, deadline, completed, assigned_node) = data;
+        Self {
+            task_id,
+            task_type,
+            data_hash,
+            requester,
+            reward,
+            deadline,
+            completed,
+            assigned_node,
+        }
+    }
+}
+
+impl OnChainTask {
+    // Mirrors the require() checks in CognitiveRegistry.assignTask
+    pub fn is_open(&self, now: u64) -> bool {
+        self.task_id != U256::zero()
+            && !self.completed
+            && self.assigned_node == Address::zero()
+            && self.deadline > U256::from(now)
+    }
+    
+    pub fn into_reasoning_task(self) -> ReasoningTask {
+        ReasoningTask {
+            task_id: saturating_u64(self.task_id),
+            task_type: self.task_type,
+            // Payload lives off-chain, only its hash is stored in the registry
+            data: serde_json::Value::Null,
+            data_hash: to_hex(&self.data_hash),
+            requester: format!("{:?}", self.requester),
+            reward: saturating_u64(self.reward),
+            deadline: saturating_u64(self.deadline),
+        }
+    }
+}
+
+pub(crate) fn saturating_u64(value: U256) -> u64 {
+    value.min(U256::from(u64::MAX)).as_u64()
+}
+
+pub struct RegistryClient<M> {
+    contract: CognitiveRegistry<M>,
+    max_block_range: u64,
+    confirmations: usize,
+}
+
+impl<M: Middleware + 'static> RegistryClient<M> {
+    pub fn new(address: Address, client: Arc<M>) -> Self {
+        Self {
+            contract: CognitiveRegistry::new(address, client),
+            max_block_range: DEFAULT_MAX_BLOCK_RANGE,
+            confirmations: 1,
+        }
+    }
+    
+    pub fn with_confirmations(mut self, confirmations: usize) -> Self {
+        self.confirmations = confirmations.max(1);
+        self
+    }
+    
+    pub fn with_max_block_range(mut self, max_block_range: u64) -> Self {
+        self.max_block_range = max_block_range.max(1);
+        self
+    }
+    
+    pub fn address(&self) -> Address {
+        self.contract.address()
+    }
+    
+    pub fn contract(&self) -> &CognitiveRegistry<M> {
+        &self.contract
+    }
+    
+    pub fn max_block_range(&self) -> u64 {
+        self.max_block_range
+    }
+    
+    pub async fn task_created_ids(&self, from_block: u64, to_block: u64) -> Result<BTreeSet<u64>> {
+        let events = self
+            .query_chunked(self.contract.task_created_filter(), from_block, to_block)
+            .await?;
+        
+        Ok(events.into_iter().map(|event| saturating_u64(event.task_id)).collect())
+    }
+    
+    // (task id, node) of every TaskAssigned log in the range, only those of
+    // `node` if given
+    pub async fn task_assignments(
+        &self,
+        from_block: u64,
+        to_block: u64,
+        node: Option<Address>,
+    ) -> Result<Vec<(u64, Address)>> {
+        let mut filter = self.contract.task_assigned_filter();
+        if let Some(node) = node {
+            filter = filter.topic2(node);
+        }
+        
+        let events = self.query_chunked(filter, from_block, to_block).await?;
+        
+        Ok(events
+            .into_iter()
+            .map(|event| (saturating_u64(event.task_id), event.node))
+            .collect())
+    }
+    
+    // Runs `event` over the range in queries of at most `max_block_range` blocks
+    async fn query_chunked<D: EthLogDecode>(
+        &self,
+        mut event: Event<Arc<M>, M, D>,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<D>> {
+        let mut logs = Vec::new();
+        let mut start = from_block;
+        
+        while start <= to_block {
+            let end = to_block.min(start + self.max_block_range - 1);
+            
+            event.filter = event.filter.clone().from_block(start).to_block(end);
+            let events = event
+                .query()
+                .await
+                .map_err(|e| anyhow::anyhow!("eth_getLogs failed for blocks {}-{}: {}", start, end, e))?;
+            logs.extend(events);
+            
+            start = end + 1;
+        }
+        
+        Ok(logs)
+    }
+    
+    // The node `account` works for: its own address unless it is the
+    // node's operator key
+    pub async fn node_of(&self, account: Address) -> Result<Address> {
+        Ok(self.contract.node_of(account).call().await?)
+    }
+    
+    pub async fn get_task(&self, task_id: u64) -> Result<OnChainTask> {
+        let data: TaskData = self.contract.reasoning_tasks(U256::from(task_id)).call().await?;
+        
+        Ok(data.into())
+    }
+    
+    // Open tasks created since the cursor. Each is recorded in `jobs` before
+    // the cursor moves past it, so a crash in between cannot lose it.
+    pub async fn fetch_open_tasks(
+        &self,
+        cursor: &BlockCursor,
+        jobs: &JobStore,
+        start_block: u64,
+    ) -> Result<Vec<ReasoningTask>> {
+        let client = self.contract.client();
+        
+        let head = client
+            .get_block_number()
+            .await
+            .map_err(|e| anyhow::anyhow!("eth_blockNumber failed: {}", e))?
+            .as_u64();
+        
+        let from_block = match cursor.load()? {
+            Some(last) => last + 1,
+            None => start_block,
+        };
+        
+        if from_block > head {
+            return Ok(vec![]);
+        }
+        
+        let task_ids = self.task_created_ids(from_block, head).await?;
+        debug!("Found {} TaskCreated logs in blocks {}-{}", task_ids.len(), from_block, head);
+        
+        // Deadlines are checked against chain time, the same clock the contract uses
+        let now = client
+            .get_block(head)
+            .await
+            .map_err(|e| anyhow::anyhow!("eth_getBlockByNumber failed: {}", e))?
+            .map(|block| block.timestamp.as_u64())
+            .ok_or_else(|| anyhow::anyhow!("Block {} not found", head))?;
+        
+        let mut tasks = Vec::new();
+        for task_id in task_ids {
+            let task = self.get_task(task_id).await?;
+            
+            if task.is_open(now) {
+                let task = task.into_reasoning_task();
+                jobs.discover(&task).await?;
+                tasks.push(task);
+            } else {
+                debug!("Skipping task {}: completed, assigned or expired", task_id);
+            }
+        }
+        
+        cursor.save(head)?;
+        
+        if !tasks.is_empty() {
+            info!("📥 Discovered {} open task(s) up to block {}", tasks.len(), head);
+        }
+        
+        Ok(tasks)
+    }
+    
+    pub async fn assign_task(&self, task_id: u64) -> Result<TransactionReceipt, RegistryError> {
+        info!("🙋 Claiming task {}", task_id);
+        
+        let call = self.contract.assign_task(U256::from(task_id));
+        
+        self.send_and_confirm(task_id, call).await
+    }
+    
+    pub async fn submit_task_result(
+        &self,
+        task_id: u64,
+        result_hash: [u8; 32],
+    ) -> Result<TransactionReceipt, RegistryError> {
+        let tx_hash = self.send_task_result(task_id, result_hash).await?;
+        
+        self.confirm(task_id, tx_hash).await
+    }
+    
+    // Sends the result without waiting, so the hash can be recorded before
+    // the transaction is mined
+    pub async fn send_task_result(&self, task_id: u64, result_hash: [u8; 32]) -> Result<TxHash, RegistryError> {
+        info!("📤 Submitting result for task {} (hash {})", task_id, to_hex(&result_hash));
+        
+        let call = self.contract.submit_task_result(U256::from(task_id), result_hash);
+        
+        self.send(task_id, call).await
+    }
+    
+    async fn send_and_confirm(
+        &self,
+        task_id: u64,
+        call: ContractCall<M, ()>,
+    ) -> Result<TransactionReceipt, RegistryError> {
+        let tx_hash = self.send(task_id, call).await?;
+        
+        self.confirm(task_id, tx_hash).await
+    }
+    
+    async fn send(&self, task_id: u64, call: ContractCall<M, ()>) -> Result<TxHash, RegistryError> {
+        // Dry-run first so a revert surfaces with its reason and costs no gas
+        call.call()
+            .await
+            .map_err(|e| RegistryError::from_contract_error(task_id, e))?;
+        
+        let pending = call
+            .send()
+            .await
+            .map_err(|e| RegistryError::from_contract_error(task_id, e))?;
+        
+        Ok(pending.tx_hash())
+    }
+    
+    // Waits for a transaction sent earlier, possibly by a previous run
+    pub async fn confirm(&self, task_id: u64, tx_hash: TxHash) -> Result<TransactionReceipt, RegistryError> {
+        let client = self.contract.client();
+        
+        let receipt = PendingTransaction::new(tx_hash, client.provider())
+            .confirmations(self.confirmations)
+            .await
+            .map_err(|e| RegistryError::Other(e.into()))?
+            .ok_or(RegistryError::Dropped(tx_hash))?;
+        
+        // The state may have changed between the dry-run and inclusion
+        if receipt.status != Some(U64::one()) {
+            return Err(RegistryError::Reverted {
+                task_id,
+                reason: format!("transaction {:?} failed on-chain", tx_hash),
+            });
+        }
+        
+        debug!("Transaction {:?} confirmed in block {:?}", tx_hash, receipt.block_number);
+        Ok(receipt)
+    }
+}
@@ -0,0 +1,236 @@
+use async_trait::async_trait;
+use ethers::signers::{LocalWallet, Signer, WalletError};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::transaction::eip712::Eip712;
+use ethers::types::{Address, Bytes, Signature};
+use ethers::utils::rlp::Rlp;
+use serde_json::{json, Value};
+use thiserror::Error;
+use tokio::time::Duration;
+
+use shared::config::{ConfigError, KeySource, SignerConfig};
+
+const RPC_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Error)]
+pub enum SignerError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error("remote signer error {code}: {message}")]
+    Rpc { code: i64, message: String },
+    #[error("unexpected response from remote signer: {0}")]
+    InvalidResponse(String),
+    #[error("remote signer does not hold {0:?}")]
+    UnknownAccount(Address),
+    #[error("remote signer holds {0} accounts, configure the address to use")]
+    AmbiguousAccount(usize),
+    #[error("remote signature is not from {0:?} over the requested payload")]
+    WrongSignature(Address),
+    #[error("remote signer does not support {0}")]
+    Unsupported(&'static str),
+}
+
+// Key held by a separate process behind a JSON-RPC API, as offered by
+// Web3Signer (eth1 mode) and Clef. The key never enters this process, and
+// every signature that comes back is checked against the account and the
+// exact payload that was asked for.
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    url: String,
+    address: Address,
+    chain_id: u64,
+    client: reqwest::Client,
+}
+
+impl RemoteSigner {
+    // Uses `address` if the signer holds it, or the signer's only account
+    pub async fn connect(url: impl Into<String>, address: Option<Address>) -> Result<Self, SignerError> {
+        let mut signer = Self {
+            url: url.into(),
+            address: Address::zero(),
+            chain_id: 1,
+            client: reqwest::Client::builder().timeout(RPC_TIMEOUT).build()?,
+        };
+        
+        let accounts: Vec<Address> = serde_json::from_value(signer.call("eth_accounts", json!([])).await?)
+            .map_err(|e| SignerError::InvalidResponse(e.to_string()))?;
+        
+        signer.address = match address {
+            Some(address) if accounts.contains(&address) => address,
+            Some(address) => return Err(SignerError::UnknownAccount(address)),
+            None if accounts.len() == 1 => accounts[0],
+            None => return Err(SignerError::AmbiguousAccount(accounts.len())),
+        };
+        
+        Ok(signer)
+    }
+    
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+    
+    async fn call(&self, method: &str, params: Value) -> Result<Value, SignerError> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: Value = self
+            .client
+            .post(&self.url)
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        
+        if let Some(error) = response.get("error") {
+            return Err(SignerError::Rpc {
+                code: error["code"].as_i64().unwrap_or_default(),
+                message: error["message"].as_str().unwrap_or_default().to_string(),
+            });
+        }
+        
+        response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| SignerError::InvalidResponse(format!("no result for {}", method)))
+    }
+}
+
+#[async_trait]
+impl Signer for RemoteSigner {
+    type Error = SignerError;
+    
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(&self, message: S) -> Result<Signature, Self::Error> {
+        let message = message.as_ref();
+        let result = self
+            .call("eth_sign", json!([self.address, Bytes::from(message.to_vec())]))
+            .await?;
+        
+        let signature: Signature = result
+            .as_str()
+            .ok_or_else(|| SignerError::InvalidResponse(result.to_string()))?
+            .parse()
+            .map_err(|e| SignerError::InvalidResponse(format!("{}", e)))?;
+        
+        signature
+            .verify(message, self.address)
+            .map_err(|_| SignerError::WrongSignature(self.address))?;
+        Ok(signature)
+    }
+    
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        let mut tx = tx.clone();
+        tx.set_from(self.address);
+        if tx.chain_id().is_none() {
+            tx.set_chain_id(self.chain_id);
+        }
+        
+        // Web3Signer answers with the raw signed transaction, Clef with `{ raw, tx }`
+        let result = self.call("eth_signTransaction", json!([tx])).await?;
+        let raw: Bytes = serde_json::from_value(result.get("raw").unwrap_or(&result).clone())
+            .map_err(|e| SignerError::InvalidResponse(e.to_string()))?;
+        let (_, signature) =
+            TypedTransaction::decode_signed(&Rlp::new(&raw)).map_err(|e| SignerError::InvalidResponse(e.to_string()))?;
+        
+        // The caller encodes its own transaction with this signature, so it
+        // has to cover exactly that transaction
+        if signature.recover(tx.sighash()).ok() != Some(self.address) {
+            return Err(SignerError::WrongSignature(self.address));
+        }
+        
+        Ok(signature)
+    }
+    
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(&self, _payload: &T) -> Result<Signature, Self::Error> {
+        Err(SignerError::Unsupported("EIP-712 typed data"))
+    }
+    
+    fn address(&self) -> Address {
+        self.address
+    }
+    
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+    
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+}
+
+// The signer both daemons sign with, whichever way the key is held
+#[derive(Debug, Clone)]
+pub enum NodeSigner {
+    Local(LocalWallet),
+    Remote(RemoteSigner),
+}
+
+impl NodeSigner {
+    pub async fn from_config(config: &SignerConfig, chain_id: u64) -> Result<Self, SignerError> {
+        match config.source()? {
+            KeySource::Remote { url, address } => {
+                let signer = RemoteSigner::connect(url, address).await?;
+                Ok(Self::Remote(signer.with_chain_id(chain_id)))
+            }
+            KeySource::PrivateKey | KeySource::Keystore(_) => Ok(Self::Local(config.wallet(chain_id)?)),
+        }
+    }
+    
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Local(_) => "local key".to_string(),
+            Self::Remote(signer) => format!("remote signer at {}", signer.url()),
+        }
+    }
+}
+
+#[async_trait]
+impl Signer for NodeSigner {
+    type Error = SignerError;
+    
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(&self, message: S) -> Result<Signature, Self::Error> {
+        match self {
+            Self::Local(wallet) => Ok(wallet.sign_message(message).await?),
+            Self::Remote(signer) => signer.sign_message(message).await,
+        }
+    }
+    
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        match self {
+            Self::Local(wallet) => Ok(wallet.sign_transaction(tx).await?),
+            Self::Remote(signer) => signer.sign_transaction(tx).await,
+        }
+    }
+    
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(&self, payload: &T) -> Result<Signature, Self::Error> {
+        match self {
+            Self::Local(wallet) => Ok(wallet.sign_typed_data(payload).await?),
+            Self::Remote(signer) => signer.sign_typed_data(payload).await,
+        }
+    }
+    
+    fn address(&self) -> Address {
+        match self {
+            Self::Local(wallet) => wallet.address(),
+            Self::Remote(signer) => signer.address(),
+        }
+    }
+    
+    fn chain_id(&self) -> u64 {
+        match self {
+            Self::Local(wallet) => wallet.chain_id(),
+            Self::Remote(signer) => signer.chain_id(),
+        }
+    }
+    
+    fn with_chain_id<T: Into<u64>>(self, chain_id: T) -> Self {
+        match self {
+            Self::Local(wallet) => Self::Local(wallet.with_chain_id(chain_id)),
+            Self::Remote(signer) => Self::Remote(signer.with_chain_id(chain_id)),
+        }
+    }
+}
@@ -1,41 +1,142 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use shared::metrics::Histogram;
 use shared::types::{ReasoningTask, ReasoningResult};
+use std::sync::Arc;
+use std::time::Instant;
+use thiserror::Error;
+use tokio::runtime::Handle;
+use tokio::task;
+use tokio::time::{timeout, Duration};
+use tracing::warn;
 
-use crate::reasoning::{
-    market_prediction::MarketPredictor,
-    anomaly_detection::AnomalyDetector,
-    risk_scoring::RiskScorer,
-};
+use crate::metering::cpu_timed;
+use crate::reasoning::cancel::CancelFlag;
+use crate::reasoning::{ModuleRegistry, ReasoningModule};
+
+// How long a timed-out run gets to notice it was cancelled before
+// `process_with_timeout` returns without it
+pub const CANCEL_GRACE: Duration = Duration::from_secs(1);
+
+// Returned (inside the anyhow error) by `process_with_timeout` when the run
+// is out of time
+#[derive(Debug, Error)]
+#[error("Task {task_id} timed out after {}s", limit.as_secs_f64())]
+pub struct TimedOut {
+    pub task_id: u64,
+    pub limit: Duration,
+}
 
 pub struct TaskProcessor {
-    market_predictor: MarketPredictor,
-    anomaly_detector: AnomalyDetector,
-    risk_scorer: RiskScorer,
+    modules: ModuleRegistry,
+    // Run time of each successful module call, by task type
+    latency: Option<Arc<Histogram>>,
+}
+
+impl Default for TaskProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TaskProcessor {
     pub fn new() -> Self {
-        Self {
-            market_predictor: MarketPredictor::new(),
-            anomaly_detector: AnomalyDetector::new(),
-            risk_scorer: RiskScorer::new(),
-        }
+        Self::with_modules(ModuleRegistry::with_builtin_modules())
+    }
+    
+    pub fn with_modules(modules: ModuleRegistry) -> Self {
+        Self { modules, latency: None }
+    }
+    
+    pub fn with_latency(mut self, latency: Arc<Histogram>) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+    
+    pub fn register(&mut self, module: impl ReasoningModule + 'static) -> Result<()> {
+        self.modules.register(module)
+    }
+    
+    pub fn modules(&self) -> &ModuleRegistry {
+        &self.modules
     }
     
     pub async fn process(&self, task: &ReasoningTask) -> Result<ReasoningResult> {
-        match task.task_type.as_str() {
-            "market_prediction" => {
-                self.market_predictor.predict(&task.data).await
-            }
-            "anomaly_detection" => {
-                self.anomaly_detector.detect(&task.data).await
-            }
-            "risk_scoring" => {
-                self.risk_scorer.score(&task.data).await
-            }
-            _ => {
-                anyhow::bail!("Unknown task type: {}", task.task_type)
+        let module = self
+            .modules
+            .get(&task.task_type)
+            .ok_or_else(|| anyhow::anyhow!("Unknown task type: {}", task.task_type))?;
+        
+        let started = Instant::now();
+        let (result, cpu_time) = cpu_timed(module.run(&task.data)).await;
+        self.measured(module.as_ref(), task, result?, started.elapsed(), cpu_time)
+    }
+    
+    // Like `process`, but gives up after `limit`. Modules compute without
+    // yielding, so the run gets a blocking thread of its own. Once out of
+    // time it is cancelled and given `CANCEL_GRACE` to stop, so callers
+    // limiting concurrency count it until then. A run that ignores the
+    // cancellation is left to finish on its own and its output is dropped.
+    pub async fn process_with_timeout(&self, task: &ReasoningTask, limit: Duration) -> Result<ReasoningResult> {
+        let module = self
+            .modules
+            .get(&task.task_type)
+            .ok_or_else(|| anyhow!("Unknown task type: {}", task.task_type))?;
+        let data = task.data.clone();
+        let cancel = CancelFlag::new();
+        
+        let started = Instant::now();
+        let run_module = module.clone();
+        let run_cancel = cancel.clone();
+        let runtime = Handle::current();
+        let mut run = task::spawn_blocking(move || {
+            run_cancel.scope(|| {
+                runtime.block_on(async {
+                    tokio::select! {
+                        output = cpu_timed(run_module.run(&data)) => Some(output),
+                        _ = run_cancel.cancelled() => None,
+                    }
+                })
+            })
+        });
+        
+        let joined = match timeout(limit, &mut run).await {
+            Ok(joined) => joined,
+            Err(_) => {
+                cancel.cancel();
+                if timeout(CANCEL_GRACE, run).await.is_err() {
+                    warn!("Task {} still running after it was cancelled", task.task_id);
+                }
+                return Err(TimedOut {
+                    task_id: task.task_id,
+                    limit,
+                }
+                .into());
             }
+        };
+        
+        match joined.map_err(|e| anyhow!("Task {} panicked: {}", task.task_id, e))? {
+            Some((result, cpu_time)) => self.measured(module.as_ref(), task, result?, started.elapsed(), cpu_time),
+            None => Err(anyhow!("Task {} was cancelled", task.task_id)),
+        }
+    }
+    
+    // Timing and cost of a run are filled in here rather than trusted from
+    // the module
+    fn measured(
+        &self,
+        module: &dyn ReasoningModule,
+        task: &ReasoningTask,
+        mut result: ReasoningResult,
+        elapsed: Duration,
+        cpu_time: Duration,
+    ) -> Result<ReasoningResult> {
+        if let Some(latency) = &self.latency {
+            latency.observe(&[&task.task_type], elapsed.as_secs_f64());
         }
+        
+        result.computation_time_ms = elapsed.as_millis() as u64;
+        result.cpu_time_ms = cpu_time.as_millis() as u64;
+        result.work_units = module.work_units(&task.data);
+        Ok(result)
     }
 }
@@ -0,0 +1,251 @@
+#[cfg(test)]
+mod tests {
+    use cognitive_engine::datastore::{
+        raw_cid, resolve_task_data, DataStoreChain, DataStoreError, GatewayDataStore, IpfsDataStore,
+        LocalDataStore, TaskDataStore, MAX_IPFS_BLOCK_BYTES,
+    };
+    use serde_json::{json, Value};
+    use shared::canonical::to_canonical_bytes;
+    use shared::hashing::{sha256, task_data_hash, to_hex};
+    use shared::types::ReasoningTask;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn payload() -> Value {
+        json!({ "symbol": "MATIC/USD", "prices": [1.2, 1.3, 1.25, 1.4, 1.35] })
+    }
+
+    fn task_for(data: &Value) -> ReasoningTask {
+        ReasoningTask {
+            task_id: 1,
+            task_type: "market_prediction".to_string(),
+            data: Value::Null,
+            data_hash: to_hex(&task_data_hash(data).unwrap()),
+            requester: "0x0".to_string(),
+            reward: 0,
+            deadline: 0,
+        }
+    }
+
+    // Minimal stand-in for an HTTP gateway and the Kubo RPC API. Objects are
+    // keyed by request path; `/api/v0/add` stores the uploaded file under its CID.
+    // Paths under `/down/` fail with 502, and `/wrong/api/v0/add` reports some
+    // other CID.
+    async fn spawn_server(objects: Arc<Mutex<HashMap<String, Vec<u8>>>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let objects = objects.clone();
+                
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    let (head_len, content_length) = loop {
+                        let n = socket.read(&mut buf).await.unwrap();
+                        request.extend_from_slice(&buf[..n]);
+                        if let Some(pos) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                            let head = String::from_utf8_lossy(&request[..pos]).to_lowercase();
+                            let length = head
+                                .lines()
+                                .find_map(|line| line.strip_prefix("content-length:"))
+                                .map(|len| len.trim().parse::<usize>().unwrap())
+                                .unwrap_or(0);
+                            break (pos + 4, length);
+                        }
+                    };
+                    while request.len() < head_len + content_length {
+                        let n = socket.read(&mut buf).await.unwrap();
+                        request.extend_from_slice(&buf[..n]);
+                    }
+                    
+                    let head = String::from_utf8_lossy(&request[..head_len]).to_string();
+                    let target = head.split_whitespace().nth(1).unwrap().to_string();
+                    let body = &request[head_len..];
+                    
+                    let (status, response) = if target.starts_with("/down/") {
+                        ("502 Bad Gateway", b"upstream unavailable".to_vec())
+                    } else if target.starts_with("/wrong/api/v0/add") {
+                        let cid = raw_cid(&sha256(b"something else"));
+                        ("200 OK", json!({ "Name": "data.json", "Hash": cid }).to_string().into_bytes())
+                    } else if target.starts_with("/api/v0/add") {
+                        // The file part sits between the first blank line and the closing boundary
+                        let start = body.windows(4).position(|w| w == b"\r\n\r\n").unwrap() + 4;
+                        let end = body.windows(4).rposition(|w| w == b"\r\n--").unwrap();
+                        let file = body[start..end].to_vec();
+                        let cid = raw_cid(&sha256(&file));
+                        objects.lock().unwrap().insert(format!("/api/v0/cat?arg={}", cid), file);
+                        ("200 OK", json!({ "Name": "data.json", "Hash": cid }).to_string().into_bytes())
+                    } else {
+                        match objects.lock().unwrap().get(&target) {
+                            Some(bytes) => ("200 OK", bytes.clone()),
+                            None if target.starts_with("/api/v0/") => {
+                                ("500 Internal Server Error", b"{\"Message\":\"block not found\"}".to_vec())
+                            }
+                            None => ("404 Not Found", Vec::new()),
+                        }
+                    };
+                    
+                    let header = format!(
+                        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        status,
+                        response.len()
+                    );
+                    socket.write_all(header.as_bytes()).await.unwrap();
+                    socket.write_all(&response).await.unwrap();
+                });
+            }
+        });
+        
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn test_raw_cid() {
+        // Well-known CID of the empty raw block
+        assert_eq!(raw_cid(&sha256(b"")), "bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku");
+    }
+
+    #[tokio::test]
+    async fn test_local_store_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LocalDataStore::new(dir.path().join("task-data"));
+        
+        let data_hash = store.put(&payload()).await.unwrap();
+        assert_eq!(data_hash, task_data_hash(&payload()).unwrap());
+        assert_eq!(store.fetch(&data_hash).await.unwrap(), payload());
+        
+        // Unknown hashes are reported as missing
+        let missing = store.fetch(&[0u8; 32]).await.unwrap_err();
+        assert!(matches!(missing, DataStoreError::NotFound(_)));
+        // It may still show up, unlike a payload that fails its hash
+        assert!(missing.is_transient());
+    }
+
+    #[tokio::test]
+    async fn test_tampered_data_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LocalDataStore::new(dir.path());
+        
+        let data_hash = store.put(&payload()).await.unwrap();
+        std::fs::write(store.path_for(&data_hash), br#"{"symbol":"MATIC/USD","prices":[9.9]}"#).unwrap();
+        
+        let err = store.fetch(&data_hash).await.unwrap_err();
+        assert!(matches!(err, DataStoreError::HashMismatch { .. }));
+        assert!(!err.is_transient());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_task_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LocalDataStore::new(dir.path());
+        store.put(&payload()).await.unwrap();
+        
+        let mut task = task_for(&payload());
+        resolve_task_data(&store, &mut task).await.unwrap();
+        assert_eq!(task.data, payload());
+        
+        // Payloads already attached to the task are left alone
+        let mut inline = task_for(&payload());
+        inline.data = json!({ "prices": [1.0] });
+        resolve_task_data(&store, &mut inline).await.unwrap();
+        assert_eq!(inline.data, json!({ "prices": [1.0] }));
+        
+        let mut bad = task_for(&payload());
+        bad.data_hash = "0x1234".to_string();
+        assert!(matches!(
+            resolve_task_data(&store, &mut bad).await.unwrap_err(),
+            DataStoreError::InvalidHash(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_gateway_store() {
+        let data_hash = task_data_hash(&payload()).unwrap();
+        let objects = Arc::new(Mutex::new(HashMap::new()));
+        objects.lock().unwrap().insert(
+            format!("/ipfs/{}", raw_cid(&data_hash)),
+            to_canonical_bytes(&payload()).unwrap(),
+        );
+        let base = spawn_server(objects.clone()).await;
+        
+        let store = GatewayDataStore::new(format!("{}/ipfs/{{cid}}", base));
+        assert_eq!(store.fetch(&data_hash).await.unwrap(), payload());
+        assert!(matches!(store.put(&payload()).await.unwrap_err(), DataStoreError::ReadOnly(_)));
+        
+        // Plain base URLs are addressed by the hex hash
+        let by_hash = GatewayDataStore::new(format!("{}/data/", base));
+        assert_eq!(by_hash.url_for(&data_hash), format!("{}/data/{}", base, hex::encode(data_hash)));
+        assert!(matches!(by_hash.fetch(&data_hash).await.unwrap_err(), DataStoreError::NotFound(_)));
+        
+        // A gateway serving something else for the hash is not trusted
+        objects.lock().unwrap().insert(
+            format!("/data/{}", hex::encode(data_hash)),
+            br#"{"prices":[1.0]}"#.to_vec(),
+        );
+        assert!(matches!(
+            by_hash.fetch(&data_hash).await.unwrap_err(),
+            DataStoreError::HashMismatch { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_ipfs_store_roundtrip() {
+        let base = spawn_server(Arc::new(Mutex::new(HashMap::new()))).await;
+        let store = IpfsDataStore::new(base.clone());
+        
+        assert!(matches!(
+            store.fetch(&task_data_hash(&payload()).unwrap()).await.unwrap_err(),
+            DataStoreError::NotFound(_)
+        ));
+        
+        let data_hash = store.put(&payload()).await.unwrap();
+        assert_eq!(store.fetch(&data_hash).await.unwrap(), payload());
+        
+        // Only payloads that fit one block can be found by their CID
+        let large = json!({ "prices": vec![1.5; MAX_IPFS_BLOCK_BYTES / 2] });
+        assert!(matches!(
+            store.put(&large).await.unwrap_err(),
+            DataStoreError::TooLargeForIpfs(_)
+        ));
+        
+        let wrong = IpfsDataStore::new(format!("{}/wrong", base));
+        assert!(matches!(
+            wrong.put(&payload()).await.unwrap_err(),
+            DataStoreError::CidMismatch { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_store_is_not_missing_data() {
+        let base = spawn_server(Arc::new(Mutex::new(HashMap::new()))).await;
+        let data_hash = task_data_hash(&payload()).unwrap();
+        
+        let ipfs = IpfsDataStore::new(format!("{}/down", base));
+        let gateway = GatewayDataStore::new(format!("{}/down/{{hash}}", base));
+        
+        for err in [ipfs.fetch(&data_hash).await.unwrap_err(), gateway.fetch(&data_hash).await.unwrap_err()] {
+            assert!(matches!(err, DataStoreError::Unavailable { .. }), "{}", err);
+            assert!(err.is_transient());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chain_falls_through() {
+        let empty = tempfile::tempdir().unwrap();
+        let filled = tempfile::tempdir().unwrap();
+        LocalDataStore::new(filled.path()).put(&payload()).await.unwrap();
+        
+        let chain = DataStoreChain::new()
+            .with_store(Arc::new(LocalDataStore::new(empty.path())))
+            .with_store(Arc::new(LocalDataStore::new(filled.path())));
+        
+        let mut task = task_for(&payload());
+        resolve_task_data(&chain, &mut task).await.unwrap();
+        assert_eq!(task.data, payload());
+    }
+}
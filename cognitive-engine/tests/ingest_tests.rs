@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod tests {
+    use cognitive_engine::ingest::WorkQueue;
+    use shared::types::ReasoningTask;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn task(task_id: u64) -> ReasoningTask {
+        ReasoningTask {
+            task_id,
+            task_type: "market_prediction".to_string(),
+            data: serde_json::Value::Null,
+            data_hash: String::new(),
+            requester: "0x0000000000000000000000000000000000000001".to_string(),
+            reward: 1_000,
+            deadline: 2_000,
+        }
+    }
+
+    #[test]
+    fn test_queue_dedupes_and_drops_claimed_tasks() {
+        let queue = WorkQueue::new();
+        
+        assert!(queue.push(task(1)));
+        assert!(queue.push(task(2)));
+        // Backfill and live events may both report the same task
+        assert!(!queue.push(task(1)));
+        assert_eq!(queue.len(), 2);
+        
+        // Claimed by someone else before we got to it
+        assert!(queue.remove(1));
+        assert!(!queue.remove(1));
+        
+        assert_eq!(queue.try_pop().unwrap().task_id, 2);
+        assert!(queue.is_empty());
+        
+        // Once popped the id may be queued again
+        assert!(queue.push(task(2)));
+    }
+
+    #[test]
+    fn test_queue_orders_by_reward_then_deadline() {
+        let queue = WorkQueue::new();
+        
+        let mut cheap = task(1);
+        cheap.reward = 10;
+        let mut late = task(2);
+        late.deadline = 9_000;
+        let mut valuable = task(3);
+        valuable.reward = 5_000;
+        let soon = task(4);
+        
+        for task in [cheap, late, valuable, soon] {
+            queue.push(task);
+        }
+        
+        let order: Vec<u64> = std::iter::from_fn(|| queue.try_pop()).map(|task| task.task_id).collect();
+        assert_eq!(order, vec![3, 4, 2, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_pop_waits_for_push() {
+        let queue = Arc::new(WorkQueue::new());
+        
+        let consumer = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.pop().await })
+        };
+        
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        queue.push(task(9));
+        
+        let popped = tokio::time::timeout(Duration::from_secs(1), consumer)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(popped.task_id, 9);
+    }
+}
@@ -0,0 +1,183 @@
+#[cfg(test)]
+mod tests {
+    use cognitive_engine::jobs::{JobState, JobStore};
+    use serde_json::json;
+    use shared::types::{ReasoningResult, ReasoningTask};
+
+    fn task(task_id: u64) -> ReasoningTask {
+        ReasoningTask {
+            task_id,
+            task_type: "market_prediction".to_string(),
+            data: serde_json::Value::Null,
+            data_hash: String::new(),
+            requester: "0x0000000000000000000000000000000000000001".to_string(),
+            reward: 1_000,
+            deadline: 4_000_000_000,
+        }
+    }
+
+    fn result() -> ReasoningResult {
+        ReasoningResult {
+            prediction: json!({ "predicted_price": 1.5 }),
+            confidence_score: 0.85,
+            computation_time_ms: 150,
+            ..ReasoningResult::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle() {
+        let jobs = JobStore::in_memory();
+        
+        assert!(jobs.discover(&task(1)).await.unwrap());
+        // Seen again through another source
+        assert!(!jobs.discover(&task(1)).await.unwrap());
+        assert_eq!(jobs.get(1).unwrap().state, JobState::Discovered);
+        
+        // Only one worker takes it up
+        assert!(jobs.mark_claimed(1, "0xc1".to_string()).await.is_err());
+        assert_eq!(jobs.take_up(&task(1)).await.unwrap(), Some(JobState::Discovered));
+        assert_eq!(jobs.take_up(&task(1)).await.unwrap(), None);
+        assert!(!jobs.discover(&task(1)).await.unwrap());
+        
+        // Results can only be recorded for claimed tasks
+        assert!(jobs.mark_computed(1, result(), "0x01".to_string()).await.is_err());
+        
+        jobs.mark_claimed(1, "0xc1".to_string()).await.unwrap();
+        jobs.mark_computed(1, result(), "0x01".to_string()).await.unwrap();
+        jobs.mark_submitted(1, "0xs1".to_string()).await.unwrap();
+        
+        // A second submission of the same job is refused
+        assert!(jobs.mark_submitted(1, "0xs2".to_string()).await.is_err());
+        
+        jobs.mark_confirmed(1).await.unwrap();
+        let job = jobs.get(1).unwrap();
+        assert_eq!(job.state, JobState::Confirmed);
+        assert_eq!(job.claim_tx.as_deref(), Some("0xc1"));
+        assert_eq!(job.submit_tx.as_deref(), Some("0xs1"));
+        assert_eq!(job.result_hash.as_deref(), Some("0x01"));
+        
+        // Finished jobs stay finished
+        assert!(jobs.mark_failed(1, "late").await.is_err());
+        assert!(jobs.unfinished().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dropped_submission_can_be_resent() {
+        let jobs = JobStore::in_memory();
+        jobs.take_up(&task(1)).await.unwrap();
+        jobs.mark_claimed(1, "0xc1".to_string()).await.unwrap();
+        jobs.mark_computed(1, result(), "0x01".to_string()).await.unwrap();
+        jobs.mark_submitted(1, "0xs1".to_string()).await.unwrap();
+        
+        jobs.mark_resubmittable(1).await.unwrap();
+        let job = jobs.get(1).unwrap();
+        assert_eq!(job.state, JobState::Computed);
+        assert!(job.submit_tx.is_none());
+        assert!(job.result.is_some());
+        
+        jobs.mark_submitted(1, "0xs2".to_string()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_deferred_job_is_taken_up_again() {
+        let jobs = JobStore::in_memory();
+        
+        // A task not discovered before is recorded as it is taken up
+        assert_eq!(jobs.take_up(&task(1)).await.unwrap(), Some(JobState::Discovered));
+        jobs.defer(1, "gas price unavailable").await.unwrap();
+        assert_eq!(jobs.get(1).unwrap().state, JobState::Deferred);
+        assert_eq!(jobs.unfinished().len(), 1);
+        
+        // Only one source gets it back
+        assert!(!jobs.discover(&task(1)).await.unwrap());
+        assert_eq!(jobs.take_up(&task(1)).await.unwrap(), Some(JobState::Deferred));
+        assert_eq!(jobs.take_up(&task(1)).await.unwrap(), None);
+        assert_eq!(jobs.get(1).unwrap().state, JobState::Admitting);
+        
+        // A claim mined before a restart is picked up without its transaction
+        jobs.recover_claim(1).await.unwrap();
+        let job = jobs.get(1).unwrap();
+        assert_eq!(job.state, JobState::Claimed);
+        assert!(job.claim_tx.is_none());
+        assert!(jobs.defer(1, "too late").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unfinished_jobs_survive_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jobs").join("engine-jobs.json");
+        
+        {
+            let jobs = JobStore::open(&path).unwrap();
+            jobs.discover(&task(1)).await.unwrap();
+            for task_id in 2..=5 {
+                jobs.take_up(&task(task_id)).await.unwrap();
+            }
+            
+            jobs.mark_claimed(2, "0xc2".to_string()).await.unwrap();
+            
+            jobs.mark_claimed(3, "0xc3".to_string()).await.unwrap();
+            jobs.mark_computed(3, result(), "0x03".to_string()).await.unwrap();
+            
+            jobs.mark_claimed(4, "0xc4".to_string()).await.unwrap();
+            jobs.mark_computed(4, result(), "0x04".to_string()).await.unwrap();
+            jobs.mark_submitted(4, "0xs4".to_string()).await.unwrap();
+            
+            jobs.mark_failed(5, "task data unavailable").await.unwrap();
+        }
+        
+        let jobs = JobStore::open(&path).unwrap();
+        assert_eq!(jobs.jobs().len(), 5);
+        
+        let unfinished: Vec<(u64, JobState)> = jobs
+            .unfinished()
+            .iter()
+            .map(|job| (job.task.task_id, job.state))
+            .collect();
+        assert_eq!(
+            unfinished,
+            vec![
+                (1, JobState::Discovered),
+                (2, JobState::Claimed),
+                (3, JobState::Computed),
+                (4, JobState::Submitted)
+            ]
+        );
+        
+        let computed = jobs.get(3).unwrap();
+        assert_eq!(computed.result.unwrap().prediction, result().prediction);
+        assert_eq!(jobs.get(5).unwrap().error.as_deref(), Some("task data unavailable"));
+        
+        // Already-known tasks are not picked up again as new work
+        assert!(!jobs.discover(&task(1)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_finished_jobs_are_pruned_after_deadline() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("engine-jobs.json");
+        let jobs = JobStore::open(&path).unwrap();
+        
+        let expired = ReasoningTask {
+            deadline: 2_000,
+            ..task(1)
+        };
+        jobs.take_up(&expired).await.unwrap();
+        jobs.take_up(&task(2)).await.unwrap();
+        jobs.take_up(&task(3)).await.unwrap();
+        
+        // Unfinished jobs stay whatever their deadline
+        jobs.discover(&task(4)).await.unwrap();
+        assert!(jobs.get(1).is_some());
+        
+        jobs.mark_failed(1, "deadline passed").await.unwrap();
+        jobs.mark_failed(2, "not admitted").await.unwrap();
+        assert!(jobs.get(1).is_none());
+        assert_eq!(jobs.get(2).unwrap().state, JobState::Failed);
+        
+        let reopened = JobStore::open(&path).unwrap();
+        let ids: Vec<u64> = reopened.jobs().iter().map(|job| job.task.task_id).collect();
+        assert_eq!(ids, vec![2, 3, 4]);
+    }
+}
@@ -0,0 +1,224 @@
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use cognitive_engine::metrics::{module_latency, task_type_label, UNKNOWN_TASK_TYPE};
+    use cognitive_engine::reasoning::{cancel, value_count, ModuleRegistry, ReasoningModule};
+    use cognitive_engine::tasks::{TaskProcessor, CANCEL_GRACE};
+    use serde_json::{json, Value};
+    use shared::types::{ReasoningResult, ReasoningTask};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    struct EchoModule;
+
+    #[async_trait]
+    impl ReasoningModule for EchoModule {
+        fn task_type(&self) -> &str {
+            "echo"
+        }
+        
+        fn input_schema(&self) -> Value {
+            json!({ "type": "object" })
+        }
+        
+        fn version(&self) -> &str {
+            "0.1.0"
+        }
+        
+        async fn run(&self, data: &Value) -> Result<ReasoningResult> {
+            Ok(ReasoningResult {
+                prediction: data.clone(),
+                confidence_score: 1.0,
+                ..ReasoningResult::default()
+            })
+        }
+    }
+
+    // Sleeps for `data.secs`, or spins without yielding if `data.spin` is
+    // set; a spin stops early when cancelled unless `data.stubborn` is set
+    struct SlowModule;
+
+    #[async_trait]
+    impl ReasoningModule for SlowModule {
+        fn task_type(&self) -> &str {
+            "slow"
+        }
+        
+        fn input_schema(&self) -> Value {
+            json!({ "type": "object" })
+        }
+        
+        fn version(&self) -> &str {
+            "0.1.0"
+        }
+        
+        async fn run(&self, data: &Value) -> Result<ReasoningResult> {
+            let duration = Duration::from_secs_f64(data["secs"].as_f64().unwrap_or(0.0));
+            if data["spin"].as_bool().unwrap_or(false) {
+                let started = Instant::now();
+                let stubborn = data["stubborn"].as_bool().unwrap_or(false);
+                while started.elapsed() < duration && (stubborn || !cancel::requested()) {
+                    std::hint::spin_loop();
+                }
+            } else {
+                tokio::time::sleep(duration).await;
+            }
+            
+            // Claims far less time than it took
+            Ok(ReasoningResult {
+                prediction: data.clone(),
+                confidence_score: 1.0,
+                computation_time_ms: 1,
+                ..ReasoningResult::default()
+            })
+        }
+    }
+
+    fn task(task_type: &str, data: Value) -> ReasoningTask {
+        ReasoningTask {
+            task_id: 1,
+            task_type: task_type.to_string(),
+            data,
+            data_hash: String::new(),
+            requester: "0x0000000000000000000000000000000000000001".to_string(),
+            reward: 1_000,
+            deadline: 2_000,
+        }
+    }
+
+    #[test]
+    fn test_builtin_modules_registered() {
+        let registry = ModuleRegistry::with_builtin_modules();
+        
+        let task_types: Vec<&str> = registry.task_types().collect();
+        assert_eq!(task_types, vec!["anomaly_detection", "market_prediction", "risk_scoring", "technical_indicators"]);
+        
+        for task_type in task_types {
+            let module = registry.get(task_type).unwrap();
+            assert_eq!(module.task_type(), task_type);
+            assert_eq!(module.input_schema()["type"], "object");
+        }
+    }
+
+    #[test]
+    fn test_duplicate_registration_rejected() {
+        let mut registry = ModuleRegistry::new();
+        
+        registry.register(EchoModule).unwrap();
+        assert!(registry.register(EchoModule).is_err());
+    }
+
+    #[test]
+    fn test_retain_enabled_modules() {
+        let mut registry = ModuleRegistry::with_builtin_modules();
+        
+        let unknown = ["market_prediction".to_string(), "market_predicton".to_string()].into();
+        assert!(registry.retain(&unknown).is_err());
+        assert_eq!(registry.task_types().count(), 4);
+        
+        registry.retain(&["risk_scoring".to_string()].into()).unwrap();
+        assert_eq!(registry.task_types().collect::<Vec<_>>(), vec!["risk_scoring"]);
+    }
+
+    #[test]
+    fn test_metric_labels_are_module_names() {
+        let registry = ModuleRegistry::with_builtin_modules();
+        
+        assert_eq!(task_type_label(&registry, "risk_scoring"), "risk_scoring");
+        // Any string can be posted on chain as a task type
+        assert_eq!(task_type_label(&registry, "risk_scoring-7f3a"), UNKNOWN_TASK_TYPE);
+    }
+
+    #[tokio::test]
+    async fn test_processor_dispatches_registered_module() {
+        let mut processor = TaskProcessor::new();
+        processor.register(EchoModule).unwrap();
+        
+        let result = processor.process(&task("echo", json!({ "x": 1 }))).await.unwrap();
+        assert_eq!(result.prediction, json!({ "x": 1 }));
+        
+        let result = processor
+            .process(&task("market_prediction", json!({ "prices": [1.0, 2.0, 3.0] })))
+            .await
+            .unwrap();
+        assert!(result.prediction["predicted_price"].is_number());
+        
+        assert!(processor.process(&task("unknown", json!({}))).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_processor_measures_runs() {
+        let mut processor = TaskProcessor::new();
+        processor.register(SlowModule).unwrap();
+        
+        // Sleeping takes wall-clock time but hardly any CPU
+        let sleeping = json!({ "secs": 0.2 });
+        let result = processor.process(&task("slow", sleeping.clone())).await.unwrap();
+        assert!(result.computation_time_ms >= 200);
+        assert!(result.cpu_time_ms < 100);
+        
+        let spinning = json!({ "secs": 0.2, "spin": true });
+        let result = processor.process(&task("slow", spinning.clone())).await.unwrap();
+        assert!(result.computation_time_ms >= 200);
+        assert!(result.cpu_time_ms >= 100);
+        
+        // Work units depend on the input alone, however long the run took
+        assert_eq!(result.work_units, 2);
+        let result = processor.process(&task("slow", sleeping)).await.unwrap();
+        assert_eq!(result.work_units, 1);
+        
+        let prices = json!({ "symbol": "MATIC/USD", "prices": [1.2, 1.3, 1.25] });
+        assert_eq!(value_count(&prices), 4);
+        // Built-in modules count their own operations instead: Holt's 81
+        // smoothing pairs over 3 prices plus one step, then a backtest fit
+        // on the first 2
+        let result = processor.process(&task("market_prediction", prices)).await.unwrap();
+        assert_eq!(result.work_units, (81 * 3 + 1) + (81 * 2 + 1));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_process_with_timeout() {
+        let latency = Arc::new(module_latency());
+        let mut processor = TaskProcessor::new().with_latency(latency.clone());
+        processor.register(SlowModule).unwrap();
+        let limit = Duration::from_millis(100);
+        
+        let result = processor
+            .process_with_timeout(&task("slow", json!({ "secs": 0.01 })), limit)
+            .await
+            .unwrap();
+        assert!((10..100).contains(&result.computation_time_ms));
+        
+        let started = Instant::now();
+        let err = processor
+            .process_with_timeout(&task("slow", json!({ "secs": 30 })), limit)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        
+        // The limit also holds for modules that never yield but check for
+        // cancellation
+        let started = Instant::now();
+        let err = processor
+            .process_with_timeout(&task("slow", json!({ "secs": 1, "spin": true })), limit)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+        assert!(started.elapsed() < Duration::from_millis(900));
+        
+        // A run that ignores cancellation is only waited for a grace period
+        let started = Instant::now();
+        let err = processor
+            .process_with_timeout(&task("slow", json!({ "secs": 2.5, "spin": true, "stubborn": true })), limit)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+        assert!(started.elapsed() >= limit + CANCEL_GRACE);
+        assert!(started.elapsed() < Duration::from_secs(2));
+        
+        // Only runs that returned are timed
+        assert_eq!(latency.count(&["slow"]), 1);
+    }
+}
@@ -1,11 +1,26 @@
 #[cfg(test)]
 mod tests {
     use cognitive_engine::reasoning::{
+        forecast,
+        indicators,
+        market_data,
         market_prediction::MarketPredictor,
         anomaly_detection::AnomalyDetector,
         risk_scoring::RiskScorer,
+        technical_indicators::TechnicalAnalyzer,
+        ReasoningModule,
     };
     use serde_json::json;
+    use shared::types::{Candle, ForecastModel, ForecastStep, Gap, Interval, MarketPredictionInput, Trend};
+
+    fn input(prices: Vec<f64>, model: ForecastModel, horizon: u64) -> MarketPredictionInput {
+        MarketPredictionInput {
+            prices,
+            model,
+            horizon,
+            ..MarketPredictionInput::default()
+        }
+    }
 
     #[tokio::test]
     async fn test_market_prediction() {
@@ -22,6 +37,178 @@ mod tests {
         assert!(result.prediction["predicted_price"].is_number());
     }
 
+    #[test]
+    fn test_forecast_models() {
+        // A straight line up by 1 per step
+        let line: Vec<f64> = (1..=20).map(f64::from).collect();
+        
+        let fit = forecast::fit(ForecastModel::Holt, &line, 3);
+        assert_eq!(fit.path, vec![21.0, 22.0, 23.0]);
+        assert_eq!(fit.slope, 1.0);
+        
+        let fit = forecast::fit(ForecastModel::Arima, &line, 2);
+        assert_eq!(fit.path, vec![21.0, 22.0]);
+        
+        // Moving averages lag behind the last price and carry no trend forward
+        let fit = forecast::fit(ForecastModel::Sma, &line, 2);
+        assert_eq!(fit.path, vec![17.0, 17.0]);
+        assert_eq!(fit.slope, 1.0);
+        let fit = forecast::fit(ForecastModel::Ema, &line, 1);
+        assert!(fit.path[0] < 20.0 && fit.slope > 0.0);
+        
+        // Constant growth of 10% per step
+        let growth: Vec<f64> = (0..10).map(|step| 100.0 * 1.1f64.powi(step)).collect();
+        let fit = forecast::fit(ForecastModel::LinearRegression, &growth, 2);
+        assert!((fit.path[1] - 100.0 * 1.1f64.powi(11)).abs() < 1e-9);
+        assert!((fit.slope - growth[9] * 0.1).abs() < 1e-9);
+        
+        // Alternating changes are picked up by the autoregressive term
+        let zigzag = [10.0, 12.0, 11.0, 13.0, 12.0, 14.0, 13.0, 15.0];
+        let fit = forecast::fit(ForecastModel::Arima, &zigzag, 2);
+        assert!(fit.path[0] < 15.0 && fit.path[1] > fit.path[0]);
+    }
+
+    #[test]
+    fn test_market_prediction_output() {
+        let predictor = MarketPredictor::new();
+        let line: Vec<f64> = (1..=20).map(f64::from).collect();
+        
+        let output = predictor.forecast(&input(line.clone(), ForecastModel::Holt, 3));
+        assert_eq!(output.predicted_price, 23.0);
+        assert_eq!(output.trend, Trend::Bullish);
+        assert_eq!(output.forecast.len(), 3);
+        assert_eq!(output.forecast[2].price, output.predicted_price);
+        assert!(output.forecast.iter().all(|step| step.lower <= step.price && step.price <= step.upper));
+        
+        // The fit is perfect once past the start, so confidence is high
+        assert!(output.backtest.samples > 0);
+        assert!(output.confidence > 0.8);
+        
+        // A model that ignores the trend misses by more, with wider intervals
+        let sma = predictor.forecast(&input(line.clone(), ForecastModel::Sma, 3));
+        assert!(sma.backtest.mae > output.backtest.mae);
+        assert!(sma.confidence < output.confidence);
+        let width = |step: &ForecastStep| step.upper - step.lower;
+        assert!(width(&sma.forecast[2]) > width(&output.forecast[2]));
+        assert!(width(&sma.forecast[2]) > width(&sma.forecast[0]));
+        
+        let falling: Vec<f64> = line.iter().rev().copied().collect();
+        assert_eq!(predictor.forecast(&input(falling, ForecastModel::Arima, 1)).trend, Trend::Bearish);
+        let flat = vec![5.0; 10];
+        assert_eq!(predictor.forecast(&input(flat, ForecastModel::Ema, 1)).trend, Trend::Sideways);
+        
+        // Nothing to backtest on a single price
+        let output = predictor.forecast(&input(vec![5.0], ForecastModel::Sma, 2));
+        assert_eq!(output.backtest.samples, 0);
+        assert_eq!(output.confidence, 0.0);
+        assert_eq!(output.forecast[1].lower, 5.0);
+        
+        // Horizons past what the history can test are still covered
+        let output = predictor.forecast(&input(vec![1.0, 2.0, 1.5, 2.5], ForecastModel::Ema, 10));
+        assert!(output.forecast.iter().all(|step| step.upper > step.price));
+    }
+
+    fn candle(timestamp: u64, open: f64, close: f64, volume: f64) -> Candle {
+        Candle {
+            timestamp,
+            open,
+            high: open.max(close) + 0.5,
+            low: open.min(close) - 0.5,
+            close,
+            volume,
+        }
+    }
+
+    #[test]
+    fn test_market_data() {
+        let hour = Interval::from_secs(3_600).unwrap();
+        let candles = [candle(3_600, 1.0, 2.0, 5.0), candle(7_200, 2.0, 3.0, 5.0), candle(18_000, 4.0, 5.0, 5.0)];
+        
+        // The two missing hours are carried at the last close
+        let (filled, gaps) = market_data::fill_gaps(&candles, hour);
+        assert_eq!(gaps, vec![Gap { from: 10_800, to: 14_400, missing: 2 }]);
+        assert_eq!(filled.len(), 5);
+        assert_eq!(filled[2], Candle { timestamp: 10_800, open: 3.0, high: 3.0, low: 3.0, close: 3.0, volume: 0.0 });
+        
+        // Buckets start on multiples of two hours: [1h], [2h, 3h], [4h, 5h]
+        let merged = market_data::resample(&filled, Interval::from_secs(7_200).unwrap());
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[1], Candle { timestamp: 7_200, open: 2.0, high: 3.5, low: 1.5, close: 3.0, volume: 5.0 });
+        assert_eq!(merged[2], Candle { timestamp: 14_400, open: 3.0, high: 5.5, low: 3.0, close: 5.0, volume: 5.0 });
+    }
+
+    #[tokio::test]
+    async fn test_basket_prediction() {
+        let predictor = MarketPredictor::new();
+        let candles = |start: f64, step: f64| -> Vec<Candle> {
+            (0..20)
+                .map(|n| candle(n * 60, start + step * n as f64, start + step * (n + 1) as f64, 1.0))
+                .collect()
+        };
+        
+        let mut steady = candles(10.0, 1.0);
+        steady.remove(5);
+        let data = json!({
+            "assets": [
+                { "symbol": "UP", "interval": "1m", "candles": steady },
+                { "symbol": "DOWN", "interval": "1m", "candles": candles(100.0, -1.0) }
+            ],
+            "horizon": 2
+        });
+        
+        let result = predictor.predict(&data).await.unwrap();
+        let assets = result.prediction["assets"].as_array().unwrap();
+        assert_eq!(assets.len(), 2);
+        
+        assert_eq!(assets[0]["symbol"], "UP");
+        assert_eq!(assets[0]["interval"], "1m");
+        assert_eq!(assets[0]["candles"], 20);
+        assert_eq!(assets[0]["gaps"], json!([{ "from": 300, "to": 300, "missing": 1 }]));
+        assert_eq!(assets[0]["trend"], "bullish");
+        assert_eq!(assets[0]["forecast"][1]["timestamp"], 21 * 60);
+        assert_eq!(assets[1]["trend"], "bearish");
+        assert!(assets[1]["gaps"].as_array().unwrap().is_empty());
+        
+        // The filled gap costs the first asset some accuracy, and the basket
+        // reports its weakest member
+        let confidence: Vec<f64> = assets.iter().map(|asset| asset["confidence"].as_f64().unwrap()).collect();
+        assert!(confidence[0] < confidence[1]);
+        assert_eq!(result.confidence_score, confidence[0]);
+        
+        let data = json!({
+            "assets": [{ "symbol": "UP", "interval": "1m", "candles": candles(10.0, 1.0) }],
+            "resample": "5m"
+        });
+        let result = predictor.predict(&data).await.unwrap();
+        let asset = &result.prediction["assets"][0];
+        assert_eq!((asset["interval"].as_str(), asset["candles"].as_u64()), (Some("5m"), Some(4)));
+        assert_eq!(asset["forecast"][0]["timestamp"], 20 * 60);
+    }
+
+    #[test]
+    fn test_work_units() {
+        let predictor = MarketPredictor::new();
+        let prices: Vec<f64> = (0..40).map(|n| 1.0 + 0.01 * n as f64).collect();
+        let work = |model, horizon| predictor.work(&input(prices.clone(), model, horizon));
+        
+        // The same history costs more with a heavier model or a longer forecast
+        assert!(work(ForecastModel::Sma, 1) < work(ForecastModel::Arima, 1));
+        assert!(work(ForecastModel::Arima, 1) < work(ForecastModel::Holt, 1));
+        assert!(work(ForecastModel::Holt, 1) < work(ForecastModel::Holt, 5));
+        assert!(predictor.work(&input(prices[..10].to_vec(), ForecastModel::Holt, 1)) < work(ForecastModel::Holt, 1));
+        
+        let data = json!({ "prices": prices, "model": "arima" });
+        assert_eq!(predictor.work_units(&data), work(ForecastModel::Arima, 1));
+        
+        // Indicators cost what they compute
+        let analyzer = TechnicalAnalyzer::new();
+        let cost = |indicators| analyzer.work_units(&json!({ "prices": prices, "indicators": indicators }));
+        assert!(cost(json!([{ "name": "rsi" }])) < cost(json!([{ "name": "rsi" }, { "name": "macd" }])));
+        let bollinger = |period: u64| cost(json!([{ "name": "bollinger", "period": period }]));
+        assert!(bollinger(5) < bollinger(20));
+        assert_eq!(cost(json!([{ "name": "rsi" }])), 40 + 3 * 40);
+    }
+
     #[tokio::test]
     async fn test_anomaly_detection() {
         let detector = AnomalyDetector::new();
@@ -40,6 +227,7 @@ mod tests {
     }
 
     #[tokio::test]
+    #[allow(clippy::manual_range_contains)]
     async fn test_risk_scoring() {
         let scorer = RiskScorer::new();
         
@@ -58,4 +246,95 @@ mod tests {
         let risk_level = result.prediction["risk_level"].as_str().unwrap();
         assert!(["low", "medium", "high", "critical"].contains(&risk_level));
     }
+
+    #[test]
+    fn test_indicators() {
+        let some = |values: &[f64]| -> Vec<Option<f64>> { values.iter().copied().map(Some).collect() };
+        
+        // Alternating moves: even at first, then leaning on the last move
+        let rsi = indicators::rsi(&[1.0, 2.0, 1.0, 2.0, 1.0], 2);
+        assert_eq!(rsi, [vec![None, None], some(&[50.0, 75.0, 37.5])].concat());
+        assert_eq!(indicators::rsi(&[1.0, 2.0, 3.0], 2)[2], Some(100.0));
+        assert_eq!(indicators::rsi(&[1.0, 1.0, 1.0], 2)[2], Some(50.0));
+        
+        // On a straight line the fast average leads the slow one by a constant
+        let line: Vec<f64> = (1..=8).map(f64::from).collect();
+        assert_eq!(indicators::ema(&line[..5], 3), [vec![None, None], some(&[2.0, 3.0, 4.0])].concat());
+        let macd = indicators::macd(&line, 3, 5, 2);
+        assert_eq!(macd.macd, [vec![None; 4], some(&[1.0; 4])].concat());
+        assert_eq!(macd.signal, [vec![None; 5], some(&[1.0; 3])].concat());
+        assert_eq!(macd.histogram[7], Some(0.0));
+        
+        let bands = indicators::bollinger(&[1.0, 2.0, 3.0], 3, 2.0);
+        assert_eq!(bands.middle[2], Some(2.0));
+        assert_eq!(bands.upper[2], Some(2.0 + 2.0 * (2.0f64 / 3.0).sqrt()));
+        assert_eq!(bands.lower[1], None);
+        
+        // The third candle gaps up, so its true range reaches back to the
+        // previous close
+        let candles = [candle(0, 10.0, 10.0, 1.0), candle(60, 10.0, 10.0, 3.0), candle(120, 13.0, 13.0, 0.0)];
+        assert_eq!(indicators::atr(&candles, 2), vec![None, Some(1.0), Some(2.25)]);
+        assert_eq!(indicators::vwap(&candles, None), vec![Some(10.0), Some(10.0), Some(10.0)]);
+        assert_eq!(indicators::vwap(&candles[2..], None), vec![None]);
+        assert_eq!(indicators::vwap(&candles, Some(2))[0], None);
+        let candles = [candle(0, 12.0, 6.0, 1.0), candle(60, 9.0, 3.0, 4.0), candle(120, 15.0, 12.0, 2.0)];
+        assert_eq!(indicators::vwap(&candles, None)[2], indicators::vwap(&candles, Some(3))[2]);
+        
+        let growth: Vec<f64> = (0..5).map(|n| 100.0 * 1.1f64.powi(n)).collect();
+        assert!(indicators::volatility(&growth, 3, None)[4].unwrap() < 1e-12);
+        let swings = indicators::volatility(&[100.0, 110.0, 100.0], 2, Some(365)).pop().unwrap().unwrap();
+        assert!((swings - 1.1f64.ln() * 2f64.sqrt() * 365f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[tokio::test]
+    async fn test_technical_indicators() {
+        let analyzer = TechnicalAnalyzer::new();
+        let candles: Vec<Candle> = (0..40).map(|n| candle(n * 60, 10.0 + n as f64, 11.0 + n as f64, 100.0)).collect();
+        
+        let data = json!({
+            "symbol": "MATIC/USD",
+            "candles": candles,
+            "indicators": [
+                { "name": "rsi", "period": 7 },
+                { "name": "rsi" },
+                { "name": "macd" },
+                { "name": "bollinger", "period": 10, "std_dev": 1.5 },
+                { "name": "atr", "period": 5 },
+                { "name": "vwap", "period": 3 },
+                { "name": "volatility", "period": 10, "periods_per_year": 525600 }
+            ]
+        });
+        
+        let result = analyzer.analyze(&data).await.unwrap();
+        assert_eq!(result.confidence_score, 1.0);
+        
+        let output = &result.prediction;
+        assert_eq!(output["symbol"], "MATIC/USD");
+        let indicators = output["indicators"].as_array().unwrap();
+        assert_eq!(indicators.len(), 7);
+        
+        // Parameters are echoed with their defaults filled in
+        assert_eq!(indicators[0]["name"], "rsi");
+        assert_eq!(indicators[1]["period"], 14);
+        assert_eq!((indicators[2]["fast"].as_u64(), indicators[2]["slow"].as_u64()), (Some(12), Some(26)));
+        
+        assert_eq!(indicators[0]["latest"]["rsi"], 100.0);
+        assert_eq!(indicators[0]["series"]["rsi"][6], json!(null));
+        assert_eq!(indicators[0]["series"]["rsi"].as_array().unwrap().len(), 40);
+        assert!(indicators[2]["latest"]["macd"].as_f64().unwrap() > 0.0);
+        assert_eq!(indicators[4]["latest"]["atr"], 2.0);
+        let keys: Vec<&String> = indicators[3]["latest"].as_object().unwrap().keys().collect();
+        assert_eq!(keys, ["lower", "middle", "upper"]);
+        
+        // The same input always gives the same output
+        assert_eq!(analyzer.analyze(&data).await.unwrap().prediction, result.prediction);
+        
+        // Volume-based indicators need candles
+        let closes: Vec<f64> = candles.iter().map(|candle| candle.close).collect();
+        let err = analyzer
+            .analyze(&json!({ "prices": closes, "indicators": [{ "name": "vwap" }] }))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("vwap needs candles"));
+    }
 }
@@ -0,0 +1,198 @@
+#[cfg(test)]
+mod tests {
+    use cognitive_engine::cursor::BlockCursor;
+    use cognitive_engine::jobs::{JobState, JobStore};
+    use cognitive_engine::registry::{OnChainTask, RegistryClient, RegistryError};
+    use ethers::prelude::*;
+    use serde_json::{json, Value};
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn open_task() -> OnChainTask {
+        OnChainTask {
+            task_id: U256::from(7),
+            task_type: "market_prediction".to_string(),
+            data_hash: [0xab; 32],
+            requester: Address::repeat_byte(0x11),
+            reward: ethers::utils::parse_ether("0.01").unwrap(),
+            deadline: U256::from(2_000),
+            completed: false,
+            assigned_node: Address::zero(),
+        }
+    }
+
+    #[test]
+    fn test_open_task_filter() {
+        let task = open_task();
+        assert!(task.is_open(1_000));
+        
+        // Deadline reached
+        assert!(!task.is_open(2_000));
+        
+        let mut completed = open_task();
+        completed.completed = true;
+        assert!(!completed.is_open(1_000));
+        
+        let mut assigned = open_task();
+        assigned.assigned_node = Address::repeat_byte(0x22);
+        assert!(!assigned.is_open(1_000));
+        
+        // Unknown ids read back as a zeroed struct
+        let mut missing = open_task();
+        missing.task_id = U256::zero();
+        assert!(!missing.is_open(1_000));
+    }
+
+    #[test]
+    fn test_into_reasoning_task() {
+        let task = open_task().into_reasoning_task();
+        
+        assert_eq!(task.task_id, 7);
+        assert_eq!(task.task_type, "market_prediction");
+        assert_eq!(task.data_hash, format!("0x{}", "ab".repeat(32)));
+        assert_eq!(task.reward, 10_000_000_000_000_000);
+        assert_eq!(task.deadline, 2_000);
+        assert!(task.data.is_null());
+    }
+
+    #[test]
+    fn test_revert_reasons() {
+        assert!(matches!(
+            RegistryError::from_revert(3, "Task already assigned"),
+            RegistryError::AlreadyAssigned(3)
+        ));
+        assert!(matches!(
+            RegistryError::from_revert(3, "Deadline passed"),
+            RegistryError::DeadlinePassed(3)
+        ));
+        assert!(matches!(
+            RegistryError::from_revert(3, "Node not active"),
+            RegistryError::NodeInactive(_)
+        ));
+        assert!(matches!(
+            RegistryError::from_revert(3, "Transfer failed"),
+            RegistryError::Reverted { task_id: 3, .. }
+        ));
+    }
+
+    #[test]
+    fn test_block_cursor_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cursor = BlockCursor::new(dir.path().join("state").join("cursor.json"));
+        
+        assert_eq!(cursor.load().unwrap(), None);
+        
+        cursor.save(42).unwrap();
+        assert_eq!(cursor.load().unwrap(), Some(42));
+        
+        cursor.save(43).unwrap();
+        assert_eq!(BlockCursor::new(cursor.path()).load().unwrap(), Some(43));
+    }
+
+    fn block(value: &Value) -> u64 {
+        u64::from_str_radix(value.as_str().unwrap().trim_start_matches("0x"), 16).unwrap()
+    }
+
+    // JSON-RPC endpoint that has no logs and records the block range of
+    // every eth_getLogs call
+    async fn spawn_rpc(ranges: Arc<Mutex<Vec<(u64, u64)>>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let ranges = ranges.clone();
+                
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    let (head_len, content_length) = loop {
+                        let n = socket.read(&mut buf).await.unwrap();
+                        request.extend_from_slice(&buf[..n]);
+                        if let Some(pos) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                            let head = String::from_utf8_lossy(&request[..pos]).to_lowercase();
+                            let length = head
+                                .lines()
+                                .find_map(|line| line.strip_prefix("content-length:"))
+                                .map(|len| len.trim().parse::<usize>().unwrap())
+                                .unwrap_or(0);
+                            break (pos + 4, length);
+                        }
+                    };
+                    while request.len() < head_len + content_length {
+                        let n = socket.read(&mut buf).await.unwrap();
+                        request.extend_from_slice(&buf[..n]);
+                    }
+                    
+                    let call: Value = serde_json::from_slice(&request[head_len..]).unwrap();
+                    assert_eq!(call["method"], "eth_getLogs");
+                    let filter = &call["params"][0];
+                    ranges
+                        .lock()
+                        .unwrap()
+                        .push((block(&filter["fromBlock"]), block(&filter["toBlock"])));
+                    
+                    let body = json!({ "jsonrpc": "2.0", "id": call["id"], "result": [] }).to_string();
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    socket.write_all(response.as_bytes()).await.unwrap();
+                });
+            }
+        });
+        
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_log_scans_are_chunked() {
+        let ranges = Arc::new(Mutex::new(Vec::new()));
+        let url = spawn_rpc(ranges.clone()).await;
+        
+        let provider = Arc::new(Provider::<Http>::try_from(url).unwrap());
+        let registry = RegistryClient::new(Address::repeat_byte(0x42), provider).with_max_block_range(1_000);
+        
+        let assigned = registry
+            .task_assignments(100, 2_599, Some(Address::repeat_byte(0x22)))
+            .await
+            .unwrap();
+        assert!(assigned.is_empty());
+        assert_eq!(*ranges.lock().unwrap(), vec![(100, 1_099), (1_100, 2_099), (2_100, 2_599)]);
+        
+        ranges.lock().unwrap().clear();
+        assert!(registry.task_created_ids(5, 5).await.unwrap().is_empty());
+        assert_eq!(*ranges.lock().unwrap(), vec![(5, 5)]);
+    }
+
+    // Needs a local node with CognitiveRegistry deployed:
+    //   npx hardhat node && npm run deploy -- --network localhost
+    //   POLYGON_RPC_URL=http://localhost:8545 REGISTRY_ADDRESS=0x... cargo test -- --ignored
+    #[tokio::test]
+    #[ignore]
+    async fn test_fetch_open_tasks_local_node() {
+        let rpc_url = std::env::var("POLYGON_RPC_URL").unwrap();
+        let registry_address: Address = std::env::var("REGISTRY_ADDRESS").unwrap().parse().unwrap();
+        
+        let provider = Arc::new(Provider::<Http>::try_from(rpc_url).unwrap());
+        let registry = RegistryClient::new(registry_address, provider);
+        
+        let dir = tempfile::tempdir().unwrap();
+        let cursor = BlockCursor::new(dir.path().join("cursor.json"));
+        
+        let jobs = JobStore::in_memory();
+        
+        let tasks = registry.fetch_open_tasks(&cursor, &jobs, 0).await.unwrap();
+        for task in &tasks {
+            assert!(task.task_id > 0);
+            assert!(task.data_hash.starts_with("0x"));
+            assert_eq!(jobs.get(task.task_id).unwrap().state, JobState::Discovered);
+        }
+        
+        // A second scan starts after the saved cursor
+        assert!(cursor.load().unwrap().is_some());
+    }
+}
@@ -0,0 +1,170 @@
+#[cfg(test)]
+mod tests {
+    use cognitive_engine::signer::{NodeSigner, RemoteSigner, SignerError};
+    use ethers::signers::{LocalWallet, Signer};
+    use ethers::types::transaction::eip2718::TypedTransaction;
+    use ethers::types::{Address, Bytes, TransactionRequest, U256};
+    use ethers::utils::rlp::Rlp;
+    use serde_json::{json, Value};
+    use shared::config::SignerConfig;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const DEV_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    fn wallet() -> LocalWallet {
+        DEV_KEY.parse::<LocalWallet>().unwrap().with_chain_id(31337u64)
+    }
+
+    // Minimal Web3Signer-style JSON-RPC endpoint holding `wallet`. With
+    // `tamper` set it signs a different nonce than it was asked to.
+    async fn spawn_signer(wallet: LocalWallet, tamper: bool) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let wallet = wallet.clone();
+                
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    let (head_len, content_length) = loop {
+                        let n = socket.read(&mut buf).await.unwrap();
+                        request.extend_from_slice(&buf[..n]);
+                        if let Some(pos) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                            let head = String::from_utf8_lossy(&request[..pos]).to_lowercase();
+                            let length = head
+                                .lines()
+                                .find_map(|line| line.strip_prefix("content-length:"))
+                                .map(|len| len.trim().parse::<usize>().unwrap())
+                                .unwrap_or(0);
+                            break (pos + 4, length);
+                        }
+                    };
+                    while request.len() < head_len + content_length {
+                        let n = socket.read(&mut buf).await.unwrap();
+                        request.extend_from_slice(&buf[..n]);
+                    }
+                    
+                    let call: Value = serde_json::from_slice(&request[head_len..]).unwrap();
+                    let params = &call["params"];
+                    let result = match call["method"].as_str().unwrap() {
+                        "eth_accounts" => json!([wallet.address()]),
+                        "eth_sign" => {
+                            let message: Bytes = serde_json::from_value(params[1].clone()).unwrap();
+                            json!(format!("0x{}", wallet.sign_message(&message).await.unwrap()))
+                        }
+                        "eth_signTransaction" => {
+                            let mut tx: TypedTransaction = serde_json::from_value(params[0].clone()).unwrap();
+                            if tamper {
+                                tx.set_nonce(tx.nonce().copied().unwrap_or_default() + 1);
+                            }
+                            let signature = wallet.sign_transaction(&tx).await.unwrap();
+                            json!(tx.rlp_signed(&signature))
+                        }
+                        _ => Value::Null,
+                    };
+                    
+                    let response = json!({ "jsonrpc": "2.0", "id": call["id"], "result": result }).to_string();
+                    let header = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        response.len()
+                    );
+                    socket.write_all(header.as_bytes()).await.unwrap();
+                    socket.write_all(response.as_bytes()).await.unwrap();
+                });
+            }
+        });
+        
+        format!("http://{}", addr)
+    }
+
+    fn transaction() -> TypedTransaction {
+        TransactionRequest::new()
+            .to(Address::repeat_byte(0x42))
+            .value(U256::from(1_000))
+            .nonce(7)
+            .gas(21_000)
+            .gas_price(1_000_000_000)
+            .chain_id(31337)
+            .into()
+    }
+
+    #[tokio::test]
+    async fn test_remote_signer() {
+        let url = spawn_signer(wallet(), false).await;
+        
+        assert!(matches!(
+            RemoteSigner::connect(&url, Some(Address::repeat_byte(1))).await,
+            Err(SignerError::UnknownAccount(_))
+        ));
+        
+        let signer = RemoteSigner::connect(&url, None).await.unwrap().with_chain_id(31337u64);
+        assert_eq!(signer.address(), wallet().address());
+        
+        let signature = signer.sign_message("hello").await.unwrap();
+        assert_eq!(signature, wallet().sign_message("hello").await.unwrap());
+        
+        // The signature completes the transaction as the caller built it
+        let tx = transaction();
+        let signature = signer.sign_transaction(&tx).await.unwrap();
+        let raw = tx.rlp_signed(&signature);
+        let (decoded, decoded_signature) = TypedTransaction::decode_signed(&Rlp::new(&raw)).unwrap();
+        assert_eq!(decoded.nonce(), Some(&U256::from(7)));
+        assert_eq!(decoded_signature.recover(decoded.sighash()).unwrap(), wallet().address());
+    }
+
+    #[tokio::test]
+    async fn test_remote_signature_over_other_payload_is_refused() {
+        let url = spawn_signer(wallet(), true).await;
+        let signer = RemoteSigner::connect(&url, None).await.unwrap().with_chain_id(31337u64);
+        
+        assert!(matches!(
+            signer.sign_transaction(&transaction()).await,
+            Err(SignerError::WrongSignature(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_node_signer_from_config() {
+        let local = NodeSigner::from_config(
+            &SignerConfig {
+                private_key: Some(DEV_KEY.to_string()),
+                ..SignerConfig::default()
+            },
+            31337,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(local, NodeSigner::Local(_)));
+        
+        let url = spawn_signer(wallet(), false).await;
+        let remote = NodeSigner::from_config(
+            &SignerConfig {
+                remote_url: Some(url),
+                address: Some(wallet().address()),
+                ..SignerConfig::default()
+            },
+            31337,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(remote, NodeSigner::Remote(_)));
+        
+        // Same key, same signatures, whichever way it is held
+        assert_eq!(local.address(), remote.address());
+        assert_eq!(remote.chain_id(), 31337);
+        let tx = transaction();
+        assert_eq!(
+            local.sign_transaction(&tx).await.unwrap(),
+            remote.sign_transaction(&tx).await.unwrap()
+        );
+        
+        assert!(matches!(
+            NodeSigner::from_config(&SignerConfig::default(), 31337).await,
+            Err(SignerError::Config(_))
+        ));
+    }
+}
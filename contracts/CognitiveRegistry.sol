@@ -30,6 +30,10 @@ contract CognitiveRegistry is Ownable, ReentrancyGuard {
     mapping(address => CognitiveNode) public cognitiveNodes;
     mapping(uint256 => ReasoningTask) public reasoningTasks;
     mapping(address => bool) public isRegistered;
+    // Hot key that claims and completes tasks for a node, so the staking key
+    // can stay offline. Rewards are still paid to the node.
+    mapping(address => address) public operatorOf;
+    mapping(address => address) public nodeOfOperator;
     
     address[] public nodeList;
     uint256 public taskCounter;
@@ -37,6 +41,7 @@ contract CognitiveRegistry is Ownable, ReentrancyGuard {
     
     event NodeRegistered(address indexed validator, string nodeId);
     event NodeDeactivated(address indexed validator);
+    event OperatorSet(address indexed node, address indexed operator);
     event TaskCreated(uint256 indexed taskId, string taskType, uint256 reward);
     event TaskAssigned(uint256 indexed taskId, address indexed node);
     event TaskCompleted(uint256 indexed taskId, address indexed node, uint256 reward);
@@ -46,6 +51,7 @@ contract CognitiveRegistry is Ownable, ReentrancyGuard {
     
     function registerCognitiveNode(string memory nodeId) external payable {
         require(!isRegistered[msg.sender], "Already registered");
+        require(nodeOfOperator[msg.sender] == address(0), "Address is an operator");
         require(msg.value >= minStakeAmount, "Insufficient stake");
         
         cognitiveNodes[msg.sender] = CognitiveNode({
@@ -64,6 +70,31 @@ contract CognitiveRegistry is Ownable, ReentrancyGuard {
         emit NodeRegistered(msg.sender, nodeId);
     }
     
+    // Replaces the node's operator; address(0) removes it
+    function setOperator(address operator) external {
+        require(isRegistered[msg.sender], "Not registered");
+        require(!isRegistered[operator], "Operator is a node");
+        require(operator == address(0) || nodeOfOperator[operator] == address(0), "Operator in use");
+        
+        address previous = operatorOf[msg.sender];
+        if (previous != address(0)) {
+            delete nodeOfOperator[previous];
+        }
+        
+        operatorOf[msg.sender] = operator;
+        if (operator != address(0)) {
+            nodeOfOperator[operator] = msg.sender;
+        }
+        
+        emit OperatorSet(msg.sender, operator);
+    }
+    
+    // The node `account` acts for: its own address unless it is an operator
+    function nodeOf(address account) public view returns (address) {
+        address node = nodeOfOperator[account];
+        return node == address(0) ? account : node;
+    }
+    
     function createReasoningTask(
         string memory taskType,
         bytes32 dataHash,
@@ -88,39 +119,41 @@ contract CognitiveRegistry is Ownable, ReentrancyGuard {
     }
     
     function assignTask(uint256 taskId) external {
-        require(isRegistered[msg.sender], "Not registered");
-        require(cognitiveNodes[msg.sender].isActive, "Node not active");
+        address node = nodeOf(msg.sender);
+        require(isRegistered[node], "Not registered");
+        require(cognitiveNodes[node].isActive, "Node not active");
         
         ReasoningTask storage task = reasoningTasks[taskId];
         require(!task.completed, "Task completed");
         require(task.assignedNode == address(0), "Task already assigned");
         require(block.timestamp < task.deadline, "Task expired");
         
-        task.assignedNode = msg.sender;
-        emit TaskAssigned(taskId, msg.sender);
+        task.assignedNode = node;
+        emit TaskAssigned(taskId, node);
     }
     
     function submitTaskResult(
         uint256 taskId,
         bytes32 resultHash
     ) external nonReentrant {
+        address node = nodeOf(msg.sender);
         ReasoningTask storage task = reasoningTasks[taskId];
-        require(task.assignedNode == msg.sender, "Not assigned to you");
+        require(task.assignedNode == node, "Not assigned to you");
         require(!task.completed, "Already completed");
         require(block.timestamp < task.deadline, "Deadline passed");
         
         task.completed = true;
         
-        CognitiveNode storage node = cognitiveNodes[msg.sender];
-        node.tasksCompleted++;
-        node.reputationScore += 10;
+        CognitiveNode storage info = cognitiveNodes[node];
+        info.tasksCompleted++;
+        info.reputationScore += 10;
         
         uint256 reward = task.reward;
-        (bool success, ) = msg.sender.call{value: reward}("");
+        (bool success, ) = node.call{value: reward}("");
         require(success, "Transfer failed");
         
-        emit TaskCompleted(taskId, msg.sender, reward);
-        emit ReputationUpdated(msg.sender, node.reputationScore);
+        emit TaskCompleted(taskId, node, reward);
+        emit ReputationUpdated(node, info.reputationScore);
     }
     
     function getActiveNodes() external view returns (address[] memory) {
@@ -1,3 +1,4 @@
+use bindings::CognitiveRegistry;
 use ethers::prelude::*;
 use std::sync::Arc;
 
@@ -18,20 +19,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     let registry_address: Address = std::env::var("REGISTRY_ADDRESS")?.parse()?;
     
-    // ABI for registerCognitiveNode
-    let abi = r#"[{
-        "type": "function",
-        "name": "registerCognitiveNode",
-        "inputs": [{"name": "nodeId", "type": "string"}],
-        "outputs": [],
-        "stateMutability": "payable"
-    }]"#;
-    
-    let contract = Contract::new(
-        registry_address,
-        serde_json::from_str::<ethers::abi::Abi>(abi)?,
-        Arc::new(client),
-    );
+    let contract = CognitiveRegistry::new(registry_address, Arc::new(client));
     
     let node_id = format!("polyneuron-{}", chrono::Utc::now().timestamp());
     let stake_amount = ethers::utils::parse_ether("0.1")?; // 0.1 MATIC (testnet)
@@ -40,7 +28,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("💰 Stake: 0.1 MATIC (testnet amount)");
     
     let tx = contract
-        .method::<_, ()>("registerCognitiveNode", node_id)?
+        .register_cognitive_node(node_id)
         .value(stake_amount);
     
     let pending_tx = tx.send().await?;
@@ -0,0 +1,39 @@
+use bindings::CognitiveRegistry;
+use cognitive_engine::signer::NodeSigner;
+use ethers::prelude::*;
+use shared::config::{NodeConfig, Role};
+use std::sync::Arc;
+
+// Signs with the staking key (`[staking_signer]`, STAKING_KEYSTORE_PATH or
+// STAKING_REMOTE_SIGNER_URL) and lets OPERATOR_ADDRESS claim and submit
+// tasks for the node. Rewards keep going to the node address.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("🔑 Setting node operator...");
+    
+    let mut config = match std::env::var("POLYNEURONS_CONFIG") {
+        Ok(path) if !path.is_empty() => NodeConfig::from_file(path)?,
+        _ => NodeConfig::default(),
+    };
+    config.apply_env()?;
+    config.validate(Role::Staking)?;
+    
+    let provider = Provider::<Http>::try_from(config.chain.rpc_url.as_str())?;
+    let chain_id = config.chain.check_chain_id(provider.get_chainid().await?.as_u64())?;
+    let signer = NodeSigner::from_config(&config.staking_signer, chain_id).await?;
+    let node_address = signer.address();
+    
+    let operator: Address = std::env::var("OPERATOR_ADDRESS")?.parse()?;
+    let registry_address = config.chain.registry_address.expect("validated");
+    let contract = CognitiveRegistry::new(registry_address, Arc::new(SignerMiddleware::new(provider, signer)));
+    
+    println!("📝 Node: {:?}", node_address);
+    println!("⚙️  Operator: {:?}", operator);
+    
+    let receipt = contract.set_operator(operator).send().await?.await?;
+    
+    println!("✅ Operator set!");
+    println!("   Tx Hash: {:?}", receipt.unwrap().transaction_hash);
+    
+    Ok(())
+}
@@ -1,6 +1,8 @@
+use bindings::cognitive_registry::{CognitiveRegistry, TaskCreatedFilter};
 use ethers::prelude::*;
+use cognitive_engine::datastore::{IpfsDataStore, LocalDataStore, TaskDataStore};
+use shared::types::{ForecastModel, MarketPredictionInput, TaskPayload};
 use std::sync::Arc;
-use sha2::{Sha256, Digest};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -19,54 +21,39 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let registry_address: Address = std::env::var("REGISTRY_ADDRESS")?.parse()?;
     
     // Task data
-    let task_data = serde_json::json!({
-        "type": "market_prediction",
-        "symbol": "MATIC/USD",
-        "prices": [1.2, 1.3, 1.25, 1.4, 1.35]
-    });
-    
-    // Hash data
-    let mut hasher = Sha256::new();
-    hasher.update(task_data.to_string().as_bytes());
-    let hash_result = hasher.finalize();
-    
-    // Convert to [u8; 32] for bytes32
-    let mut data_hash = [0u8; 32];
-    data_hash.copy_from_slice(&hash_result);
-    
-    println!("🎯 Task Type: market_prediction");
-    println!("📊 Data Hash: 0x{}", hex::encode(&data_hash));
-    
-    let abi = r#"[{
-        "type": "function",
-        "name": "createReasoningTask",
-        "inputs": [
-            {"name": "taskType", "type": "string"},
-            {"name": "dataHash", "type": "bytes32"},
-            {"name": "deadline", "type": "uint256"}
-        ],
-        "outputs": [{"name": "", "type": "uint256"}],
-        "stateMutability": "payable"
-    }]"#;
-    
-    let contract = Contract::new(
-        registry_address,
-        serde_json::from_str::<ethers::abi::Abi>(abi)?,
-        Arc::new(client),
-    );
+    let task_data = MarketPredictionInput {
+        symbol: Some("MATIC/USD".to_string()),
+        prices: vec![1.2, 1.3, 1.25, 1.4, 1.35],
+        model: ForecastModel::Holt,
+        horizon: 3,
+        ..MarketPredictionInput::default()
+    }
+    .to_value();
+    
+    // Publish the payload so nodes can resolve the on-chain dataHash to it.
+    // The returned hash is the canonical (RFC 8785) one nodes verify against.
+    let data_dir = std::env::var("TASK_DATA_DIR").unwrap_or_else(|_| ".polyneurons/task-data".to_string());
+    let data_hash = LocalDataStore::new(data_dir).put(&task_data).await?;
+    
+    if let Some(api_url) = std::env::var("IPFS_API_URL").ok().filter(|url| !url.is_empty()) {
+        IpfsDataStore::new(api_url).put(&task_data).await?;
+        println!("📦 Task data pinned to IPFS");
+    }
+    
+    println!("🎯 Task Type: {}", MarketPredictionInput::TASK_TYPE);
+    println!("📊 Data Hash: 0x{}", hex::encode(data_hash));
+    
+    let contract = CognitiveRegistry::new(registry_address, Arc::new(client));
     
     let deadline = chrono::Utc::now().timestamp() as u64 + 3600; // 1 hour
     let reward = ethers::utils::parse_ether("0.01")?; // 0.01 MATIC reward (cheaper for testing)
     
     let tx = contract
-        .method::<_, U256>(
-            "createReasoningTask",
-            (
-                "market_prediction".to_string(),
-                data_hash,
-                U256::from(deadline),
-            ),
-        )?
+        .create_reasoning_task(
+            MarketPredictionInput::TASK_TYPE.to_string(),
+            data_hash,
+            U256::from(deadline),
+        )
         .value(reward);
     
     let pending_tx = tx.send().await?;
@@ -74,6 +61,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     println!("✅ Task submitted!");
     println!("   Tx Hash: {:?}", receipt.as_ref().unwrap().transaction_hash);
+    
+    if let Some(created) = receipt
+        .as_ref()
+        .and_then(|r| r.logs.iter().find_map(|log| parse_log::<TaskCreatedFilter>(log.clone()).ok()))
+    {
+        println!("   🆔 Task ID: {}", created.task_id);
+    }
+    
     println!("   💰 Reward: 0.01 MATIC");
     
     if let Some(receipt) = receipt.as_ref() {
@@ -1,12 +1,14 @@
+use bindings::cognitive_registry::{CognitiveRegistry, TaskCreatedFilter};
 use ethers::prelude::*;
+use cognitive_engine::datastore::{IpfsDataStore, LocalDataStore, TaskDataStore};
+use shared::types::{ForecastModel, MarketPredictionInput, TaskPayload};
 use std::sync::Arc;
-use sha2::{Sha256, Digest};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("📋 Submitting Cheap Reasoning Task...");
     println!("💡 This version uses minimal reward for testing");
-    println!("");
+    println!();
     
     let rpc_url = std::env::var("AMOY_RPC_URL")
         .or_else(|_| std::env::var("POLYGON_RPC_URL"))?;
@@ -21,40 +23,29 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let registry_address: Address = std::env::var("REGISTRY_ADDRESS")?.parse()?;
     
     // Task data
-    let task_data = serde_json::json!({
-        "type": "market_prediction",
-        "symbol": "MATIC/USD",
-        "prices": [1.2, 1.3, 1.25, 1.4, 1.35]
-    });
-    
-    // Hash data
-    let mut hasher = Sha256::new();
-    hasher.update(task_data.to_string().as_bytes());
-    let hash_result = hasher.finalize();
-    
-    let mut data_hash = [0u8; 32];
-    data_hash.copy_from_slice(&hash_result);
-    
-    println!("🎯 Task Type: market_prediction");
-    println!("📊 Data Hash: 0x{}", hex::encode(&data_hash));
-    
-    let abi = r#"[{
-        "type": "function",
-        "name": "createReasoningTask",
-        "inputs": [
-            {"name": "taskType", "type": "string"},
-            {"name": "dataHash", "type": "bytes32"},
-            {"name": "deadline", "type": "uint256"}
-        ],
-        "outputs": [{"name": "", "type": "uint256"}],
-        "stateMutability": "payable"
-    }]"#;
-    
-    let contract = Contract::new(
-        registry_address,
-        serde_json::from_str::<ethers::abi::Abi>(abi)?,
-        Arc::new(client),
-    );
+    let task_data = MarketPredictionInput {
+        symbol: Some("MATIC/USD".to_string()),
+        prices: vec![1.2, 1.3, 1.25, 1.4, 1.35],
+        model: ForecastModel::Holt,
+        horizon: 3,
+        ..MarketPredictionInput::default()
+    }
+    .to_value();
+    
+    // Publish the payload so nodes can resolve the on-chain dataHash to it.
+    // The returned hash is the canonical (RFC 8785) one nodes verify against.
+    let data_dir = std::env::var("TASK_DATA_DIR").unwrap_or_else(|_| ".polyneurons/task-data".to_string());
+    let data_hash = LocalDataStore::new(data_dir).put(&task_data).await?;
+    
+    if let Some(api_url) = std::env::var("IPFS_API_URL").ok().filter(|url| !url.is_empty()) {
+        IpfsDataStore::new(api_url).put(&task_data).await?;
+        println!("📦 Task data pinned to IPFS");
+    }
+    
+    println!("🎯 Task Type: {}", MarketPredictionInput::TASK_TYPE);
+    println!("📊 Data Hash: 0x{}", hex::encode(data_hash));
+    
+    let contract = CognitiveRegistry::new(registry_address, Arc::new(client));
     
     let deadline = chrono::Utc::now().timestamp() as u64 + 3600;
     
@@ -65,14 +56,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("⛽ Estimating gas...");
     
     let tx = contract
-        .method::<_, U256>(
-            "createReasoningTask",
-            (
-                "market_prediction".to_string(),
-                data_hash,
-                U256::from(deadline),
-            ),
-        )?
+        .create_reasoning_task(
+            MarketPredictionInput::TASK_TYPE.to_string(),
+            data_hash,
+            U256::from(deadline),
+        )
         .value(reward);
     
     // Estimate gas first
@@ -87,7 +75,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             
             println!("   Gas price: {} gwei", ethers::utils::format_units(gas_price, "gwei")?);
             println!("   Estimated gas cost: {} MATIC", gas_cost_matic);
-            println!("");
+            println!();
             println!("📊 Total Cost Breakdown:");
             println!("   Reward:   0.001 MATIC");
             println!("   Gas fee:  {} MATIC", gas_cost_matic);
@@ -95,7 +83,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             
             let total = ethers::utils::parse_ether("0.001")? + gas_cost;
             println!("   TOTAL:    {} MATIC", ethers::utils::format_ether(total));
-            println!("");
+            println!();
         }
         Err(e) => {
             println!("⚠️  Could not estimate gas: {}", e);
@@ -109,6 +97,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("✅ Task submitted!");
     println!("   Tx Hash: {:?}", receipt.as_ref().unwrap().transaction_hash);
     
+    if let Some(created) = receipt
+        .as_ref()
+        .and_then(|r| r.logs.iter().find_map(|log| parse_log::<TaskCreatedFilter>(log.clone()).ok()))
+    {
+        println!("   🆔 Task ID: {}", created.task_id);
+    }
+    
     if let Some(receipt) = receipt.as_ref() {
         if let Some(gas_used) = receipt.gas_used {
             println!("   ⛽ Actual Gas Used: {}", gas_used);
@@ -1,10 +1,11 @@
+use bindings::CognitiveRegistry;
 use ethers::prelude::*;
 use std::sync::Arc;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("🔍 Viewing Cognitive Node Status...");
-    println!("");
+    println!();
     
     let rpc_url = std::env::var("AMOY_RPC_URL")
         .or_else(|_| std::env::var("POLYGON_RPC_URL"))?;
@@ -18,41 +19,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let wallet: LocalWallet = private_key.parse()?;
     let node_address = wallet.address();
     
-    let abi = r#"[{
-        "type": "function",
-        "name": "cognitiveNodes",
-        "inputs": [{"name": "", "type": "address"}],
-        "outputs": [
-            {"name": "validator", "type": "address"},
-            {"name": "nodeId", "type": "string"},
-            {"name": "stakedAmount", "type": "uint256"},
-            {"name": "reputationScore", "type": "uint256"},
-            {"name": "tasksCompleted", "type": "uint256"},
-            {"name": "isActive", "type": "bool"},
-            {"name": "registeredAt", "type": "uint256"}
-        ],
-        "stateMutability": "view"
-    }]"#;
+    let contract = CognitiveRegistry::new(registry_address, provider);
     
-    let contract = Contract::new(
-        registry_address,
-        serde_json::from_str::<ethers::abi::Abi>(abi)?,
-        provider,
-    );
-    
-    type NodeData = (Address, String, U256, U256, U256, bool, U256);
-    
-    let node: NodeData = contract
-        .method::<_, NodeData>("cognitiveNodes", node_address)?
+    let (validator, node_id, staked, reputation, tasks_completed, is_active, registered_at) = contract
+        .cognitive_nodes(node_address)
         .call()
         .await?;
     
-    let (validator, node_id, staked, reputation, tasks_completed, is_active, registered_at) = node;
-    
     if validator == Address::zero() {
         println!("❌ Node not registered");
         println!("   Address: {:?}", node_address);
-        println!("");
+        println!();
         println!("💡 Register with: make register-node");
         return Ok(());
     }
@@ -83,7 +60,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
     
-    println!("");
+    println!();
     println!("🔗 View on Explorer:");
     println!("   https://amoy.polygonscan.com/address/{:?}", registry_address);
     
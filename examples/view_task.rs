@@ -1,10 +1,11 @@
+use bindings::CognitiveRegistry;
 use ethers::prelude::*;
 use std::sync::Arc;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("🔍 Viewing Reasoning Task...");
-    println!("");
+    println!();
     
     let rpc_url = std::env::var("AMOY_RPC_URL")
         .or_else(|_| std::env::var("POLYGON_RPC_URL"))?;
@@ -13,28 +14,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     let registry_address: Address = std::env::var("REGISTRY_ADDRESS")?.parse()?;
     
-    let abi = r#"[{
-        "type": "function",
-        "name": "reasoningTasks",
-        "inputs": [{"name": "", "type": "uint256"}],
-        "outputs": [
-            {"name": "taskId", "type": "uint256"},
-            {"name": "taskType", "type": "string"},
-            {"name": "dataHash", "type": "bytes32"},
-            {"name": "requester", "type": "address"},
-            {"name": "reward", "type": "uint256"},
-            {"name": "deadline", "type": "uint256"},
-            {"name": "completed", "type": "bool"},
-            {"name": "assignedNode", "type": "address"}
-        ],
-        "stateMutability": "view"
-    }]"#;
-    
-    let contract = Contract::new(
-        registry_address,
-        serde_json::from_str::<ethers::abi::Abi>(abi)?,
-        provider,
-    );
+    let contract = CognitiveRegistry::new(registry_address, provider);
     
     // Get task ID from args or default to 1
     // Note: When run via cargo, args start from index 0 as the binary name
@@ -46,17 +26,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
     
     println!("🔍 Fetching Task #{}...", task_id);
-    println!("");
-    
-    type TaskData = (U256, String, [u8; 32], Address, U256, U256, bool, Address);
+    println!();
     
-    let task: TaskData = contract
-        .method::<_, TaskData>("reasoningTasks", U256::from(task_id))?
+    let (task_id, task_type, data_hash, requester, reward, deadline, completed, assigned_node) = contract
+        .reasoning_tasks(U256::from(task_id))
         .call()
         .await?;
     
-    let (task_id, task_type, data_hash, requester, reward, deadline, completed, assigned_node) = task;
-    
     println!("📋 Task Details:");
     println!("   ID:           {}", task_id);
     println!("   Type:         {}", task_type);
@@ -87,7 +63,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
     
-    println!("");
+    println!();
     println!("🔗 View on Explorer:");
     println!("   https://amoy.polygonscan.com/address/{:?}", registry_address);
     
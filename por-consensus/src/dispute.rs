@@ -0,0 +1,287 @@
+use serde::{Deserialize, Serialize};
+use shared::hashing::{hashes_equal, result_hash, to_hex};
+use shared::types::{ProofOfReasoning, ReasoningResult};
+use std::collections::BTreeMap;
+use thiserror::Error;
+use tracing::info;
+
+use crate::rewards::basis_points;
+use crate::{ConsensusEngine, ProofStatus, Stake};
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DisputeError {
+    #[error("unknown proof {0}")]
+    UnknownProof(String),
+    #[error("challenge window for proof {0} has closed")]
+    WindowClosed(String),
+    #[error("proof {0} is already challenged")]
+    AlreadyChallenged(String),
+    #[error("no open challenge for proof {0}")]
+    NoChallenge(String),
+    #[error("prover cannot challenge own proof {0}")]
+    OwnProof(String),
+    #[error("evidence for proof {0} reproduces the submitted output")]
+    NothingToDispute(String),
+    #[error("{challenger} needs a stake of at least {bond} to challenge")]
+    InsufficientStake { challenger: String, bond: Stake },
+    #[error("prover of proof {0} has no stake to slash")]
+    UnstakedProver(String),
+    #[error("could not hash evidence: {0}")]
+    Evidence(String),
+    #[error("could not save dispute: {0}")]
+    Storage(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisputeConfig {
+    // How long after `ProofOfReasoning.timestamp` a proof can be challenged
+    pub challenge_window_secs: u64,
+    // Forfeited by a challenger whose challenge fails
+    pub challenger_bond: Stake,
+    // Share of stake taken from the losing side, in basis points
+    pub prover_slash_bps: u32,
+    pub validator_slash_bps: u32,
+}
+
+impl Default for DisputeConfig {
+    fn default() -> Self {
+        Self {
+            challenge_window_secs: 3_600,
+            challenger_bond: 10_000_000_000_000_000, // 0.01 MATIC
+            prover_slash_bps: 5_000,
+            validator_slash_bps: 1_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Challenge {
+    pub proof_id: String,
+    pub challenger: String,
+    // `result_hash` of the challenger's own re-execution
+    pub evidence_hash: String,
+    // Taken from the challenger's stake when the challenge opens
+    pub bond: Stake,
+    pub opened_at: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Resolution {
+    // Re-execution reproduced the prover's output; the challenger loses the bond
+    ProofUpheld,
+    // Re-execution did not; prover and approving validators are slashed
+    ProofOverturned,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisputeOutcome {
+    pub resolution: Resolution,
+    pub slashed: BTreeMap<String, Stake>,
+    // Everything slashed goes to the winning side
+    pub recipient: String,
+    pub payout: Stake,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dispute {
+    pub challenge: Challenge,
+    pub outcome: Option<DisputeOutcome>,
+}
+
+// Applies the dispute rules to an engine. Disputes themselves are kept (and
+// persisted) by the engine, see `ConsensusEngine::dispute`.
+pub struct DisputeManager {
+    config: DisputeConfig,
+}
+
+impl DisputeManager {
+    pub fn new(config: DisputeConfig) -> Self {
+        Self { config }
+    }
+    
+    pub fn config(&self) -> &DisputeConfig {
+        &self.config
+    }
+    
+    pub fn window_open(&self, proof: &ProofOfReasoning, now: u64) -> bool {
+        now <= proof.timestamp.saturating_add(self.config.challenge_window_secs)
+    }
+    
+    // A proof is settled once its window has passed and any challenge
+    // against it has been resolved
+    pub fn is_settled(&self, engine: &ConsensusEngine, proof: &ProofOfReasoning, now: u64) -> bool {
+        let unresolved = engine
+            .dispute(&proof.input_hash)
+            .is_some_and(|dispute| dispute.outcome.is_none());
+        
+        !self.window_open(proof, now) && !unresolved
+    }
+    
+    // Escrows the challenger's bond until the challenge is resolved, so a
+    // challenger cannot leave the validator set to escape losing it
+    pub fn open_challenge<'a>(
+        &self,
+        engine: &'a mut ConsensusEngine,
+        proof_id: &str,
+        challenger: &str,
+        evidence: &ReasoningResult,
+        now: u64,
+    ) -> Result<&'a Challenge, DisputeError> {
+        let proof = engine
+            .get_proof(proof_id)
+            .ok_or_else(|| DisputeError::UnknownProof(proof_id.to_string()))?;
+        
+        if !self.window_open(proof, now) {
+            return Err(DisputeError::WindowClosed(proof_id.to_string()));
+        }
+        if engine.dispute(proof_id).is_some() {
+            return Err(DisputeError::AlreadyChallenged(proof_id.to_string()));
+        }
+        if proof.prover == challenger {
+            return Err(DisputeError::OwnProof(proof_id.to_string()));
+        }
+        if engine.stake_of(&proof.prover).unwrap_or(0) == 0 {
+            return Err(DisputeError::UnstakedProver(proof_id.to_string()));
+        }
+        
+        let evidence_hash = to_hex(&result_hash(evidence).map_err(|e| DisputeError::Evidence(e.to_string()))?);
+        if hashes_equal(&evidence_hash, &proof.output_hash) {
+            return Err(DisputeError::NothingToDispute(proof_id.to_string()));
+        }
+        
+        let bond = self.config.challenger_bond;
+        if engine.stake_of(challenger).unwrap_or(0) < bond {
+            return Err(DisputeError::InsufficientStake {
+                challenger: challenger.to_string(),
+                bond,
+            });
+        }
+        
+        info!("⚔️  Proof {} challenged by {}", proof_id, challenger);
+        
+        engine.take_stake(challenger, bond);
+        let dispute = Dispute {
+            challenge: Challenge {
+                proof_id: proof_id.to_string(),
+                challenger: challenger.to_string(),
+                evidence_hash,
+                bond,
+                opened_at: now,
+            },
+            outcome: None,
+        };
+        
+        engine.disputes_mut().insert(proof_id.to_string(), dispute);
+        save(engine)?;
+        
+        Ok(&engine.dispute(proof_id).expect("dispute was just inserted").challenge)
+    }
+    
+    // Settles a challenge against the arbiter's re-execution of the task
+    // (the same module run the prover and validators performed)
+    pub fn resolve<'a>(
+        &self,
+        engine: &'a mut ConsensusEngine,
+        proof_id: &str,
+        recomputed: &ReasoningResult,
+    ) -> Result<&'a DisputeOutcome, DisputeError> {
+        let challenge = engine
+            .dispute(proof_id)
+            .filter(|dispute| dispute.outcome.is_none())
+            .map(|dispute| dispute.challenge.clone())
+            .ok_or_else(|| DisputeError::NoChallenge(proof_id.to_string()))?;
+        let proof = engine
+            .get_proof(proof_id)
+            .cloned()
+            .ok_or_else(|| DisputeError::UnknownProof(proof_id.to_string()))?;
+        
+        // A prover that left the validator set since the challenge opened
+        // would get away with nothing slashed
+        let prover_stake = engine.stake_of(&proof.prover).unwrap_or(0);
+        if prover_stake == 0 {
+            return Err(DisputeError::UnstakedProver(proof_id.to_string()));
+        }
+        
+        let recomputed_hash = to_hex(&result_hash(recomputed).map_err(|e| DisputeError::Evidence(e.to_string()))?);
+        let mut slashed = BTreeMap::new();
+        
+        // All stake changes below are saved together with the outcome
+        let (resolution, recipient) = if hashes_equal(&recomputed_hash, &proof.output_hash) {
+            // The bond was escrowed when the challenge opened
+            slashed.insert(challenge.challenger.clone(), challenge.bond);
+            
+            (Resolution::ProofUpheld, proof.prover.clone())
+        } else {
+            let taken = engine.take_stake(&proof.prover, basis_points(prover_stake, self.config.prover_slash_bps));
+            slashed.insert(proof.prover.clone(), taken);
+            
+            let approvers: Vec<String> = engine
+                .tally(proof_id)
+                .map(|tally| tally.approvals().map(str::to_string).collect())
+                .unwrap_or_default();
+            
+            for approver in approvers {
+                let stake = engine.stake_of(&approver).unwrap_or(0);
+                let taken = engine.take_stake(&approver, basis_points(stake, self.config.validator_slash_bps));
+                slashed.insert(approver, taken);
+            }
+            
+            engine.overturn(proof_id);
+            engine.add_stake(&challenge.challenger, challenge.bond);
+            (Resolution::ProofOverturned, challenge.challenger.clone())
+        };
+        
+        let payout = slashed.values().sum();
+        engine.add_stake(&recipient, payout);
+        info!("⚖️  Dispute on proof {} resolved: {:?}, {} paid to {}", proof_id, resolution, payout, recipient);
+        
+        if let Some(dispute) = engine.disputes_mut().get_mut(proof_id) {
+            dispute.outcome = Some(DisputeOutcome {
+                resolution,
+                slashed,
+                recipient,
+                payout,
+            });
+        }
+        save(engine)?;
+        
+        Ok(engine
+            .dispute(proof_id)
+            .and_then(|dispute| dispute.outcome.as_ref())
+            .expect("outcome was just recorded"))
+    }
+    
+    // Forgets proofs that can no longer change: finalized, settled and, if
+    // verified, already rewarded. Keeps the engine's saved state from
+    // growing with every proof it has ever seen. Returns how many went.
+    pub fn prune(&self, engine: &mut ConsensusEngine, now: u64) -> Result<usize, DisputeError> {
+        let done: Vec<String> = engine
+            .proofs
+            .values()
+            .filter(|proof| match engine.status(&proof.input_hash) {
+                Some(ProofStatus::Rejected) => true,
+                Some(ProofStatus::Verified) => engine.ledger().is_rewarded(&proof.input_hash),
+                _ => false,
+            })
+            .filter(|proof| self.is_settled(engine, proof, now))
+            .map(|proof| proof.input_hash.clone())
+            .collect();
+        
+        if done.is_empty() {
+            return Ok(0);
+        }
+        
+        for proof_id in &done {
+            engine.forget(proof_id);
+        }
+        save(engine)?;
+        
+        info!("🧹 Pruned {} settled proofs", done.len());
+        Ok(done.len())
+    }
+}
+
+fn save(engine: &ConsensusEngine) -> Result<(), DisputeError> {
+    engine.persist().map_err(|e| DisputeError::Storage(e.to_string()))
+}
+
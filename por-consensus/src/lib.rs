@@ -1,48 +1,317 @@
 use anyhow::Result;
-use shared::types::ProofOfReasoning;
-use std::collections::HashMap;
-use tracing::info;
+use shared::hashing::{hashes_equal, result_hash, to_hex};
+use shared::types::{CognitiveNode, ProofOfReasoning, ReasoningResult};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use thiserror::Error;
+use tracing::{info, warn};
+
+pub mod dispute;
+pub mod reputation;
+pub mod rewards;
+pub mod storage;
+mod voting;
+
+use dispute::Dispute;
+pub use rewards::{RewardLedger, RewardPolicy};
+use storage::{ConsensusState, StateStore, StorageError, SCHEMA_VERSION};
+pub use voting::{ProofStatus, Stake, Threshold, VoteTally};
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ConsensusError {
+    #[error("unknown proof {0}")]
+    UnknownProof(String),
+    #[error("proof {0} was already submitted")]
+    DuplicateProof(String),
+    #[error("{validator} is not a validator for proof {proof_id}")]
+    NotAValidator { proof_id: String, validator: String },
+    #[error("{validator} already voted on proof {proof_id}")]
+    AlreadyVoted { proof_id: String, validator: String },
+    #[error("prover cannot validate own proof {0}")]
+    OwnProof(String),
+    #[error("proof {0} is already finalized ({1:?})")]
+    Finalized(String, ProofStatus),
+    #[error("rewards for proof {0} were already distributed")]
+    AlreadyRewarded(String),
+    #[error("could not persist consensus state: {0}")]
+    Storage(String),
+}
 
 pub struct ConsensusEngine {
     proofs: HashMap<String, ProofOfReasoning>,
-    validators: Vec<String>,
+    tallies: HashMap<String, VoteTally>,
+    validators: BTreeMap<String, Stake>,
+    // Approving validators needed on top of the stake threshold
     required_confirmations: u32,
+    threshold: Threshold,
+    reward_policy: RewardPolicy,
+    ledger: RewardLedger,
+    // Challenges opened through a `DisputeManager`, open or resolved
+    disputes: BTreeMap<String, Dispute>,
+    store: Option<Arc<dyn StateStore>>,
 }
 
 impl ConsensusEngine {
     pub fn new(required_confirmations: u32) -> Self {
         Self {
             proofs: HashMap::new(),
-            validators: Vec::new(),
+            tallies: HashMap::new(),
+            validators: BTreeMap::new(),
             required_confirmations,
+            threshold: Threshold::default(),
+            reward_policy: RewardPolicy::default(),
+            ledger: RewardLedger::default(),
+            disputes: BTreeMap::new(),
+            store: None,
         }
     }
     
+    // Engine backed by `store`, resuming from whatever state it holds. Every
+    // change is saved before the call that made it returns.
+    pub fn open(required_confirmations: u32, store: Arc<dyn StateStore>) -> Result<Self, StorageError> {
+        let mut engine = Self::new(required_confirmations);
+        
+        if let Some(state) = store.load()? {
+            info!(
+                "💾 Restored {} proofs, {} validators from consensus state",
+                state.proofs.len(),
+                state.validators.len()
+            );
+            engine.restore(state);
+        }
+        
+        engine.store = Some(store);
+        Ok(engine)
+    }
+    
+    pub fn with_threshold(mut self, threshold: Threshold) -> Self {
+        self.threshold = threshold;
+        self
+    }
+    
+    pub fn with_reward_policy(mut self, reward_policy: RewardPolicy) -> Self {
+        self.reward_policy = reward_policy;
+        self
+    }
+    
+    // Registers a validator with a stake of 1
     pub fn add_validator(&mut self, validator: String) {
-        self.validators.push(validator);
+        self.add_validator_with_stake(validator, 1);
+    }
+    
+    pub fn add_validator_with_stake(&mut self, validator: String, stake: Stake) {
+        self.validators.insert(validator, stake);
+        self.persist_or_warn();
     }
     
-    pub fn submit_proof(&mut self, proof: ProofOfReasoning) -> Result<()> {
+    // Mirrors a registry node: active nodes vote with their staked amount
+    pub fn add_node(&mut self, node: &CognitiveNode) {
+        if node.is_active && node.staked_amount > 0 {
+            self.add_validator_with_stake(node.validator_address.clone(), node.staked_amount);
+        } else {
+            self.remove_validator(&node.validator_address);
+        }
+    }
+    
+    pub fn remove_validator(&mut self, validator: &str) -> Option<Stake> {
+        let removed = self.validators.remove(validator);
+        if removed.is_some() {
+            self.persist_or_warn();
+        }
+        removed
+    }
+    
+    pub fn stake_of(&self, validator: &str) -> Option<Stake> {
+        self.validators.get(validator).copied()
+    }
+    
+    pub fn total_stake(&self) -> Stake {
+        self.validators.values().sum()
+    }
+    
+    // Takes up to `amount` from a node's stake and returns what was taken.
+    // Electorates of proofs already submitted keep their snapshot.
+    pub fn slash(&mut self, node: &str, amount: Stake) -> Result<Stake, ConsensusError> {
+        let slashed = self.take_stake(node, amount);
+        if slashed > 0 {
+            self.save()?;
+        }
+        Ok(slashed)
+    }
+    
+    // Adds `amount` to a node's stake, e.g. to pay out what a dispute
+    // slashed. A node that has left the validator set is re-added with it.
+    pub fn credit(&mut self, node: &str, amount: Stake) -> Result<(), ConsensusError> {
+        if amount == 0 {
+            return Ok(());
+        }
+        
+        self.add_stake(node, amount);
+        self.save()
+    }
+    
+    // `slash` and `credit` without saving, for changes that are saved
+    // together once they are all made
+    pub(crate) fn take_stake(&mut self, node: &str, amount: Stake) -> Stake {
+        let Some(stake) = self.validators.get_mut(node) else {
+            return 0;
+        };
+        
+        let taken = amount.min(*stake);
+        *stake -= taken;
+        
+        if taken > 0 {
+            info!("🔪 Took {} from {} (remaining stake {})", taken, node, stake);
+        }
+        taken
+    }
+    
+    pub(crate) fn add_stake(&mut self, node: &str, amount: Stake) {
+        if amount == 0 {
+            return;
+        }
+        
+        let stake = self.validators.entry(node.to_string()).or_insert(0);
+        *stake += amount;
+        info!("💰 Credited {} to {} (stake {})", amount, node, stake);
+    }
+    
+    // The current validator set (with non-zero stake) becomes the electorate
+    // for this proof. A proof is submitted once; resubmitting it would wipe
+    // its votes and status. Whatever the submitter put in `verified` and
+    // `confirmations` is discarded: only votes cast here finalize a proof.
+    pub fn submit_proof(&mut self, mut proof: ProofOfReasoning) -> Result<(), ConsensusError> {
         info!("📥 Received proof from: {}", proof.prover);
+        
+        if self.proofs.contains_key(&proof.input_hash) {
+            return Err(ConsensusError::DuplicateProof(proof.input_hash));
+        }
+        proof.verified = false;
+        proof.confirmations = 0;
+        
+        let eligible = self
+            .validators
+            .iter()
+            .filter(|(validator, stake)| **stake > 0 && **validator != proof.prover)
+            .map(|(validator, stake)| (validator.clone(), *stake))
+            .collect();
+        
+        self.tallies.insert(proof.input_hash.clone(), VoteTally::new(eligible));
         self.proofs.insert(proof.input_hash.clone(), proof);
-        Ok(())
+        self.save()
     }
     
-    pub fn validate_proof(&mut self, proof_id: &str, validator: &str) -> Result<bool> {
-        if let Some(proof) = self.proofs.get_mut(proof_id) {
-            proof.confirmations += 1;
-            
-            info!("✅ Proof {} validated by {}", proof_id, validator);
-            info!("   Confirmations: {}/{}", proof.confirmations, self.required_confirmations);
-            
-            if proof.confirmations >= self.required_confirmations {
+    // Approving vote; returns whether the proof is final
+    pub fn validate_proof(&mut self, proof_id: &str, validator: &str) -> Result<bool, ConsensusError> {
+        Ok(self.vote(proof_id, validator, true)? == ProofStatus::Verified)
+    }
+    
+    pub fn reject_proof(&mut self, proof_id: &str, validator: &str) -> Result<ProofStatus, ConsensusError> {
+        self.vote(proof_id, validator, false)
+    }
+    
+    pub fn vote(&mut self, proof_id: &str, validator: &str, approved: bool) -> Result<ProofStatus, ConsensusError> {
+        let (Some(proof), Some(tally)) = (self.proofs.get_mut(proof_id), self.tallies.get_mut(proof_id)) else {
+            return Err(ConsensusError::UnknownProof(proof_id.to_string()));
+        };
+        
+        if tally.status() != ProofStatus::Pending {
+            return Err(ConsensusError::Finalized(proof_id.to_string(), tally.status()));
+        }
+        if proof.prover == validator {
+            return Err(ConsensusError::OwnProof(proof_id.to_string()));
+        }
+        let stake = tally.stake_of(validator).ok_or_else(|| ConsensusError::NotAValidator {
+            proof_id: proof_id.to_string(),
+            validator: validator.to_string(),
+        })?;
+        if tally.has_voted(validator) {
+            return Err(ConsensusError::AlreadyVoted {
+                proof_id: proof_id.to_string(),
+                validator: validator.to_string(),
+            });
+        }
+        
+        tally.record(validator, stake, approved);
+        proof.confirmations = tally.approvals().count() as u32;
+        
+        info!(
+            "{} Proof {} {} by {}",
+            if approved { "✅" } else { "❌" },
+            proof_id,
+            if approved { "approved" } else { "rejected" },
+            validator
+        );
+        info!(
+            "   Stake: {} approving, {} rejecting of {}",
+            tally.approved_stake(),
+            tally.rejected_stake(),
+            tally.total_stake()
+        );
+        
+        let status = tally.evaluate(self.threshold, self.required_confirmations);
+        tally.set_status(status);
+        
+        match status {
+            ProofStatus::Verified => {
                 proof.verified = true;
                 info!("🎉 Proof {} reached consensus!", proof_id);
-                return Ok(true);
             }
+            ProofStatus::Rejected => info!("🚫 Proof {} can no longer reach consensus, rejected", proof_id),
+            ProofStatus::Pending => {}
+        }
+        
+        self.save()?;
+        Ok(status)
+    }
+    
+    pub fn get_proof(&self, proof_id: &str) -> Option<&ProofOfReasoning> {
+        self.proofs.get(proof_id)
+    }
+    
+    // A proof shown to be wrong after the fact, e.g. by a won challenge.
+    // Saved by the caller along with the rest of the dispute.
+    pub(crate) fn overturn(&mut self, proof_id: &str) {
+        if let Some(proof) = self.proofs.get_mut(proof_id) {
+            proof.verified = false;
+        }
+        if let Some(tally) = self.tallies.get_mut(proof_id) {
+            tally.set_status(ProofStatus::Rejected);
         }
+    }
+    
+    // Drops a finalized proof with its votes and dispute. Saved by the
+    // caller. The reward ledger keeps the id so it is never paid twice.
+    pub(crate) fn forget(&mut self, proof_id: &str) {
+        self.proofs.remove(proof_id);
+        self.tallies.remove(proof_id);
+        self.disputes.remove(proof_id);
+    }
+    
+    pub fn dispute(&self, proof_id: &str) -> Option<&Dispute> {
+        self.disputes.get(proof_id)
+    }
+    
+    pub(crate) fn disputes_mut(&mut self) -> &mut BTreeMap<String, Dispute> {
+        &mut self.disputes
+    }
+    
+    pub fn status(&self, proof_id: &str) -> Option<ProofStatus> {
+        self.tallies.get(proof_id).map(|tally| tally.status())
+    }
+    
+    pub fn tally(&self, proof_id: &str) -> Option<&VoteTally> {
+        self.tallies.get(proof_id)
+    }
+    
+    // Whether a re-executed result hashes to the output committed in the proof
+    pub fn matches_output(&self, proof_id: &str, result: &ReasoningResult) -> Result<bool> {
+        let proof = self
+            .proofs
+            .get(proof_id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown proof {}", proof_id))?;
         
-        Ok(false)
+        Ok(hashes_equal(&proof.output_hash, &to_hex(&result_hash(result)?)))
     }
     
     pub fn get_verified_proofs(&self) -> Vec<&ProofOfReasoning> {
@@ -52,23 +321,125 @@ impl ConsensusEngine {
             .collect()
     }
     
-    pub fn calculate_rewards(&self, proof_id: &str) -> Result<HashMap<String, u64>> {
-        let mut rewards = HashMap::new();
+    pub fn get_rejected_proofs(&self) -> Vec<&ProofOfReasoning> {
+        self.proofs
+            .iter()
+            .filter(|(id, _)| self.status(id) == Some(ProofStatus::Rejected))
+            .map(|(_, proof)| proof)
+            .collect()
+    }
+    
+    // Splits the task's reward (`ReasoningTask.reward`) between the prover
+    // and the validators that approved, weighted by the stake they voted
+    // with. Empty until the proof is verified.
+    pub fn calculate_rewards(&self, proof_id: &str, reward: Stake) -> Result<HashMap<String, Stake>, ConsensusError> {
+        let tally = self
+            .tallies
+            .get(proof_id)
+            .ok_or_else(|| ConsensusError::UnknownProof(proof_id.to_string()))?;
         
-        if let Some(proof) = self.proofs.get(proof_id) {
-            if proof.verified {
-                // Prover gets 70% of reward
-                rewards.insert(proof.prover.clone(), 700);
-                
-                // Validators share 30%
-                let validator_reward = 300 / proof.confirmations as u64;
-                for validator in &self.validators {
-                    rewards.insert(validator.clone(), validator_reward);
-                }
-            }
+        self.split(proof_id, reward, tally.approval_stakes())
+    }
+    
+    // Same split with caller-provided weights, e.g. reputation scores.
+    // Approvers without a weight count as zero.
+    pub fn calculate_rewards_weighted(
+        &self,
+        proof_id: &str,
+        reward: Stake,
+        weights: &HashMap<String, u64>,
+    ) -> Result<HashMap<String, Stake>, ConsensusError> {
+        let tally = self
+            .tallies
+            .get(proof_id)
+            .ok_or_else(|| ConsensusError::UnknownProof(proof_id.to_string()))?;
+        
+        let approvers = tally
+            .approvals()
+            .map(|approver| (approver.to_string(), weights.get(approver).copied().unwrap_or(0) as u128))
+            .collect();
+        
+        self.split(proof_id, reward, &approvers)
+    }
+    
+    fn split(
+        &self,
+        proof_id: &str,
+        reward: Stake,
+        approvers: &BTreeMap<String, u128>,
+    ) -> Result<HashMap<String, Stake>, ConsensusError> {
+        let proof = self
+            .proofs
+            .get(proof_id)
+            .ok_or_else(|| ConsensusError::UnknownProof(proof_id.to_string()))?;
+        
+        if !proof.verified {
+            return Ok(HashMap::new());
         }
         
-        Ok(rewards)
+        Ok(rewards::split_reward(reward, &proof.prover, approvers, &self.reward_policy)
+            .into_iter()
+            .collect())
+    }
+    
+    // Pays out a verified proof into the reward ledger, once
+    pub fn distribute_rewards(&mut self, proof_id: &str, reward: Stake) -> Result<HashMap<String, Stake>, ConsensusError> {
+        if self.ledger.is_rewarded(proof_id) {
+            return Err(ConsensusError::AlreadyRewarded(proof_id.to_string()));
+        }
+        
+        let payouts = self.calculate_rewards(proof_id, reward)?;
+        if payouts.is_empty() {
+            return Ok(payouts);
+        }
+        
+        self.ledger.credit(proof_id, &payouts);
+        self.save()?;
+        Ok(payouts)
+    }
+    
+    pub fn ledger(&self) -> &RewardLedger {
+        &self.ledger
+    }
+    
+    // Snapshot of everything `open` restores
+    pub fn state(&self) -> ConsensusState {
+        ConsensusState {
+            version: SCHEMA_VERSION,
+            proofs: self.proofs.iter().map(|(id, proof)| (id.clone(), proof.clone())).collect(),
+            tallies: self.tallies.iter().map(|(id, tally)| (id.clone(), tally.clone())).collect(),
+            validators: self.validators.clone(),
+            ledger: self.ledger.clone(),
+            disputes: self.disputes.clone(),
+        }
+    }
+    
+    fn restore(&mut self, state: ConsensusState) {
+        self.proofs = state.proofs.into_iter().collect();
+        self.tallies = state.tallies.into_iter().collect();
+        self.validators = state.validators;
+        self.ledger = state.ledger;
+        self.disputes = state.disputes;
+    }
+    
+    // Saves the current state; a no-op for engines without a store
+    pub fn persist(&self) -> Result<(), StorageError> {
+        match &self.store {
+            Some(store) => store.save(&self.state()),
+            None => Ok(()),
+        }
+    }
+    
+    fn save(&self) -> Result<(), ConsensusError> {
+        self.persist().map_err(|e| ConsensusError::Storage(e.to_string()))
+    }
+    
+    // For changes that cannot report an error; the next successful save
+    // catches up
+    fn persist_or_warn(&self) {
+        if let Err(e) = self.persist() {
+            warn!("Could not persist consensus state: {}", e);
+        }
     }
 }
 
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+use tracing::debug;
+
+use crate::dispute::{Dispute, Resolution};
+use crate::{ConsensusEngine, ProofStatus};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReputationEvent {
+    ProofAccepted,
+    ProofRejected,
+    // Claimed a task and let its deadline pass without a proof
+    MissedDeadline,
+    // Verified proof later overturned by a challenge
+    ProofOverturned,
+    // Validator approved a proof that was later overturned
+    ApprovedInvalidProof,
+    ChallengeWon,
+    ChallengeLost,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReputationConfig {
+    // Score of a node with no history; decay pulls every score back here
+    pub initial_score: f64,
+    pub max_score: f64,
+    // Time for the distance to `initial_score` to halve; 0 disables decay
+    pub half_life_secs: u64,
+    pub proof_accepted: f64,
+    pub proof_rejected: f64,
+    pub missed_deadline: f64,
+    pub proof_overturned: f64,
+    pub approved_invalid_proof: f64,
+    pub challenge_won: f64,
+    pub challenge_lost: f64,
+}
+
+impl Default for ReputationConfig {
+    fn default() -> Self {
+        Self {
+            initial_score: 100.0, // CognitiveRegistry starts nodes at 100
+            max_score: 1_000.0,
+            half_life_secs: 30 * 24 * 3_600,
+            proof_accepted: 10.0,
+            proof_rejected: -20.0,
+            missed_deadline: -15.0,
+            proof_overturned: -50.0,
+            approved_invalid_proof: -20.0,
+            challenge_won: 15.0,
+            challenge_lost: -10.0,
+        }
+    }
+}
+
+impl ReputationConfig {
+    pub fn delta(&self, event: ReputationEvent) -> f64 {
+        match event {
+            ReputationEvent::ProofAccepted => self.proof_accepted,
+            ReputationEvent::ProofRejected => self.proof_rejected,
+            ReputationEvent::MissedDeadline => self.missed_deadline,
+            ReputationEvent::ProofOverturned => self.proof_overturned,
+            ReputationEvent::ApprovedInvalidProof => self.approved_invalid_proof,
+            ReputationEvent::ChallengeWon => self.challenge_won,
+            ReputationEvent::ChallengeLost => self.challenge_lost,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Score {
+    value: f64,
+    updated_at: u64,
+}
+
+// Reputation per node and task type, so a node that is good at one kind of
+// task is not trusted blindly for another. Timestamps are unix seconds.
+pub struct ReputationBook {
+    config: ReputationConfig,
+    scores: HashMap<String, HashMap<String, Score>>,
+}
+
+impl ReputationBook {
+    pub fn new(config: ReputationConfig) -> Self {
+        Self {
+            config,
+            scores: HashMap::new(),
+        }
+    }
+    
+    pub fn config(&self) -> &ReputationConfig {
+        &self.config
+    }
+    
+    // Applies an event to the node's score for `task_type` and returns the new score
+    pub fn record(&mut self, node: &str, task_type: &str, event: ReputationEvent, now: u64) -> u64 {
+        let current = self.decayed(self.entry(node, task_type), now);
+        let value = (current + self.config.delta(event)).clamp(0.0, self.config.max_score);
+        
+        self.scores
+            .entry(node.to_string())
+            .or_default()
+            .insert(task_type.to_string(), Score { value, updated_at: now });
+        
+        debug!("Reputation of {} for {}: {:.1} -> {:.1} ({:?})", node, task_type, current, value, event);
+        value.round() as u64
+    }
+    
+    pub fn score(&self, node: &str, task_type: &str, now: u64) -> u64 {
+        self.decayed(self.entry(node, task_type), now).round() as u64
+    }
+    
+    // Mean over the task types the node has a history for
+    pub fn overall(&self, node: &str, now: u64) -> u64 {
+        let Some(types) = self.scores.get(node).filter(|types| !types.is_empty()) else {
+            return self.config.initial_score.round() as u64;
+        };
+        
+        let sum: f64 = types.values().map(|score| self.decayed(Some(score), now)).sum();
+        (sum / types.len() as f64).round() as u64
+    }
+    
+    pub fn task_types(&self, node: &str) -> Vec<&str> {
+        let mut types: Vec<&str> = self
+            .scores
+            .get(node)
+            .map(|types| types.keys().map(String::as_str).collect())
+            .unwrap_or_default();
+        types.sort();
+        types
+    }
+    
+    // Scores for `task_type`, e.g. for `ConsensusEngine::calculate_rewards_weighted`
+    pub fn weights<'a>(&self, task_type: &str, nodes: impl IntoIterator<Item = &'a str>, now: u64) -> HashMap<String, u64> {
+        nodes
+            .into_iter()
+            .map(|node| (node.to_string(), self.score(node, task_type, now)))
+            .collect()
+    }
+    
+    // Candidates for a task, best first; ties are ordered by address
+    pub fn rank<'a>(&self, task_type: &str, candidates: impl IntoIterator<Item = &'a str>, now: u64) -> Vec<(String, u64)> {
+        let mut ranked: Vec<(String, u64)> = candidates
+            .into_iter()
+            .map(|node| (node.to_string(), self.score(node, task_type, now)))
+            .collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        ranked
+    }
+    
+    // Credits or penalizes the prover once the proof is finalized. Returns
+    // the status applied, or `None` while the proof is still pending.
+    pub fn record_consensus(
+        &mut self,
+        engine: &ConsensusEngine,
+        proof_id: &str,
+        task_type: &str,
+        now: u64,
+    ) -> Option<ProofStatus> {
+        let prover = engine.get_proof(proof_id)?.prover.clone();
+        let status = engine.status(proof_id)?;
+        
+        let event = match status {
+            ProofStatus::Verified => ReputationEvent::ProofAccepted,
+            ProofStatus::Rejected => ReputationEvent::ProofRejected,
+            ProofStatus::Pending => return None,
+        };
+        self.record(&prover, task_type, event, now);
+        
+        Some(status)
+    }
+    
+    // Applies a resolved dispute: the challenger wins or loses, and an
+    // overturned proof costs the prover and everyone who approved it
+    pub fn record_dispute(&mut self, dispute: &Dispute, prover: &str, task_type: &str, now: u64) {
+        let Some(outcome) = &dispute.outcome else {
+            return;
+        };
+        let challenger = &dispute.challenge.challenger;
+        
+        match outcome.resolution {
+            Resolution::ProofUpheld => {
+                self.record(challenger, task_type, ReputationEvent::ChallengeLost, now);
+            }
+            Resolution::ProofOverturned => {
+                self.record(challenger, task_type, ReputationEvent::ChallengeWon, now);
+                self.record(prover, task_type, ReputationEvent::ProofOverturned, now);
+                
+                for approver in outcome.slashed.keys().filter(|node| node.as_str() != prover) {
+                    self.record(approver, task_type, ReputationEvent::ApprovedInvalidProof, now);
+                }
+            }
+        }
+    }
+    
+    fn entry(&self, node: &str, task_type: &str) -> Option<&Score> {
+        self.scores.get(node).and_then(|types| types.get(task_type))
+    }
+    
+    // Exponential decay toward `initial_score`, so old rewards and old
+    // penalties both fade
+    fn decayed(&self, score: Option<&Score>, now: u64) -> f64 {
+        let initial = self.config.initial_score;
+        let Some(score) = score else {
+            return initial;
+        };
+        if self.config.half_life_secs == 0 {
+            return score.value;
+        }
+        
+        let elapsed = now.saturating_sub(score.updated_at) as f64;
+        let factor = 0.5f64.powf(elapsed / self.config.half_life_secs as f64);
+        initial + (score.value - initial) * factor
+    }
+}
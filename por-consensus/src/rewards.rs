@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::Stake;
+
+const BPS: u128 = 10_000;
+
+// Weights are scaled down to at most this total before dividing, which keeps
+// every intermediate product within u128 whatever the reward
+const MAX_TOTAL_WEIGHT: u128 = 1 << 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RewardPolicy {
+    // Prover's share of the task reward, in basis points; approving
+    // validators split the rest
+    pub prover_share_bps: u32,
+}
+
+impl Default for RewardPolicy {
+    fn default() -> Self {
+        Self { prover_share_bps: 7_000 }
+    }
+}
+
+// Splits `reward` between the prover and the approving validators, weighted
+// by `approvers` (stake, reputation, ...). Integer division leaves at most one
+// unit per approver undistributed; those go to the largest remainders, so the
+// payouts always add up to exactly `reward`. Without approvers the prover
+// receives everything.
+pub fn split_reward(
+    reward: Stake,
+    prover: &str,
+    approvers: &BTreeMap<String, u128>,
+    policy: &RewardPolicy,
+) -> BTreeMap<String, Stake> {
+    let mut payouts = BTreeMap::new();
+    
+    if approvers.is_empty() {
+        payouts.insert(prover.to_string(), reward);
+        return payouts;
+    }
+    
+    let prover_amount = basis_points(reward, policy.prover_share_bps);
+    let pool = reward - prover_amount;
+    payouts.insert(prover.to_string(), prover_amount);
+    
+    let weights = normalize(approvers);
+    let total: u128 = weights.values().sum();
+    
+    let mut remainders = Vec::with_capacity(weights.len());
+    let mut distributed = 0;
+    for (approver, weight) in &weights {
+        // pool * weight / total, split so nothing overflows
+        let product_rest = pool % total * weight;
+        let amount = pool / total * weight + product_rest / total;
+        
+        distributed += amount;
+        remainders.push((product_rest % total, approver.as_str()));
+        *payouts.entry(approver.clone()).or_insert(0) += amount;
+    }
+    
+    // Largest remainder first, ties broken by address for determinism
+    remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+    let dust = pool - distributed;
+    for (_, approver) in remainders.into_iter().take(dust as usize) {
+        *payouts.get_mut(approver).unwrap() += 1;
+    }
+    
+    payouts
+}
+
+// Rewards credited per node. Each proof is paid out at most once.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RewardLedger {
+    balances: BTreeMap<String, Stake>,
+    rewarded: BTreeSet<String>,
+}
+
+impl RewardLedger {
+    pub fn balance(&self, node: &str) -> Stake {
+        self.balances.get(node).copied().unwrap_or(0)
+    }
+    
+    pub fn balances(&self) -> &BTreeMap<String, Stake> {
+        &self.balances
+    }
+    
+    pub fn is_rewarded(&self, proof_id: &str) -> bool {
+        self.rewarded.contains(proof_id)
+    }
+    
+    // Returns false, crediting nothing, if the proof was already paid
+    pub(crate) fn credit<'a>(&mut self, proof_id: &str, payouts: impl IntoIterator<Item = (&'a String, &'a Stake)>) -> bool {
+        if !self.rewarded.insert(proof_id.to_string()) {
+            return false;
+        }
+        
+        for (node, amount) in payouts {
+            let balance = self.balances.entry(node.clone()).or_insert(0);
+            *balance = balance.saturating_add(*amount);
+        }
+        true
+    }
+}
+
+// `amount * bps / 10_000` without overflowing on large amounts
+pub(crate) fn basis_points(amount: Stake, bps: u32) -> Stake {
+    let bps = (bps as u128).min(BPS);
+    amount / BPS * bps + amount % BPS * bps / BPS
+}
+
+fn normalize(weights: &BTreeMap<String, u128>) -> BTreeMap<String, u128> {
+    let total = weights.values().fold(0u128, |sum, w| sum.saturating_add(*w));
+    
+    // All-zero weights (e.g. no reputation yet) fall back to an equal split
+    if total == 0 {
+        return weights.keys().map(|k| (k.clone(), 1)).collect();
+    }
+    
+    let shift = (128 - total.leading_zeros()).saturating_sub(MAX_TOTAL_WEIGHT.trailing_zeros());
+    let scaled: BTreeMap<String, u128> = weights.iter().map(|(k, w)| (k.clone(), w >> shift)).collect();
+    
+    if scaled.values().all(|w| *w == 0) {
+        return weights.keys().map(|k| (k.clone(), 1)).collect();
+    }
+    
+    scaled
+}
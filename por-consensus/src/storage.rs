@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+use shared::types::ProofOfReasoning;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use thiserror::Error;
+
+use crate::dispute::Dispute;
+use crate::rewards::RewardLedger;
+use crate::{Stake, VoteTally};
+
+// Bumped whenever `ConsensusState` changes shape; `decode` is where older
+// versions get migrated
+pub const SCHEMA_VERSION: u32 = 2;
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("consensus state has schema version {found}, this build reads versions 1 to {SCHEMA_VERSION}")]
+    UnsupportedVersion { found: u32 },
+    #[error("consensus state is corrupt: {0}")]
+    Encoding(#[from] serde_json::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+// Everything needed to resume consensus after a restart. Thresholds and the
+// reward policy are configuration and are not stored.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConsensusState {
+    pub version: u32,
+    pub proofs: BTreeMap<String, ProofOfReasoning>,
+    pub tallies: BTreeMap<String, VoteTally>,
+    pub validators: BTreeMap<String, Stake>,
+    pub ledger: RewardLedger,
+    // Added in version 2
+    #[serde(default)]
+    pub disputes: BTreeMap<String, Dispute>,
+}
+
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+pub fn encode(state: &ConsensusState) -> Result<Vec<u8>, StorageError> {
+    Ok(serde_json::to_vec(state)?)
+}
+
+pub fn decode(bytes: &[u8]) -> Result<ConsensusState, StorageError> {
+    let header: Header = serde_json::from_slice(bytes)?;
+    match header.version {
+        SCHEMA_VERSION => Ok(serde_json::from_slice(bytes)?),
+        // Version 1 had no disputes
+        1 => {
+            let mut state: ConsensusState = serde_json::from_slice(bytes)?;
+            state.version = SCHEMA_VERSION;
+            Ok(state)
+        }
+        found => Err(StorageError::UnsupportedVersion { found }),
+    }
+}
+
+// Where a `ConsensusEngine` keeps its state. `save` replaces the whole
+// snapshot and must be atomic: after a crash `load` returns either the old
+// or the new state, never a mix.
+pub trait StateStore: Send + Sync {
+    fn load(&self) -> Result<Option<ConsensusState>, StorageError>;
+    
+    fn save(&self, state: &ConsensusState) -> Result<(), StorageError>;
+}
+
+// Keeps the encoded snapshot in memory, for tests and ephemeral nodes
+#[derive(Default)]
+pub struct MemoryStore {
+    bytes: Mutex<Option<Vec<u8>>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StateStore for MemoryStore {
+    fn load(&self) -> Result<Option<ConsensusState>, StorageError> {
+        self.bytes.lock().unwrap().as_deref().map(decode).transpose()
+    }
+    
+    fn save(&self, state: &ConsensusState) -> Result<(), StorageError> {
+        let bytes = encode(state)?;
+        *self.bytes.lock().unwrap() = Some(bytes);
+        Ok(())
+    }
+}
+
+// Single JSON file, replaced with write, fsync and rename
+pub struct FileStore {
+    path: PathBuf,
+}
+
+impl FileStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+    
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl StateStore for FileStore {
+    fn load(&self) -> Result<Option<ConsensusState>, StorageError> {
+        match fs::read(&self.path) {
+            Ok(bytes) => decode(&bytes).map(Some),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+    
+    fn save(&self, state: &ConsensusState) -> Result<(), StorageError> {
+        let bytes = encode(state)?;
+        let dir = self.path.parent().filter(|dir| !dir.as_os_str().is_empty());
+        if let Some(dir) = dir {
+            fs::create_dir_all(dir)?;
+        }
+        
+        let tmp = self.path.with_extension("json.tmp");
+        let mut file = File::create(&tmp)?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+        fs::rename(&tmp, &self.path)?;
+        
+        // Make the rename itself durable
+        #[cfg(unix)]
+        if let Some(dir) = dir {
+            File::open(dir)?.sync_all()?;
+        }
+        
+        Ok(())
+    }
+}
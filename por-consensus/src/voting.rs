@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Stake in wei, as in `CognitiveRegistry.CognitiveNode.stakedAmount`
+pub type Stake = u128;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProofStatus {
+    Pending,
+    Verified,
+    Rejected,
+}
+
+// Share of the total stake that approvals must strictly exceed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Threshold {
+    numerator: u64,
+    denominator: u64,
+}
+
+impl Threshold {
+    pub fn new(numerator: u64, denominator: u64) -> Option<Self> {
+        // Approvals can never strictly exceed the whole stake
+        if denominator == 0 || numerator >= denominator {
+            return None;
+        }
+        
+        Some(Self { numerator, denominator })
+    }
+    
+    // Classic BFT supermajority: more than 2/3 of the stake
+    pub fn two_thirds() -> Self {
+        Self {
+            numerator: 2,
+            denominator: 3,
+        }
+    }
+    
+    pub fn is_exceeded(&self, stake: Stake, total: Stake) -> bool {
+        // Saturates only for stakes beyond any realistic token supply
+        stake.saturating_mul(self.denominator as u128) > total.saturating_mul(self.numerator as u128)
+    }
+}
+
+impl Default for Threshold {
+    fn default() -> Self {
+        Self::two_thirds()
+    }
+}
+
+// Votes on one proof. The validator set and stakes are snapshotted when the
+// proof is submitted, so later stake changes cannot move an open vote.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoteTally {
+    eligible: BTreeMap<String, Stake>,
+    approvals: BTreeMap<String, Stake>,
+    rejections: BTreeMap<String, Stake>,
+    status: ProofStatus,
+}
+
+impl VoteTally {
+    pub(crate) fn new(eligible: BTreeMap<String, Stake>) -> Self {
+        Self {
+            eligible,
+            approvals: BTreeMap::new(),
+            rejections: BTreeMap::new(),
+            status: ProofStatus::Pending,
+        }
+    }
+    
+    pub fn status(&self) -> ProofStatus {
+        self.status
+    }
+    
+    pub fn stake_of(&self, validator: &str) -> Option<Stake> {
+        self.eligible.get(validator).copied()
+    }
+    
+    pub fn has_voted(&self, validator: &str) -> bool {
+        self.approvals.contains_key(validator) || self.rejections.contains_key(validator)
+    }
+    
+    pub fn approvals(&self) -> impl Iterator<Item = &str> {
+        self.approvals.keys().map(String::as_str)
+    }
+    
+    // Approving validators with the stake they voted with
+    pub fn approval_stakes(&self) -> &BTreeMap<String, Stake> {
+        &self.approvals
+    }
+    
+    pub fn rejections(&self) -> impl Iterator<Item = &str> {
+        self.rejections.keys().map(String::as_str)
+    }
+    
+    pub fn total_stake(&self) -> Stake {
+        self.eligible.values().sum()
+    }
+    
+    pub fn approved_stake(&self) -> Stake {
+        self.approvals.values().sum()
+    }
+    
+    pub fn rejected_stake(&self) -> Stake {
+        self.rejections.values().sum()
+    }
+    
+    pub(crate) fn record(&mut self, validator: &str, stake: Stake, approved: bool) {
+        let votes = if approved { &mut self.approvals } else { &mut self.rejections };
+        votes.insert(validator.to_string(), stake);
+    }
+    
+    pub(crate) fn set_status(&mut self, status: ProofStatus) {
+        self.status = status;
+    }
+    
+    // Final once approvals exceed the threshold with at least
+    // `min_confirmations` approving validators; rejected as soon as that is
+    // out of reach even if every remaining validator approves.
+    pub(crate) fn evaluate(&self, threshold: Threshold, min_confirmations: u32) -> ProofStatus {
+        let total = self.total_stake();
+        let approvals = self.approvals.len() as u64;
+        
+        if threshold.is_exceeded(self.approved_stake(), total) && approvals >= min_confirmations as u64 {
+            return ProofStatus::Verified;
+        }
+        
+        let undecided = self.eligible.len() as u64 - approvals - self.rejections.len() as u64;
+        let reachable_stake = total - self.rejected_stake();
+        
+        if !threshold.is_exceeded(reachable_stake, total) || approvals + undecided < min_confirmations as u64 {
+            return ProofStatus::Rejected;
+        }
+        
+        ProofStatus::Pending
+    }
+}
@@ -1,8 +1,19 @@
 #[cfg(test)]
 mod tests {
-    use por_consensus::ConsensusEngine;
+    use por_consensus::{ConsensusEngine, ConsensusError, ProofStatus, Threshold};
     use shared::types::ProofOfReasoning;
 
+    fn pending_proof() -> ProofOfReasoning {
+        ProofOfReasoning {
+            input_hash: "0xabc123".to_string(),
+            output_hash: "0xdef456".to_string(),
+            prover: "prover1".to_string(),
+            timestamp: 1234567890,
+            verified: false,
+            confirmations: 0,
+        }
+    }
+
     #[test]
     fn test_proof_submission() {
         let mut engine = ConsensusEngine::new(3);
@@ -48,30 +59,36 @@ mod tests {
 
     #[test]
     fn test_reward_calculation() {
-        let mut engine = ConsensusEngine::new(3);
+        let mut engine = ConsensusEngine::new(2);
         
         engine.add_validator("validator1".to_string());
         engine.add_validator("validator2".to_string());
         engine.add_validator("validator3".to_string());
         
-        let proof = ProofOfReasoning {
-            input_hash: "0xabc123".to_string(),
-            output_hash: "0xdef456".to_string(),
-            prover: "prover1".to_string(),
-            timestamp: 1234567890,
-            verified: false,
-            confirmations: 3,
-        };
+        engine.submit_proof(pending_proof()).unwrap();
         
-        engine.submit_proof(proof).unwrap();
+        // Nothing is paid before the proof is verified
+        assert!(engine.calculate_rewards("0xabc123", 1_000).unwrap().is_empty());
+        
+        engine.validate_proof("0xabc123", "validator1").unwrap();
+        engine.validate_proof("0xabc123", "validator2").unwrap();
+        engine.validate_proof("0xabc123", "validator3").unwrap();
+        assert_eq!(engine.status("0xabc123"), Some(ProofStatus::Verified));
         
-        let rewards = engine.calculate_rewards("0xabc123").unwrap();
+        let rewards = engine.calculate_rewards("0xabc123", 1_000).unwrap();
         
-        // Prover should get 70% (700)
+        // Prover should get 70% of the task reward
         assert_eq!(rewards.get("prover1"), Some(&700));
         
-        // Each validator should get share of 30%
-        assert!(rewards.get("validator1").is_some());
+        // Approving validators share the remaining 30%
+        assert_eq!(rewards.get("validator1"), Some(&100));
+        assert_eq!(rewards.get("validator3"), Some(&100));
+        assert_eq!(rewards.values().sum::<u128>(), 1_000);
+        
+        assert!(matches!(
+            engine.calculate_rewards("0xmissing", 1_000),
+            Err(ConsensusError::UnknownProof(_))
+        ));
     }
 
     #[test]
@@ -99,4 +116,157 @@ mod tests {
         assert_eq!(verified.len(), 1);
         assert!(verified[0].verified);
     }
+
+    #[test]
+    fn test_only_registered_validators_vote_once() {
+        let mut engine = ConsensusEngine::new(1);
+        
+        engine.add_validator("validator1".to_string());
+        engine.add_validator("validator2".to_string());
+        engine.add_validator("prover1".to_string());
+        engine.submit_proof(pending_proof()).unwrap();
+        
+        assert!(matches!(
+            engine.validate_proof("0xabc123", "outsider"),
+            Err(ConsensusError::NotAValidator { .. })
+        ));
+        assert!(matches!(
+            engine.validate_proof("0xabc123", "prover1"),
+            Err(ConsensusError::OwnProof(_))
+        ));
+        assert!(matches!(
+            engine.validate_proof("0xunknown", "validator1"),
+            Err(ConsensusError::UnknownProof(_))
+        ));
+        
+        assert!(!engine.validate_proof("0xabc123", "validator1").unwrap());
+        assert_eq!(
+            engine.validate_proof("0xabc123", "validator1"),
+            Err(ConsensusError::AlreadyVoted {
+                proof_id: "0xabc123".to_string(),
+                validator: "validator1".to_string(),
+            })
+        );
+        
+        // A single validator counts once, so the proof is still open
+        assert_eq!(engine.status("0xabc123"), Some(ProofStatus::Pending));
+        assert_eq!(engine.tally("0xabc123").unwrap().approved_stake(), 1);
+    }
+
+    #[test]
+    fn test_stake_weighted_finality() {
+        let mut engine = ConsensusEngine::new(1);
+        
+        engine.add_validator_with_stake("whale".to_string(), 70);
+        engine.add_validator_with_stake("small1".to_string(), 15);
+        engine.add_validator_with_stake("small2".to_string(), 15);
+        engine.submit_proof(pending_proof()).unwrap();
+        
+        // 30 of 100 is not a supermajority
+        assert!(!engine.validate_proof("0xabc123", "small1").unwrap());
+        assert!(!engine.validate_proof("0xabc123", "small2").unwrap());
+        
+        // 100 of 100
+        assert!(engine.validate_proof("0xabc123", "whale").unwrap());
+        
+        assert!(matches!(
+            engine.reject_proof("0xabc123", "small1"),
+            Err(ConsensusError::Finalized(_, ProofStatus::Verified))
+        ));
+    }
+
+    #[test]
+    fn test_rejected_when_supermajority_unreachable() {
+        let mut engine = ConsensusEngine::new(1);
+        
+        for validator in ["validator1", "validator2", "validator3", "validator4"] {
+            engine.add_validator(validator.to_string());
+        }
+        engine.submit_proof(pending_proof()).unwrap();
+        
+        assert_eq!(engine.reject_proof("0xabc123", "validator1").unwrap(), ProofStatus::Pending);
+        
+        // 2 of 4 can at most approve: 2/4 does not exceed 2/3
+        assert_eq!(engine.reject_proof("0xabc123", "validator2").unwrap(), ProofStatus::Rejected);
+        assert_eq!(engine.get_rejected_proofs().len(), 1);
+        assert!(engine.get_verified_proofs().is_empty());
+        
+        let tally = engine.tally("0xabc123").unwrap();
+        assert_eq!(tally.rejections().collect::<Vec<_>>(), vec!["validator1", "validator2"]);
+    }
+
+    #[test]
+    fn test_custom_threshold_and_minimum_confirmations() {
+        let mut engine = ConsensusEngine::new(2).with_threshold(Threshold::new(1, 2).unwrap());
+        
+        engine.add_validator_with_stake("validator1".to_string(), 60);
+        engine.add_validator_with_stake("validator2".to_string(), 20);
+        engine.add_validator_with_stake("validator3".to_string(), 20);
+        engine.submit_proof(pending_proof()).unwrap();
+        
+        // Enough stake, but only one approving validator
+        assert!(!engine.validate_proof("0xabc123", "validator1").unwrap());
+        assert!(engine.validate_proof("0xabc123", "validator2").unwrap());
+        
+        assert!(Threshold::new(3, 2).is_none());
+        assert!(Threshold::new(2, 2).is_none());
+        assert!(Threshold::new(1, 0).is_none());
+    }
+
+    #[test]
+    fn test_resubmission_keeps_votes() {
+        let mut engine = ConsensusEngine::new(1);
+        
+        engine.add_validator("validator1".to_string());
+        engine.add_validator("validator2".to_string());
+        engine.submit_proof(pending_proof()).unwrap();
+        engine.reject_proof("0xabc123", "validator1").unwrap();
+        
+        assert_eq!(
+            engine.submit_proof(pending_proof()),
+            Err(ConsensusError::DuplicateProof("0xabc123".to_string()))
+        );
+        assert!(engine.tally("0xabc123").unwrap().has_voted("validator1"));
+    }
+
+    #[test]
+    fn test_submitted_verified_flag_is_ignored() {
+        let mut engine = ConsensusEngine::new(1);
+        
+        engine.add_validator("validator1".to_string());
+        engine.add_validator("validator2".to_string());
+        
+        let mut proof = pending_proof();
+        proof.verified = true;
+        proof.confirmations = 3;
+        engine.submit_proof(proof).unwrap();
+        
+        // Nothing is final or paid until the validators vote
+        assert_eq!(engine.status("0xabc123"), Some(ProofStatus::Pending));
+        assert!(!engine.get_proof("0xabc123").unwrap().verified);
+        assert_eq!(engine.get_proof("0xabc123").unwrap().confirmations, 0);
+        assert!(engine.get_verified_proofs().is_empty());
+        assert!(engine.calculate_rewards("0xabc123", 1_000).unwrap().is_empty());
+        
+        assert!(!engine.validate_proof("0xabc123", "validator1").unwrap());
+        assert!(engine.validate_proof("0xabc123", "validator2").unwrap());
+    }
+
+    #[test]
+    fn test_validator_set_snapshot() {
+        let mut engine = ConsensusEngine::new(1);
+        
+        engine.add_validator("validator1".to_string());
+        engine.submit_proof(pending_proof()).unwrap();
+        
+        // Joined after the proof was submitted
+        engine.add_validator("validator2".to_string());
+        assert!(matches!(
+            engine.validate_proof("0xabc123", "validator2"),
+            Err(ConsensusError::NotAValidator { .. })
+        ));
+        
+        assert!(engine.validate_proof("0xabc123", "validator1").unwrap());
+    }
+
 }
@@ -0,0 +1,296 @@
+#[cfg(test)]
+mod tests {
+    use por_consensus::dispute::{DisputeConfig, DisputeError, DisputeManager, Resolution};
+    use por_consensus::storage::{ConsensusState, MemoryStore, StateStore, StorageError};
+    use por_consensus::{ConsensusEngine, ConsensusError, ProofStatus};
+    use serde_json::json;
+    use shared::hashing::{result_hash, to_hex};
+    use shared::types::{CognitiveNode, ProofOfReasoning, ReasoningResult};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    const ETHER: u128 = 1_000_000_000_000_000_000;
+
+    fn node(address: &str, staked_amount: u128) -> CognitiveNode {
+        CognitiveNode {
+            validator_address: address.to_string(),
+            node_id: address.to_string(),
+            staked_amount,
+            reputation_score: 100,
+            tasks_completed: 0,
+            is_active: true,
+            registered_at: 0,
+        }
+    }
+
+    fn result(price: f64) -> ReasoningResult {
+        ReasoningResult {
+            prediction: json!({ "predicted_price": price, "trend": "bullish" }),
+            confidence_score: 0.85,
+            computation_time_ms: 150,
+            ..ReasoningResult::default()
+        }
+    }
+
+    // Accepts saves until told to fail
+    #[derive(Default)]
+    struct FlakyStore {
+        inner: MemoryStore,
+        failing: AtomicBool,
+    }
+
+    impl StateStore for FlakyStore {
+        fn load(&self) -> Result<Option<ConsensusState>, StorageError> {
+            self.inner.load()
+        }
+        
+        fn save(&self, state: &ConsensusState) -> Result<(), StorageError> {
+            if self.failing.load(Ordering::SeqCst) {
+                return Err(std::io::Error::other("disk full").into());
+            }
+            self.inner.save(state)
+        }
+    }
+
+    // prover1 claims 1.5; validator1..3 each stake 1 MATIC, challenger stakes 1 MATIC
+    fn setup() -> (ConsensusEngine, DisputeManager) {
+        setup_with_store(Arc::new(MemoryStore::new()))
+    }
+
+    fn setup_with_store(store: Arc<dyn StateStore>) -> (ConsensusEngine, DisputeManager) {
+        let mut engine = ConsensusEngine::open(1, store).unwrap();
+        engine.add_node(&node("prover1", 2 * ETHER));
+        for validator in ["validator1", "validator2", "validator3", "challenger"] {
+            engine.add_node(&node(validator, ETHER));
+        }
+        
+        engine
+            .submit_proof(ProofOfReasoning {
+                input_hash: "0xabc123".to_string(),
+                output_hash: to_hex(&result_hash(&result(1.5)).unwrap()),
+                prover: "prover1".to_string(),
+                timestamp: 1_000,
+                verified: false,
+                confirmations: 0,
+            })
+            .unwrap();
+        
+        engine.validate_proof("0xabc123", "validator1").unwrap();
+        engine.validate_proof("0xabc123", "validator2").unwrap();
+        engine.validate_proof("0xabc123", "validator3").unwrap();
+        assert_eq!(engine.status("0xabc123"), Some(ProofStatus::Verified));
+        
+        (engine, DisputeManager::new(DisputeConfig::default()))
+    }
+
+    #[test]
+    fn test_successful_challenge_slashes_prover_and_approvers() {
+        let (mut engine, disputes) = setup();
+        
+        disputes.open_challenge(&mut engine, "0xabc123", "challenger", &result(1.4), 1_500).unwrap();
+        let outcome = disputes.resolve(&mut engine, "0xabc123", &result(1.4)).unwrap().clone();
+        
+        assert_eq!(outcome.resolution, Resolution::ProofOverturned);
+        assert_eq!(outcome.recipient, "challenger");
+        assert_eq!(outcome.slashed["prover1"], ETHER);
+        assert_eq!(outcome.slashed["validator1"], ETHER / 10);
+        assert_eq!(outcome.payout, ETHER + 3 * ETHER / 10);
+        
+        assert_eq!(engine.stake_of("prover1"), Some(ETHER));
+        assert_eq!(engine.stake_of("validator2"), Some(ETHER - ETHER / 10));
+        assert_eq!(engine.stake_of("challenger"), Some(ETHER + outcome.payout));
+        
+        // The proof no longer counts as verified
+        assert_eq!(engine.status("0xabc123"), Some(ProofStatus::Rejected));
+        assert!(engine.get_verified_proofs().is_empty());
+    }
+
+    #[test]
+    fn test_failed_challenge_forfeits_bond() {
+        let (mut engine, disputes) = setup();
+        let bond = disputes.config().challenger_bond;
+        
+        // The bond is held from the moment the challenge opens
+        disputes.open_challenge(&mut engine, "0xabc123", "challenger", &result(1.4), 1_500).unwrap();
+        assert_eq!(engine.stake_of("challenger"), Some(ETHER - bond));
+        
+        let outcome = disputes.resolve(&mut engine, "0xabc123", &result(1.5)).unwrap().clone();
+        
+        assert_eq!(outcome.resolution, Resolution::ProofUpheld);
+        assert_eq!(outcome.recipient, "prover1");
+        assert_eq!(outcome.payout, bond);
+        assert_eq!(engine.stake_of("challenger"), Some(ETHER - bond));
+        assert_eq!(engine.stake_of("prover1"), Some(2 * ETHER + bond));
+        assert_eq!(engine.status("0xabc123"), Some(ProofStatus::Verified));
+        
+        assert!(matches!(
+            disputes.resolve(&mut engine, "0xabc123", &result(1.5)),
+            Err(DisputeError::NoChallenge(_))
+        ));
+    }
+
+    #[test]
+    fn test_challenge_rules() {
+        let (mut engine, disputes) = setup();
+        engine.add_node(&node("pauper", 1_000));
+        
+        // The evidence has to disagree with the proof
+        assert!(matches!(
+            disputes.open_challenge(&mut engine, "0xabc123", "challenger", &result(1.5), 1_500),
+            Err(DisputeError::NothingToDispute(_))
+        ));
+        assert!(matches!(
+            disputes.open_challenge(&mut engine, "0xabc123", "prover1", &result(1.4), 1_500),
+            Err(DisputeError::OwnProof(_))
+        ));
+        assert!(matches!(
+            disputes.open_challenge(&mut engine, "0xabc123", "pauper", &result(1.4), 1_500),
+            Err(DisputeError::InsufficientStake { .. })
+        ));
+        assert!(matches!(
+            disputes.open_challenge(&mut engine, "0xabc123", "challenger", &result(1.4), 1_000 + 3_601),
+            Err(DisputeError::WindowClosed(_))
+        ));
+        
+        disputes.open_challenge(&mut engine, "0xabc123", "challenger", &result(1.4), 1_500).unwrap();
+        assert!(matches!(
+            disputes.open_challenge(&mut engine, "0xabc123", "validator1", &result(1.4), 1_500),
+            Err(DisputeError::AlreadyChallenged(_))
+        ));
+    }
+
+    #[test]
+    fn test_unstaked_prover_cannot_escape() {
+        let (mut engine, disputes) = setup();
+        
+        // Leaving the validator set does not make a proof unchallengeable
+        engine.remove_validator("prover1");
+        assert!(matches!(
+            disputes.open_challenge(&mut engine, "0xabc123", "challenger", &result(1.4), 1_500),
+            Err(DisputeError::UnstakedProver(_))
+        ));
+        
+        // Nor does leaving once it is challenged: the dispute stays open
+        engine.add_node(&node("prover1", 2 * ETHER));
+        disputes.open_challenge(&mut engine, "0xabc123", "challenger", &result(1.4), 1_500).unwrap();
+        engine.remove_validator("prover1");
+        assert!(matches!(
+            disputes.resolve(&mut engine, "0xabc123", &result(1.4)),
+            Err(DisputeError::UnstakedProver(_))
+        ));
+        assert_eq!(engine.stake_of("validator1"), Some(ETHER));
+        assert!(engine.dispute("0xabc123").unwrap().outcome.is_none());
+    }
+
+    #[test]
+    fn test_challenger_cannot_escape_bond() {
+        let (mut engine, disputes) = setup();
+        let bond = disputes.config().challenger_bond;
+        
+        disputes.open_challenge(&mut engine, "0xabc123", "challenger", &result(1.4), 1_500).unwrap();
+        engine.remove_validator("challenger");
+        
+        let outcome = disputes.resolve(&mut engine, "0xabc123", &result(1.5)).unwrap().clone();
+        assert_eq!(outcome.slashed["challenger"], bond);
+        assert_eq!(outcome.payout, bond);
+        assert_eq!(engine.stake_of("prover1"), Some(2 * ETHER + bond));
+    }
+
+    #[test]
+    fn test_disputes_survive_restart() {
+        let store = Arc::new(MemoryStore::new());
+        let (mut engine, disputes) = setup_with_store(store.clone());
+        let bond = disputes.config().challenger_bond;
+        
+        disputes.open_challenge(&mut engine, "0xabc123", "challenger", &result(1.4), 1_500).unwrap();
+        drop(engine);
+        
+        let mut engine = ConsensusEngine::open(1, store.clone()).unwrap();
+        assert!(engine.dispute("0xabc123").unwrap().outcome.is_none());
+        assert_eq!(engine.stake_of("challenger"), Some(ETHER - bond));
+        assert!(matches!(
+            disputes.open_challenge(&mut engine, "0xabc123", "validator1", &result(1.4), 1_500),
+            Err(DisputeError::AlreadyChallenged(_))
+        ));
+        
+        disputes.resolve(&mut engine, "0xabc123", &result(1.4)).unwrap();
+        drop(engine);
+        
+        let engine = ConsensusEngine::open(1, store).unwrap();
+        let outcome = engine.dispute("0xabc123").unwrap().outcome.clone().unwrap();
+        assert_eq!(outcome.resolution, Resolution::ProofOverturned);
+        assert_eq!(engine.status("0xabc123"), Some(ProofStatus::Rejected));
+        assert_eq!(engine.stake_of("challenger"), Some(ETHER + outcome.payout));
+    }
+
+    #[test]
+    fn test_storage_errors_are_reported() {
+        let store = Arc::new(FlakyStore::default());
+        let (mut engine, disputes) = setup_with_store(store.clone());
+        store.failing.store(true, Ordering::SeqCst);
+        
+        assert!(matches!(engine.slash("validator1", 1), Err(ConsensusError::Storage(_))));
+        assert!(matches!(engine.credit("validator1", 1), Err(ConsensusError::Storage(_))));
+        assert!(matches!(
+            disputes.open_challenge(&mut engine, "0xabc123", "challenger", &result(1.4), 1_500),
+            Err(DisputeError::Storage(_))
+        ));
+    }
+
+    #[test]
+    fn test_settlement() {
+        let (mut engine, disputes) = setup();
+        let proof = engine.get_proof("0xabc123").unwrap().clone();
+        
+        assert!(!disputes.is_settled(&engine, &proof, 2_000));
+        assert!(disputes.is_settled(&engine, &proof, 5_000));
+        
+        // An open challenge keeps the proof unsettled past the window
+        disputes.open_challenge(&mut engine, "0xabc123", "challenger", &result(1.4), 2_000).unwrap();
+        assert!(!disputes.is_settled(&engine, &proof, 5_000));
+        
+        disputes.resolve(&mut engine, "0xabc123", &result(1.5)).unwrap();
+        assert!(disputes.is_settled(&engine, &proof, 5_000));
+    }
+
+    #[test]
+    fn test_prune_settled_proofs() {
+        let (mut engine, disputes) = setup();
+        engine
+            .submit_proof(ProofOfReasoning {
+                input_hash: "0xfed987".to_string(),
+                output_hash: to_hex(&result_hash(&result(1.5)).unwrap()),
+                prover: "prover1".to_string(),
+                timestamp: 1_000,
+                verified: false,
+                confirmations: 0,
+            })
+            .unwrap();
+        
+        // Nothing is final inside the window, and unpaid or pending proofs stay
+        assert_eq!(disputes.prune(&mut engine, 2_000), Ok(0));
+        assert_eq!(disputes.prune(&mut engine, 5_000), Ok(0));
+        
+        engine.distribute_rewards("0xabc123", 1_000).unwrap();
+        assert_eq!(disputes.prune(&mut engine, 5_000), Ok(1));
+        assert!(engine.get_proof("0xabc123").is_none());
+        assert!(engine.get_proof("0xfed987").is_some());
+        
+        // The ledger still refuses to pay the proof again
+        assert!(engine.ledger().is_rewarded("0xabc123"));
+    }
+
+    #[test]
+    fn test_inactive_nodes_do_not_vote() {
+        let mut engine = ConsensusEngine::new(1);
+        let mut inactive = node("validator1", ETHER);
+        inactive.is_active = false;
+        
+        engine.add_node(&node("validator1", ETHER));
+        assert_eq!(engine.stake_of("validator1"), Some(ETHER));
+        
+        engine.add_node(&inactive);
+        assert_eq!(engine.stake_of("validator1"), None);
+        assert_eq!(engine.slash("validator1", ETHER), Ok(0));
+    }
+}
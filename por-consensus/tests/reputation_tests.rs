@@ -0,0 +1,144 @@
+#[cfg(test)]
+mod tests {
+    use por_consensus::dispute::{DisputeConfig, DisputeManager};
+    use por_consensus::reputation::{ReputationBook, ReputationConfig, ReputationEvent};
+    use por_consensus::{ConsensusEngine, ProofStatus};
+    use serde_json::json;
+    use shared::hashing::{result_hash, to_hex};
+    use shared::types::{ProofOfReasoning, ReasoningResult};
+
+    const DAY: u64 = 24 * 3_600;
+
+    fn book() -> ReputationBook {
+        ReputationBook::new(ReputationConfig::default())
+    }
+
+    fn result(price: f64) -> ReasoningResult {
+        ReasoningResult {
+            prediction: json!({ "predicted_price": price }),
+            confidence_score: 0.85,
+            computation_time_ms: 150,
+            ..ReasoningResult::default()
+        }
+    }
+
+    fn engine_with_proof() -> ConsensusEngine {
+        let mut engine = ConsensusEngine::new(1);
+        for validator in ["validator1", "validator2", "challenger"] {
+            engine.add_validator_with_stake(validator.to_string(), 1_000);
+        }
+        engine.add_validator_with_stake("prover1".to_string(), 1_000);
+        
+        engine
+            .submit_proof(ProofOfReasoning {
+                input_hash: "0xabc123".to_string(),
+                output_hash: to_hex(&result_hash(&result(1.5)).unwrap()),
+                prover: "prover1".to_string(),
+                timestamp: 1_000,
+                verified: false,
+                confirmations: 0,
+            })
+            .unwrap();
+        engine
+    }
+
+    #[test]
+    fn test_events_adjust_score() {
+        let mut book = book();
+        assert_eq!(book.score("node1", "market_prediction", 0), 100);
+        
+        assert_eq!(book.record("node1", "market_prediction", ReputationEvent::ProofAccepted, 0), 110);
+        assert_eq!(book.record("node1", "market_prediction", ReputationEvent::MissedDeadline, 0), 95);
+        assert_eq!(book.record("node1", "market_prediction", ReputationEvent::ProofRejected, 0), 75);
+        
+        // Scores never go below zero
+        for _ in 0..5 {
+            book.record("node2", "market_prediction", ReputationEvent::ProofOverturned, 0);
+        }
+        assert_eq!(book.score("node2", "market_prediction", 0), 0);
+    }
+
+    #[test]
+    fn test_scores_are_per_task_type() {
+        let mut book = book();
+        for _ in 0..5 {
+            book.record("node1", "risk_scoring", ReputationEvent::ProofAccepted, 0);
+        }
+        book.record("node1", "market_prediction", ReputationEvent::ProofRejected, 0);
+        
+        assert_eq!(book.score("node1", "risk_scoring", 0), 150);
+        assert_eq!(book.score("node1", "market_prediction", 0), 80);
+        assert_eq!(book.score("node1", "sentiment_analysis", 0), 100);
+        assert_eq!(book.overall("node1", 0), 115);
+        assert_eq!(book.task_types("node1"), vec!["market_prediction", "risk_scoring"]);
+        
+        let ranked = book.rank("market_prediction", ["node1", "node2"], 0);
+        assert_eq!(ranked, vec![("node2".to_string(), 100), ("node1".to_string(), 80)]);
+    }
+
+    #[test]
+    fn test_decay_toward_initial_score() {
+        let mut book = book();
+        let half_life = book.config().half_life_secs;
+        
+        book.record("good", "market_prediction", ReputationEvent::ProofAccepted, 0);
+        book.record("good", "market_prediction", ReputationEvent::ProofAccepted, 0);
+        book.record("bad", "market_prediction", ReputationEvent::ProofRejected, 0);
+        
+        assert_eq!(book.score("good", "market_prediction", half_life), 110);
+        assert_eq!(book.score("bad", "market_prediction", half_life), 90);
+        assert_eq!(book.score("good", "market_prediction", 2 * half_life), 105);
+        assert_eq!(book.score("good", "market_prediction", 1_000 * DAY), 100);
+        
+        // New events build on the decayed score
+        assert_eq!(book.record("good", "market_prediction", ReputationEvent::ProofAccepted, half_life), 120);
+    }
+
+    #[test]
+    fn test_record_consensus() {
+        let mut engine = engine_with_proof();
+        let mut book = book();
+        
+        assert_eq!(book.record_consensus(&engine, "0xabc123", "market_prediction", 0), None);
+        
+        engine.validate_proof("0xabc123", "validator1").unwrap();
+        engine.validate_proof("0xabc123", "validator2").unwrap();
+        engine.validate_proof("0xabc123", "challenger").unwrap();
+        
+        assert_eq!(
+            book.record_consensus(&engine, "0xabc123", "market_prediction", 0),
+            Some(ProofStatus::Verified)
+        );
+        assert_eq!(book.score("prover1", "market_prediction", 0), 110);
+        
+        // Reputation can weight the reward split instead of stake
+        book.record("validator1", "market_prediction", ReputationEvent::ChallengeWon, 0);
+        let weights = book.weights("market_prediction", ["validator1", "validator2", "challenger"], 0);
+        let rewards = engine.calculate_rewards_weighted("0xabc123", 1_000, &weights).unwrap();
+        assert_eq!(rewards["validator1"], 110);
+        assert_eq!(rewards.values().sum::<u128>(), 1_000);
+    }
+
+    #[test]
+    fn test_record_dispute() {
+        let mut engine = engine_with_proof();
+        engine.validate_proof("0xabc123", "validator1").unwrap();
+        engine.validate_proof("0xabc123", "validator2").unwrap();
+        engine.validate_proof("0xabc123", "challenger").unwrap();
+        
+        let disputes = DisputeManager::new(DisputeConfig {
+            challenger_bond: 100,
+            ..DisputeConfig::default()
+        });
+        disputes.open_challenge(&mut engine, "0xabc123", "validator2", &result(1.4), 1_500).unwrap();
+        disputes.resolve(&mut engine, "0xabc123", &result(1.4)).unwrap();
+        
+        let mut book = book();
+        book.record_dispute(engine.dispute("0xabc123").unwrap(), "prover1", "market_prediction", 0);
+        
+        assert_eq!(book.score("prover1", "market_prediction", 0), 50);
+        assert_eq!(book.score("validator1", "market_prediction", 0), 80);
+        // The challenger had approved the proof too; winning only partly offsets that
+        assert_eq!(book.score("validator2", "market_prediction", 0), 95);
+    }
+}
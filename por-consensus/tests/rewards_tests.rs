@@ -0,0 +1,115 @@
+#[cfg(test)]
+mod tests {
+    use por_consensus::rewards::{split_reward, RewardPolicy};
+    use por_consensus::{ConsensusEngine, Stake};
+    use shared::types::ProofOfReasoning;
+    use std::collections::{BTreeMap, HashMap};
+
+    fn approvers(weights: &[(&str, u128)]) -> BTreeMap<String, u128> {
+        weights.iter().map(|(name, weight)| (name.to_string(), *weight)).collect()
+    }
+
+    fn verified_engine(stakes: &[(&str, Stake)], voters: &[&str]) -> ConsensusEngine {
+        let mut engine = ConsensusEngine::new(1);
+        for (validator, stake) in stakes {
+            engine.add_validator_with_stake(validator.to_string(), *stake);
+        }
+        
+        engine
+            .submit_proof(ProofOfReasoning {
+                input_hash: "0xabc123".to_string(),
+                output_hash: "0xdef456".to_string(),
+                prover: "prover1".to_string(),
+                timestamp: 1234567890,
+                verified: false,
+                confirmations: 0,
+            })
+            .unwrap();
+        for voter in voters {
+            engine.validate_proof("0xabc123", voter).unwrap();
+        }
+        
+        engine
+    }
+
+    #[test]
+    fn test_payouts_sum_to_reward() {
+        let weight_sets = [
+            approvers(&[("a", 1)]),
+            approvers(&[("a", 1), ("b", 1), ("c", 1)]),
+            approvers(&[("a", 3), ("b", 7), ("c", 11), ("d", 13)]),
+            approvers(&[("a", 1), ("b", u128::MAX / 2)]),
+            approvers(&[("a", 0), ("b", 0)]),
+        ];
+        let rewards = [0, 1, 2, 7, 999, 1_000, 10_007, 1_000_000_000_000_000_001, u128::MAX];
+        
+        for bps in [0, 3_333, 7_000, 10_000] {
+            let policy = RewardPolicy { prover_share_bps: bps };
+            for weights in &weight_sets {
+                for reward in rewards {
+                    let payouts = split_reward(reward, "prover", weights, &policy);
+                    let total = payouts.values().fold(0u128, |sum, amount| sum.checked_add(*amount).unwrap());
+                    assert_eq!(total, reward, "reward {} weights {:?} bps {}", reward, weights, bps);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_split_follows_weights() {
+        let policy = RewardPolicy::default();
+        
+        let payouts = split_reward(1_000, "prover", &approvers(&[("a", 1), ("b", 2)]), &policy);
+        assert_eq!(payouts["prover"], 700);
+        assert_eq!(payouts["a"], 100);
+        assert_eq!(payouts["b"], 200);
+        
+        // 300 split 3:2:2 floors to 128 + 85 + 85; the 2 units of dust go to
+        // the largest remainders
+        let payouts = split_reward(1_000, "prover", &approvers(&[("a", 3), ("b", 2), ("c", 2)]), &policy);
+        assert_eq!(payouts["a"], 128);
+        assert_eq!(payouts["b"], 86);
+        assert_eq!(payouts["c"], 86);
+        
+        // Zero weights alone fall back to an equal split
+        let payouts = split_reward(30, "prover", &approvers(&[("a", 0), ("b", 0)]), &RewardPolicy { prover_share_bps: 0 });
+        assert_eq!(payouts["a"], 15);
+        assert_eq!(payouts["b"], 15);
+    }
+
+    #[test]
+    fn test_no_approvers_pays_prover() {
+        let payouts = split_reward(1_000, "prover", &BTreeMap::new(), &RewardPolicy::default());
+        assert_eq!(payouts.len(), 1);
+        assert_eq!(payouts["prover"], 1_000);
+    }
+
+    #[test]
+    fn test_only_approvers_are_paid() {
+        let engine = verified_engine(
+            &[("validator1", 4), ("validator2", 1), ("validator3", 1), ("idle", 1)],
+            &["validator1", "validator2"],
+        );
+        
+        let rewards = engine.calculate_rewards("0xabc123", 1_000).unwrap();
+        assert_eq!(rewards.get("prover1"), Some(&700));
+        assert_eq!(rewards.get("validator1"), Some(&240));
+        assert_eq!(rewards.get("validator2"), Some(&60));
+        assert!(!rewards.contains_key("validator3"));
+        assert!(!rewards.contains_key("idle"));
+    }
+
+    #[test]
+    fn test_weighted_rewards() {
+        let engine = verified_engine(&[("validator1", 1), ("validator2", 1)], &["validator1", "validator2"]);
+        let reputation = HashMap::from([("validator1".to_string(), 90), ("validator2".to_string(), 10)]);
+        
+        let rewards = engine
+            .with_reward_policy(RewardPolicy { prover_share_bps: 5_000 })
+            .calculate_rewards_weighted("0xabc123", 2_000, &reputation)
+            .unwrap();
+        assert_eq!(rewards.get("prover1"), Some(&1_000));
+        assert_eq!(rewards.get("validator1"), Some(&900));
+        assert_eq!(rewards.get("validator2"), Some(&100));
+    }
+}
@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod tests {
+    use por_consensus::storage::{FileStore, MemoryStore, StateStore, StorageError, SCHEMA_VERSION};
+    use por_consensus::{ConsensusEngine, ConsensusError, ProofStatus};
+    use shared::types::ProofOfReasoning;
+    use std::sync::Arc;
+
+    const ETHER: u128 = 1_000_000_000_000_000_000;
+
+    fn proof(id: &str) -> ProofOfReasoning {
+        ProofOfReasoning {
+            input_hash: id.to_string(),
+            output_hash: "0xdef456".to_string(),
+            prover: "prover1".to_string(),
+            timestamp: 1234567890,
+            verified: false,
+            confirmations: 0,
+        }
+    }
+
+    fn engine(store: Arc<dyn StateStore>) -> ConsensusEngine {
+        let mut engine = ConsensusEngine::open(2, store).unwrap();
+        for validator in ["validator1", "validator2", "validator3"] {
+            engine.add_validator_with_stake(validator.to_string(), 1_000 * ETHER);
+        }
+        engine
+    }
+
+    #[test]
+    fn test_votes_survive_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state").join("consensus.json");
+        
+        let mut before = engine(Arc::new(FileStore::new(&path)));
+        before.submit_proof(proof("0xabc123")).unwrap();
+        before.submit_proof(proof("0xfed987")).unwrap();
+        before.validate_proof("0xabc123", "validator1").unwrap();
+        before.validate_proof("0xabc123", "validator2").unwrap();
+        drop(before);
+        
+        // Only the final file is left behind
+        assert!(path.exists());
+        assert_eq!(std::fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+        
+        let mut after = ConsensusEngine::open(2, Arc::new(FileStore::new(&path))).unwrap();
+        assert_eq!(after.stake_of("validator3"), Some(1_000 * ETHER));
+        assert_eq!(after.status("0xabc123"), Some(ProofStatus::Pending));
+        assert_eq!(after.status("0xfed987"), Some(ProofStatus::Pending));
+        assert_eq!(after.get_proof("0xabc123").unwrap().confirmations, 2);
+        
+        assert_eq!(
+            after.validate_proof("0xabc123", "validator1").unwrap_err(),
+            ConsensusError::AlreadyVoted {
+                proof_id: "0xabc123".to_string(),
+                validator: "validator1".to_string(),
+            }
+        );
+        assert!(after.validate_proof("0xabc123", "validator3").unwrap());
+    }
+
+    #[test]
+    fn test_reward_ledger_survives_restart() {
+        let store = Arc::new(MemoryStore::new());
+        
+        let mut before = engine(store.clone());
+        before.submit_proof(proof("0xabc123")).unwrap();
+        for validator in ["validator1", "validator2", "validator3"] {
+            before.validate_proof("0xabc123", validator).unwrap();
+        }
+        
+        // Nothing to pay for proofs that are not verified
+        before.submit_proof(proof("0xfed987")).unwrap();
+        assert!(before.distribute_rewards("0xfed987", 1_000).unwrap().is_empty());
+        assert!(!before.ledger().is_rewarded("0xfed987"));
+        
+        let payouts = before.distribute_rewards("0xabc123", 1_000).unwrap();
+        assert_eq!(payouts["prover1"], 700);
+        
+        let mut after = ConsensusEngine::open(2, store).unwrap();
+        assert_eq!(after.ledger().balance("prover1"), 700);
+        assert_eq!(after.ledger().balances().values().sum::<u128>(), 1_000);
+        assert_eq!(
+            after.distribute_rewards("0xabc123", 1_000).unwrap_err(),
+            ConsensusError::AlreadyRewarded("0xabc123".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rejects_unknown_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileStore::new(dir.path().join("consensus.json"));
+        assert!(store.load().unwrap().is_none());
+        
+        let mut state = ConsensusEngine::new(1).state();
+        assert_eq!(state.version, SCHEMA_VERSION);
+        
+        state.version = SCHEMA_VERSION + 1;
+        store.save(&state).unwrap();
+        assert!(matches!(
+            ConsensusEngine::open(1, Arc::new(FileStore::new(store.path()))),
+            Err(StorageError::UnsupportedVersion { .. })
+        ));
+        
+        std::fs::write(store.path(), b"{\"version\":1,\"proofs\":").unwrap();
+        assert!(matches!(store.load(), Err(StorageError::Encoding(_))));
+    }
+
+    #[test]
+    fn test_reads_version_1() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("consensus.json");
+        let mut before = engine(Arc::new(FileStore::new(&path)));
+        before.submit_proof(proof("0xabc123")).unwrap();
+        
+        // Version 1 snapshots are the same without disputes
+        let saved = std::fs::read_to_string(&path).unwrap();
+        let v1 = saved
+            .replace(&format!("\"version\":{SCHEMA_VERSION}"), "\"version\":1")
+            .replace(",\"disputes\":{}", "");
+        assert_ne!(v1, saved);
+        std::fs::write(&path, v1).unwrap();
+        
+        let after = ConsensusEngine::open(2, Arc::new(FileStore::new(&path))).unwrap();
+        assert_eq!(after.state().version, SCHEMA_VERSION);
+        assert_eq!(after.status("0xabc123"), Some(ProofStatus::Pending));
+        assert!(after.dispute("0xabc123").is_none());
+    }
+}
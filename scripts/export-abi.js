@@ -0,0 +1,25 @@
+const fs = require("fs");
+const path = require("path");
+
+// Copies the ABI of each compiled contract into bindings/abi so the Rust
+// bindings crate picks up contract changes. Run `npx hardhat compile` first.
+const CONTRACTS = ["CognitiveRegistry", "ProofOfReasoning"];
+
+const root = path.join(__dirname, "..");
+const outDir = path.join(root, "bindings", "abi");
+
+fs.mkdirSync(outDir, { recursive: true });
+
+for (const name of CONTRACTS) {
+    const artifact = path.join(root, "artifacts", "contracts", `${name}.sol`, `${name}.json`);
+
+    if (!fs.existsSync(artifact)) {
+        console.error(`❌ Missing artifact for ${name}, run \`npm run compile\` first`);
+        process.exit(1);
+    }
+
+    const { abi } = JSON.parse(fs.readFileSync(artifact, "utf8"));
+    fs.writeFileSync(path.join(outDir, `${name}.json`), JSON.stringify(abi, null, 2) + "\n");
+
+    console.log(`✅ Exported ${name} ABI (${abi.length} entries)`);
+}
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::time::Duration;
+use thiserror::Error;
+
+use crate::types::ReasoningTask;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum Rejection {
+    #[error("task type {0} is not accepted")]
+    TaskTypeNotAllowed(String),
+    #[error("reward {reward} is below the minimum of {min}")]
+    RewardTooLow { reward: u64, min: u64 },
+    #[error("reward {reward} does not cover {cost} in gas plus a profit of {min_profit}")]
+    Unprofitable { reward: u64, cost: u128, min_profit: u64 },
+    #[error("only {left}s left before the deadline, {needed}s needed")]
+    DeadlineTooClose { left: u64, needed: u64 },
+    #[error("payload is {size} bytes, at most {max} accepted")]
+    PayloadTooLarge { size: usize, max: usize },
+}
+
+impl Rejection {
+    // Whether the task may pass later: gas may get cheaper before its
+    // deadline, nothing else about a task changes
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::Unprofitable { .. })
+    }
+}
+
+// Operator rules for which tasks are worth claiming, the `[engine.admission]`
+// table of the config file. Amounts are in wei, like `ReasoningTask.reward`;
+// durations in seconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdmissionPolicy {
+    pub min_reward: u64,
+    // Reward left over after gas that makes a task worth the effort
+    pub min_profit: u64,
+    // Empty accepts every task type a module is registered for
+    pub task_types: BTreeSet<String>,
+    pub max_payload_bytes: usize,
+    // Gas used by `assignTask` and `submitTaskResult`
+    pub claim_gas: u64,
+    pub submit_gas: u64,
+    // Expected run time per task type, and for types not listed
+    pub compute_secs: HashMap<String, u64>,
+    pub default_compute_secs: u64,
+    // Time for the two transactions to be mined and confirmed
+    pub tx_margin_secs: u64,
+}
+
+impl Default for AdmissionPolicy {
+    fn default() -> Self {
+        Self {
+            min_reward: 0,
+            min_profit: 0,
+            task_types: BTreeSet::new(),
+            max_payload_bytes: 1024 * 1024,
+            claim_gas: 60_000,
+            submit_gas: 90_000,
+            compute_secs: HashMap::new(),
+            default_compute_secs: 5,
+            tx_margin_secs: 30,
+        }
+    }
+}
+
+impl AdmissionPolicy {
+    // Gas spent on a task we claim and complete, at `gas_price` wei per gas
+    pub fn gas_cost(&self, gas_price: u128) -> u128 {
+        ((self.claim_gas + self.submit_gas) as u128).saturating_mul(gas_price)
+    }
+    
+    pub fn expected_compute(&self, task_type: &str) -> Duration {
+        let secs = self.compute_secs.get(task_type).copied().unwrap_or(self.default_compute_secs);
+        Duration::from_secs(secs)
+    }
+    
+    // Everything that can be decided before the payload is fetched. `now`
+    // is unix seconds, compared against `deadline`. The deadline is checked
+    // before the gas cost, so an unprofitable task still has time to wait
+    // for cheaper gas.
+    pub fn admit(&self, task: &ReasoningTask, gas_price: u128, now: u64) -> Result<(), Rejection> {
+        if !self.task_types.is_empty() && !self.task_types.contains(&task.task_type) {
+            return Err(Rejection::TaskTypeNotAllowed(task.task_type.clone()));
+        }
+        
+        if task.reward < self.min_reward {
+            return Err(Rejection::RewardTooLow {
+                reward: task.reward,
+                min: self.min_reward,
+            });
+        }
+        
+        let needed = self.expected_compute(&task.task_type).as_secs() + self.tx_margin_secs;
+        let left = task.deadline.saturating_sub(now);
+        if left <= needed {
+            return Err(Rejection::DeadlineTooClose { left, needed });
+        }
+        
+        let cost = self.gas_cost(gas_price);
+        if (task.reward as u128) < cost.saturating_add(self.min_profit as u128) {
+            return Err(Rejection::Unprofitable {
+                reward: task.reward,
+                cost,
+                min_profit: self.min_profit,
+            });
+        }
+        
+        Ok(())
+    }
+    
+    // Size of the resolved payload, serialized
+    pub fn admit_payload(&self, task: &ReasoningTask) -> Result<(), Rejection> {
+        let size = serde_json::to_vec(&task.data).map(|bytes| bytes.len()).unwrap_or(usize::MAX);
+        
+        if size > self.max_payload_bytes {
+            return Err(Rejection::PayloadTooLarge {
+                size,
+                max: self.max_payload_bytes,
+            });
+        }
+        
+        Ok(())
+    }
+}
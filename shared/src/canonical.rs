@@ -0,0 +1,184 @@
+use serde::Serialize;
+use serde_json::{Map, Number, Value};
+use thiserror::Error;
+
+// Integers above 2^53 cannot be represented exactly as IEEE 754 doubles,
+// which is the only number type RFC 8785 allows.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+// Reasoning outputs are rounded to this many decimal places before hashing
+// so that last-bit differences in floating point math between machines do
+// not change the output hash.
+pub const RESULT_DECIMALS: i32 = 9;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CanonicalError {
+    #[error("integer {0} is outside the range representable as an IEEE 754 double")]
+    UnsafeInteger(String),
+    #[error("number {0} is not finite")]
+    NonFinite(String),
+    #[error("value could not be converted to JSON: {0}")]
+    Serialize(String),
+}
+
+// RFC 8785 (JSON Canonicalization Scheme) serialization: object members are
+// sorted by their UTF-16 code units, no insignificant whitespace, and numbers
+// are written the way ECMAScript's Number.prototype.toString writes them.
+pub fn to_canonical_bytes(value: &Value) -> Result<Vec<u8>, CanonicalError> {
+    let mut out = String::new();
+    write_value(value, &mut out)?;
+    Ok(out.into_bytes())
+}
+
+pub fn to_canonical_string(value: &Value) -> Result<String, CanonicalError> {
+    let mut out = String::new();
+    write_value(value, &mut out)?;
+    Ok(out)
+}
+
+pub fn to_canonical_value<T: Serialize>(value: &T) -> Result<Value, CanonicalError> {
+    serde_json::to_value(value).map_err(|e| CanonicalError::Serialize(e.to_string()))
+}
+
+// Rounds every floating point number to `decimals` places and folds -0 into 0.
+// Integers are left untouched.
+pub fn normalize_floats(value: &Value, decimals: i32) -> Value {
+    match value {
+        Value::Number(n) if n.is_f64() => {
+            let scale = 10f64.powi(decimals);
+            let rounded = (n.as_f64().unwrap_or(0.0) * scale).round() / scale;
+            let rounded = if rounded == 0.0 { 0.0 } else { rounded };
+            Number::from_f64(rounded).map(Value::Number).unwrap_or(Value::Null)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(|v| normalize_floats(v, decimals)).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), normalize_floats(v, decimals)))
+                .collect::<Map<String, Value>>(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn write_value(value: &Value, out: &mut String) -> Result<(), CanonicalError> {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => write_number(n, out)?,
+        Value::String(s) => write_string(s, out),
+        Value::Array(items) => {
+            out.push('[');
+            for (idx, item) in items.iter().enumerate() {
+                if idx > 0 {
+                    out.push(',');
+                }
+                write_value(item, out)?;
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            
+            out.push('{');
+            for (idx, (key, item)) in entries.into_iter().enumerate() {
+                if idx > 0 {
+                    out.push(',');
+                }
+                write_string(key, out);
+                out.push(':');
+                write_value(item, out)?;
+            }
+            out.push('}');
+        }
+    }
+    
+    Ok(())
+}
+
+fn write_number(n: &Number, out: &mut String) -> Result<(), CanonicalError> {
+    if let Some(u) = n.as_u64() {
+        if u > MAX_SAFE_INTEGER {
+            return Err(CanonicalError::UnsafeInteger(n.to_string()));
+        }
+        out.push_str(&u.to_string());
+    } else if let Some(i) = n.as_i64() {
+        if i.unsigned_abs() > MAX_SAFE_INTEGER {
+            return Err(CanonicalError::UnsafeInteger(n.to_string()));
+        }
+        out.push_str(&i.to_string());
+    } else {
+        let f = n.as_f64().ok_or_else(|| CanonicalError::NonFinite(n.to_string()))?;
+        out.push_str(&format_es_number(f)?);
+    }
+    
+    Ok(())
+}
+
+// ECMAScript Number::toString(x) for finite doubles (ECMA-262, 6.1.6.1.20)
+pub fn format_es_number(x: f64) -> Result<String, CanonicalError> {
+    if !x.is_finite() {
+        return Err(CanonicalError::NonFinite(x.to_string()));
+    }
+    
+    if x == 0.0 {
+        return Ok("0".to_string());
+    }
+    
+    // Rust's `{:e}` yields the shortest digit string that round-trips,
+    // which is what the ECMAScript algorithm calls for.
+    let sci = format!("{:e}", x.abs());
+    let (mantissa, exponent) = sci.split_once('e').expect("`{:e}` always contains an exponent");
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let exponent: i32 = exponent.parse().expect("`{:e}` exponent is an integer");
+    
+    let k = digits.len() as i32;
+    let n = exponent + 1;
+    
+    let mut out = String::new();
+    if x < 0.0 {
+        out.push('-');
+    }
+    
+    if k <= n && n <= 21 {
+        out.push_str(&digits);
+        out.extend(std::iter::repeat_n('0', (n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        out.push_str(&digits[..n as usize]);
+        out.push('.');
+        out.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        out.push_str("0.");
+        out.extend(std::iter::repeat_n('0', (-n) as usize));
+        out.push_str(&digits);
+    } else {
+        out.push_str(&digits[..1]);
+        if k > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        out.push('e');
+        out.push(if n > 0 { '+' } else { '-' });
+        out.push_str(&(n - 1).abs().to_string());
+    }
+    
+    Ok(out)
+}
+
+fn write_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{09}' => out.push_str("\\t"),
+            '\u{0A}' => out.push_str("\\n"),
+            '\u{0C}' => out.push_str("\\f"),
+            '\u{0D}' => out.push_str("\\r"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
@@ -0,0 +1,597 @@
+use clap::Parser;
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::Address;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::admission::AdmissionPolicy;
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("cannot read config file {path}: {source}")]
+    Read { path: PathBuf, source: std::io::Error },
+    #[error("invalid config file {path}: {source}")]
+    Parse { path: PathBuf, source: toml::de::Error },
+    #[error("{key} is invalid: {reason}")]
+    Invalid { key: String, reason: String },
+    #[error("{0} must be set")]
+    Missing(&'static str),
+    #[error("no signing key configured: set PRIVATE_KEY, a keystore or a remote signer")]
+    NoKey,
+    #[error("more than one signing key configured: use only one of PRIVATE_KEY, a keystore and a remote signer")]
+    AmbiguousKey,
+    #[error("a remote signer has no local wallet")]
+    RemoteKey,
+    #[error("cannot unlock keystore {path}: {reason}")]
+    Keystore { path: PathBuf, reason: String },
+    #[error("configured for chain {configured} but the RPC endpoint is on chain {actual}")]
+    ChainIdMismatch { configured: u64, actual: u64 },
+}
+
+impl ConfigError {
+    fn invalid(key: &str, reason: impl ToString) -> Self {
+        Self::Invalid {
+            key: key.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+// What a configuration is validated for: one of the daemons, or staking
+// operations signed with the cold key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Engine,
+    Validator,
+    Staking,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NodeConfig {
+    pub chain: ChainConfig,
+    // Hot key the daemons sign with
+    pub signer: SignerConfig,
+    // Cold key that owns the stake; only used to register the node and set
+    // its operator, never loaded by the daemons
+    pub staking_signer: SignerConfig,
+    pub data: DataConfig,
+    pub engine: EngineConfig,
+    pub validator: ValidatorConfig,
+    pub modules: ModulesConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChainConfig {
+    pub rpc_url: String,
+    // Without one the engine polls over HTTP instead of following events
+    pub ws_url: Option<String>,
+    // Checked against the RPC endpoint at startup when set
+    pub chain_id: Option<u64>,
+    pub confirmations: usize,
+    pub registry_address: Option<Address>,
+    pub por_address: Option<Address>,
+    pub start_block: u64,
+    // Defaults to `start_block`
+    pub por_start_block: Option<u64>,
+}
+
+impl Default for ChainConfig {
+    fn default() -> Self {
+        Self {
+            rpc_url: "http://localhost:8545".to_string(),
+            ws_url: None,
+            chain_id: None,
+            confirmations: 1,
+            registry_address: None,
+            por_address: None,
+            start_block: 0,
+            por_start_block: None,
+        }
+    }
+}
+
+impl ChainConfig {
+    // The chain to sign for, given the one reported by the RPC endpoint
+    pub fn check_chain_id(&self, actual: u64) -> Result<u64, ConfigError> {
+        match self.chain_id {
+            Some(configured) if configured != actual => Err(ConfigError::ChainIdMismatch { configured, actual }),
+            _ => Ok(actual),
+        }
+    }
+}
+
+// Secrets never come from the config file: the raw key only from the
+// PRIVATE_KEY environment variable, the keystore password from
+// KEYSTORE_PASSWORD, a password file or a prompt.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SignerConfig {
+    pub keystore: Option<PathBuf>,
+    pub password_file: Option<PathBuf>,
+    // Ask for the keystore password on the terminal
+    pub password_prompt: bool,
+    // JSON-RPC signer holding the key (Web3Signer, Clef)
+    pub remote_url: Option<String>,
+    // Account to use; required if the remote signer holds several
+    pub address: Option<Address>,
+    #[serde(skip)]
+    pub private_key: Option<String>,
+    #[serde(skip)]
+    pub password: Option<String>,
+}
+
+impl fmt::Debug for SignerConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignerConfig")
+            .field("keystore", &self.keystore)
+            .field("password_file", &self.password_file)
+            .field("password_prompt", &self.password_prompt)
+            .field("remote_url", &self.remote_url)
+            .field("address", &self.address)
+            .field("private_key", &self.private_key.as_ref().map(|_| "<redacted>"))
+            .field("password", &self.password.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeySource<'a> {
+    PrivateKey,
+    Keystore(&'a Path),
+    Remote { url: &'a str, address: Option<Address> },
+}
+
+impl SignerConfig {
+    pub fn is_configured(&self) -> bool {
+        self.private_key.is_some() || self.keystore.is_some() || self.remote_url.is_some()
+    }
+    
+    // The single place the key comes from
+    pub fn source(&self) -> Result<KeySource<'_>, ConfigError> {
+        match (&self.private_key, &self.keystore, &self.remote_url) {
+            (Some(_), None, None) => Ok(KeySource::PrivateKey),
+            (None, Some(path), None) => Ok(KeySource::Keystore(path)),
+            (None, None, Some(url)) => Ok(KeySource::Remote {
+                url,
+                address: self.address,
+            }),
+            (None, None, None) => Err(ConfigError::NoKey),
+            _ => Err(ConfigError::AmbiguousKey),
+        }
+    }
+    
+    fn validate(&self, section: &str) -> Result<(), ConfigError> {
+        match self.source()? {
+            KeySource::Keystore(_) if self.password.is_none() && self.password_file.is_none() && !self.password_prompt => {
+                Err(ConfigError::Missing("KEYSTORE_PASSWORD, password_file or password_prompt"))
+            }
+            KeySource::Remote { url, .. } if !(url.starts_with("http://") || url.starts_with("https://")) => {
+                Err(ConfigError::invalid(&format!("{}.remote_url", section), "expected an http:// or https:// URL"))
+            }
+            _ => Ok(()),
+        }
+    }
+    
+    // Loads a raw or keystore key; remote keys are reached through
+    // `cognitive_engine::signer::NodeSigner` instead
+    pub fn wallet(&self, chain_id: u64) -> Result<LocalWallet, ConfigError> {
+        let wallet = match self.source()? {
+            // The parse error is not passed on, it may quote the key
+            KeySource::PrivateKey => self
+                .private_key
+                .as_deref()
+                .unwrap_or_default()
+                .trim()
+                .parse::<LocalWallet>()
+                .map_err(|_| ConfigError::invalid("PRIVATE_KEY", "not a hex-encoded secp256k1 private key"))?,
+            KeySource::Keystore(path) => {
+                let password = self.keystore_password(path)?;
+                LocalWallet::decrypt_keystore(path, password).map_err(|e| ConfigError::Keystore {
+                    path: path.to_path_buf(),
+                    reason: e.to_string(),
+                })?
+            }
+            KeySource::Remote { .. } => return Err(ConfigError::RemoteKey),
+        };
+        
+        if let Some(address) = self.address {
+            if wallet.address() != address {
+                return Err(ConfigError::invalid("address", format!("the key is for {:?}, not {:?}", wallet.address(), address)));
+            }
+        }
+        
+        Ok(wallet.with_chain_id(chain_id))
+    }
+    
+    fn keystore_password(&self, keystore: &Path) -> Result<String, ConfigError> {
+        if let Some(password) = &self.password {
+            return Ok(password.clone());
+        }
+        
+        if let Some(path) = &self.password_file {
+            let password = std::fs::read_to_string(path).map_err(|source| ConfigError::Read {
+                path: path.clone(),
+                source,
+            })?;
+            return Ok(password.trim_end_matches(['\r', '\n']).to_string());
+        }
+        
+        if self.password_prompt {
+            return rpassword::prompt_password(format!("Password for {}: ", keystore.display())).map_err(|e| {
+                ConfigError::Keystore {
+                    path: keystore.to_path_buf(),
+                    reason: format!("cannot read password: {}", e),
+                }
+            });
+        }
+        
+        Err(ConfigError::Missing("KEYSTORE_PASSWORD, password_file or password_prompt"))
+    }
+}
+
+// Where task payloads are resolved from, in order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DataConfig {
+    pub dir: PathBuf,
+    pub gateway: Option<String>,
+    pub ipfs_api_url: Option<String>,
+}
+
+impl Default for DataConfig {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from(".polyneurons/task-data"),
+            gateway: None,
+            ipfs_api_url: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EngineConfig {
+    pub poll_interval_secs: u64,
+    pub concurrency: usize,
+    pub max_task_timeout_secs: u64,
+    pub cursor_path: PathBuf,
+    pub jobs_path: PathBuf,
+    // Which tasks are worth claiming, the `[engine.admission]` table
+    pub admission: AdmissionPolicy,
+    // Serves /metrics, /healthz and /readyz when set
+    pub metrics_addr: Option<SocketAddr>,
+    // Blocks the task scan may trail the chain head by and still be ready
+    pub max_lag_blocks: u64,
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_secs: 10,
+            concurrency: 4,
+            max_task_timeout_secs: 300,
+            cursor_path: PathBuf::from(".polyneurons/engine-cursor.json"),
+            jobs_path: PathBuf::from(".polyneurons/engine-jobs.json"),
+            admission: AdmissionPolicy::default(),
+            metrics_addr: None,
+            max_lag_blocks: 50,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ValidatorConfig {
+    pub block_interval_secs: u64,
+    // Assigned tasks and peer proofs are checked on this interval
+    pub task_interval_secs: u64,
+    pub tolerance_file: Option<PathBuf>,
+    // Upper bound on re-executing a task, for own and peer proofs alike
+    pub max_task_timeout_secs: u64,
+    // Proofs already submitted, so a restart does not submit them again
+    pub proofs_path: PathBuf,
+    pub metrics_addr: Option<SocketAddr>,
+    pub max_lag_blocks: u64,
+}
+
+impl Default for ValidatorConfig {
+    fn default() -> Self {
+        Self {
+            block_interval_secs: 2,
+            task_interval_secs: 15,
+            tolerance_file: None,
+            max_task_timeout_secs: 300,
+            proofs_path: PathBuf::from(".polyneurons/validator-proofs.json"),
+            metrics_addr: None,
+            max_lag_blocks: 50,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ModulesConfig {
+    // Task types the engine takes on; empty enables every built-in module
+    pub enabled: BTreeSet<String>,
+}
+
+// Settings are layered: built-in defaults, then the TOML file, then
+// environment variables, then command-line flags.
+impl NodeConfig {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        
+        toml::from_str(&contents).map_err(|source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+    
+    pub fn apply_env(&mut self) -> Result<(), ConfigError> {
+        self.apply_vars(|key| std::env::var(key).ok())
+    }
+    
+    // Like `apply_env`, with variables looked up through `var`. Empty
+    // values count as unset, as in `.env.example`.
+    pub fn apply_vars(&mut self, var: impl Fn(&str) -> Option<String>) -> Result<(), ConfigError> {
+        let var = |key: &str| var(key).filter(|value| !value.trim().is_empty());
+        
+        set(&mut self.chain.rpc_url, "POLYGON_RPC_URL", &var)?;
+        set_some(&mut self.chain.ws_url, "POLYGON_WS_URL", &var)?;
+        set_some(&mut self.chain.chain_id, "CHAIN_ID", &var)?;
+        set(&mut self.chain.confirmations, "TX_CONFIRMATIONS", &var)?;
+        set_some(&mut self.chain.registry_address, "REGISTRY_ADDRESS", &var)?;
+        set_some(&mut self.chain.por_address, "POR_CONTRACT_ADDRESS", &var)?;
+        set(&mut self.chain.start_block, "REGISTRY_START_BLOCK", &var)?;
+        set_some(&mut self.chain.por_start_block, "POR_START_BLOCK", &var)?;
+        
+        set_some(&mut self.signer.keystore, "KEYSTORE_PATH", &var)?;
+        set_some(&mut self.signer.password_file, "KEYSTORE_PASSWORD_FILE", &var)?;
+        if let Some(private_key) = var("PRIVATE_KEY") {
+            self.signer.private_key = Some(private_key);
+        }
+        if let Some(password) = var("KEYSTORE_PASSWORD") {
+            self.signer.password = Some(password);
+        }
+        set_some(&mut self.signer.remote_url, "REMOTE_SIGNER_URL", &var)?;
+        set_some(&mut self.signer.address, "SIGNER_ADDRESS", &var)?;
+        
+        // No raw key for the cold key
+        set_some(&mut self.staking_signer.keystore, "STAKING_KEYSTORE_PATH", &var)?;
+        set_some(&mut self.staking_signer.password_file, "STAKING_KEYSTORE_PASSWORD_FILE", &var)?;
+        if let Some(password) = var("STAKING_KEYSTORE_PASSWORD") {
+            self.staking_signer.password = Some(password);
+        }
+        set_some(&mut self.staking_signer.remote_url, "STAKING_REMOTE_SIGNER_URL", &var)?;
+        set_some(&mut self.staking_signer.address, "STAKING_SIGNER_ADDRESS", &var)?;
+        
+        set(&mut self.data.dir, "TASK_DATA_DIR", &var)?;
+        set_some(&mut self.data.gateway, "TASK_DATA_GATEWAY", &var)?;
+        set_some(&mut self.data.ipfs_api_url, "IPFS_API_URL", &var)?;
+        
+        set(&mut self.engine.poll_interval_secs, "ENGINE_POLL_INTERVAL_SECS", &var)?;
+        set(&mut self.engine.concurrency, "ENGINE_CONCURRENCY", &var)?;
+        set(&mut self.engine.max_task_timeout_secs, "MAX_TASK_TIMEOUT_SECS", &var)?;
+        set(&mut self.engine.cursor_path, "ENGINE_CURSOR_PATH", &var)?;
+        set(&mut self.engine.jobs_path, "ENGINE_JOBS_PATH", &var)?;
+        set_some(&mut self.engine.metrics_addr, "ENGINE_METRICS_ADDR", &var)?;
+        set(&mut self.engine.max_lag_blocks, "ENGINE_MAX_LAG_BLOCKS", &var)?;
+        
+        set(&mut self.validator.block_interval_secs, "VALIDATOR_BLOCK_INTERVAL_SECS", &var)?;
+        set(&mut self.validator.task_interval_secs, "VALIDATOR_TASK_INTERVAL_SECS", &var)?;
+        set_some(&mut self.validator.tolerance_file, "POR_TOLERANCE_FILE", &var)?;
+        set(&mut self.validator.max_task_timeout_secs, "VALIDATOR_MAX_TASK_TIMEOUT_SECS", &var)?;
+        set(&mut self.validator.proofs_path, "VALIDATOR_PROOFS_PATH", &var)?;
+        set_some(&mut self.validator.metrics_addr, "VALIDATOR_METRICS_ADDR", &var)?;
+        set(&mut self.validator.max_lag_blocks, "VALIDATOR_MAX_LAG_BLOCKS", &var)?;
+        
+        if let Some(modules) = var("ENGINE_MODULES") {
+            self.modules.enabled = split_list(&modules);
+        }
+        
+        Ok(())
+    }
+    
+    // Fails on anything the daemon for `role` could not start with. The key
+    // itself is only checked when the wallet is loaded.
+    pub fn validate(&self, role: Role) -> Result<(), ConfigError> {
+        let chain = &self.chain;
+        if !(chain.rpc_url.starts_with("http://") || chain.rpc_url.starts_with("https://")) {
+            return Err(ConfigError::invalid("chain.rpc_url", "expected an http:// or https:// URL"));
+        }
+        
+        if let Some(ws_url) = &chain.ws_url {
+            if !(ws_url.starts_with("ws://") || ws_url.starts_with("wss://")) {
+                return Err(ConfigError::invalid("chain.ws_url", "expected a ws:// or wss:// URL"));
+            }
+        }
+        
+        required_address(chain.registry_address, "chain.registry_address")?;
+        
+        match role {
+            Role::Staking => self.staking_signer.validate("staking_signer")?,
+            Role::Engine | Role::Validator => self.signer.validate("signer")?,
+        }
+        
+        match role {
+            Role::Engine => {
+                positive(self.engine.poll_interval_secs, "engine.poll_interval_secs")?;
+                positive(self.engine.concurrency as u64, "engine.concurrency")?;
+                positive(self.engine.max_task_timeout_secs, "engine.max_task_timeout_secs")?;
+            }
+            Role::Validator => {
+                required_address(chain.por_address, "chain.por_address")?;
+                positive(self.validator.block_interval_secs, "validator.block_interval_secs")?;
+                positive(self.validator.task_interval_secs, "validator.task_interval_secs")?;
+                positive(self.validator.max_task_timeout_secs, "validator.max_task_timeout_secs")?;
+            }
+            Role::Staking => {}
+        }
+        
+        Ok(())
+    }
+}
+
+// Command-line flags shared by both daemons. Secrets are deliberately not
+// accepted here, they would show up in the process list.
+#[derive(Debug, Clone, Default, Parser)]
+#[command(version)]
+pub struct Cli {
+    #[arg(short, long, value_name = "FILE", help = "TOML config file [env: POLYNEURONS_CONFIG]")]
+    pub config: Option<PathBuf>,
+    #[arg(long, value_name = "URL", help = "HTTP JSON-RPC endpoint")]
+    pub rpc_url: Option<String>,
+    #[arg(long, value_name = "URL", help = "WebSocket endpoint for following events")]
+    pub ws_url: Option<String>,
+    #[arg(long, help = "Expected chain ID, checked against the RPC endpoint")]
+    pub chain_id: Option<u64>,
+    #[arg(long, value_name = "ADDRESS", help = "CognitiveRegistry contract")]
+    pub registry_address: Option<Address>,
+    #[arg(long, value_name = "ADDRESS", help = "ProofOfReasoning contract")]
+    pub por_address: Option<Address>,
+    #[arg(long, value_name = "BLOCK", help = "First block to scan for registry events")]
+    pub start_block: Option<u64>,
+    #[arg(long, value_name = "FILE", help = "Encrypted JSON keystore to sign with")]
+    pub keystore: Option<PathBuf>,
+    #[arg(long, value_name = "FILE", help = "File holding the keystore password")]
+    pub password_file: Option<PathBuf>,
+    #[arg(long, help = "Ask for the keystore password on the terminal")]
+    pub password_prompt: bool,
+    #[arg(long, value_name = "URL", help = "JSON-RPC signer holding the key")]
+    pub remote_signer: Option<String>,
+    #[arg(long, value_name = "ADDRESS", help = "Account to sign with")]
+    pub signer_address: Option<Address>,
+    #[arg(long, value_name = "SECS", help = "Seconds between polls for pending tasks")]
+    pub poll_interval_secs: Option<u64>,
+    #[arg(long, value_name = "TASKS", help = "Tasks handled at the same time")]
+    pub concurrency: Option<usize>,
+    #[arg(long, value_name = "TYPES", help = "Comma-separated task types to take on")]
+    pub modules: Option<String>,
+    #[arg(long, value_name = "ADDR", help = "Serve /metrics, /healthz and /readyz on this address")]
+    pub metrics_addr: Option<SocketAddr>,
+}
+
+impl Cli {
+    // The complete configuration for `role`, validated
+    pub fn load(&self, role: Role) -> Result<NodeConfig, ConfigError> {
+        let path = self
+            .config
+            .clone()
+            .or_else(|| std::env::var_os("POLYNEURONS_CONFIG").filter(|path| !path.is_empty()).map(PathBuf::from));
+        
+        let mut config = match path {
+            Some(path) => NodeConfig::from_file(path)?,
+            None => NodeConfig::default(),
+        };
+        config.apply_env()?;
+        self.apply(&mut config);
+        config.validate(role)?;
+        Ok(config)
+    }
+    
+    pub fn apply(&self, config: &mut NodeConfig) {
+        if let Some(rpc_url) = &self.rpc_url {
+            config.chain.rpc_url = rpc_url.clone();
+        }
+        if let Some(ws_url) = &self.ws_url {
+            config.chain.ws_url = Some(ws_url.clone());
+        }
+        if let Some(chain_id) = self.chain_id {
+            config.chain.chain_id = Some(chain_id);
+        }
+        if let Some(address) = self.registry_address {
+            config.chain.registry_address = Some(address);
+        }
+        if let Some(address) = self.por_address {
+            config.chain.por_address = Some(address);
+        }
+        if let Some(start_block) = self.start_block {
+            config.chain.start_block = start_block;
+        }
+        if let Some(keystore) = &self.keystore {
+            config.signer.keystore = Some(keystore.clone());
+        }
+        if let Some(password_file) = &self.password_file {
+            config.signer.password_file = Some(password_file.clone());
+        }
+        if self.password_prompt {
+            config.signer.password_prompt = true;
+        }
+        if let Some(url) = &self.remote_signer {
+            config.signer.remote_url = Some(url.clone());
+        }
+        if let Some(address) = self.signer_address {
+            config.signer.address = Some(address);
+        }
+        if let Some(secs) = self.poll_interval_secs {
+            config.engine.poll_interval_secs = secs;
+        }
+        if let Some(concurrency) = self.concurrency {
+            config.engine.concurrency = concurrency;
+        }
+        if let Some(modules) = &self.modules {
+            config.modules.enabled = split_list(modules);
+        }
+        // Each daemon reads its own section
+        if let Some(addr) = self.metrics_addr {
+            config.engine.metrics_addr = Some(addr);
+            config.validator.metrics_addr = Some(addr);
+        }
+    }
+}
+
+fn set<T>(target: &mut T, key: &str, var: &impl Fn(&str) -> Option<String>) -> Result<(), ConfigError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    if let Some(value) = var(key) {
+        *target = value.trim().parse().map_err(|e| ConfigError::invalid(key, e))?;
+    }
+    Ok(())
+}
+
+fn set_some<T>(target: &mut Option<T>, key: &str, var: &impl Fn(&str) -> Option<String>) -> Result<(), ConfigError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    if let Some(value) = var(key) {
+        *target = Some(value.trim().parse().map_err(|e| ConfigError::invalid(key, e))?);
+    }
+    Ok(())
+}
+
+fn split_list(list: &str) -> BTreeSet<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn required_address(address: Option<Address>, key: &'static str) -> Result<(), ConfigError> {
+    match address {
+        None => Err(ConfigError::Missing(key)),
+        Some(address) if address.is_zero() => Err(ConfigError::invalid(key, "the zero address is not a contract")),
+        Some(_) => Ok(()),
+    }
+}
+
+fn positive(value: u64, key: &str) -> Result<(), ConfigError> {
+    if value == 0 {
+        return Err(ConfigError::invalid(key, "must be at least 1"));
+    }
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::canonical::{normalize_floats, to_canonical_bytes, to_canonical_value, CanonicalError, RESULT_DECIMALS};
+use crate::types::{ReasoningResult, ReasoningTask};
+
+pub type Hash32 = [u8; 32];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Sha256,
+    Keccak256,
+}
+
+impl HashAlgorithm {
+    pub fn digest(self, bytes: &[u8]) -> Hash32 {
+        match self {
+            Self::Sha256 => sha256(bytes),
+            Self::Keccak256 => keccak256(bytes),
+        }
+    }
+}
+
+pub fn sha256(bytes: &[u8]) -> Hash32 {
+    Sha256::digest(bytes).into()
+}
+
+pub fn keccak256(bytes: &[u8]) -> Hash32 {
+    ethers::utils::keccak256(bytes)
+}
+
+pub fn hash_canonical(value: &Value, algorithm: HashAlgorithm) -> Result<Hash32, CanonicalError> {
+    Ok(algorithm.digest(&to_canonical_bytes(value)?))
+}
+
+pub fn hash_serializable<T: Serialize>(value: &T, algorithm: HashAlgorithm) -> Result<Hash32, CanonicalError> {
+    hash_canonical(&to_canonical_value(value)?, algorithm)
+}
+
+// `dataHash` stored in CognitiveRegistry for a task payload
+pub fn task_data_hash(data: &Value) -> Result<Hash32, CanonicalError> {
+    hash_canonical(data, HashAlgorithm::Sha256)
+}
+
+// The part of a reasoning result that is committed to. The measured
+// computation time differs between nodes and is left out, and floats are
+// rounded to RESULT_DECIMALS places.
+pub fn committed_result(result: &ReasoningResult) -> Value {
+    let committed = serde_json::json!({
+        "prediction": result.prediction,
+        "confidence_score": result.confidence_score,
+    });
+    
+    normalize_floats(&committed, RESULT_DECIMALS)
+}
+
+// Hash committed for a reasoning result (`resultHash` / PoR `outputHash`).
+// Equal to `task_data_hash(&committed_result(result))`, so a published
+// result can be served by any TaskDataStore under its output hash.
+pub fn result_hash(result: &ReasoningResult) -> Result<Hash32, CanonicalError> {
+    hash_canonical(&committed_result(result), HashAlgorithm::Sha256)
+}
+
+// PoR `inputHash`: binds a proof to one registry task and its payload, so the
+// same data submitted under another task id yields a different proof.
+pub fn proof_input_hash(task: &ReasoningTask) -> Result<Hash32, CanonicalError> {
+    let input = serde_json::json!({
+        "task_id": task.task_id,
+        "task_type": task.task_type,
+        "data_hash": task.data_hash.to_lowercase(),
+    });
+    
+    hash_canonical(&input, HashAlgorithm::Sha256)
+}
+
+pub fn to_hex(hash: &Hash32) -> String {
+    format!("0x{}", hex::encode(hash))
+}
+
+pub fn parse_hash(s: &str) -> Option<Hash32> {
+    let bytes = hex::decode(s.strip_prefix("0x").unwrap_or(s)).ok()?;
+    bytes.try_into().ok()
+}
+
+pub fn hashes_equal(a: &str, b: &str) -> bool {
+    match (parse_hash(a), parse_hash(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.eq_ignore_ascii_case(b),
+    }
+}
@@ -1 +1,8 @@
+pub mod admission;
+pub mod canonical;
+pub mod config;
+pub mod hashing;
+pub mod metrics;
+pub mod telemetry;
 pub mod types;
+pub mod validation;
@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+
+// Seconds, from a quick statistical module up to the default task timeout
+pub const LATENCY_BUCKETS: &[f64] = &[0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0, 300.0];
+
+// A metric family that renders itself in the Prometheus text format
+pub trait Metric: Send + Sync {
+    fn encode(&self, out: &mut String);
+}
+
+struct Desc {
+    name: String,
+    help: String,
+    labels: Vec<&'static str>,
+}
+
+impl Desc {
+    fn new(name: &str, help: &str, labels: &[&'static str]) -> Self {
+        Self {
+            name: name.to_string(),
+            help: help.to_string(),
+            labels: labels.to_vec(),
+        }
+    }
+    
+    fn key(&self, values: &[&str]) -> Vec<String> {
+        assert_eq!(values.len(), self.labels.len(), "label values for {}", self.name);
+        values.iter().map(|value| value.to_string()).collect()
+    }
+    
+    fn header(&self, out: &mut String, kind: &str) {
+        let _ = writeln!(out, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(out, "# TYPE {} {}", self.name, kind);
+    }
+    
+    // `{a="x",b="y"}` plus any extra pair, or nothing without labels
+    fn labels(&self, values: &[String], extra: Option<(&str, &str)>) -> String {
+        let pairs: Vec<String> = self
+            .labels
+            .iter()
+            .zip(values)
+            .map(|(name, value)| format!("{}=\"{}\"", name, escape(value)))
+            .chain(extra.map(|(name, value)| format!("{}=\"{}\"", name, value)))
+            .collect();
+        
+        if pairs.is_empty() {
+            String::new()
+        } else {
+            format!("{{{}}}", pairs.join(","))
+        }
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+// Monotonically increasing count per label set
+pub struct Counter {
+    desc: Desc,
+    values: Mutex<BTreeMap<Vec<String>, f64>>,
+}
+
+impl Counter {
+    pub fn new(name: &str, help: &str, labels: &[&'static str]) -> Self {
+        Self {
+            desc: Desc::new(name, help, labels),
+            values: Mutex::new(BTreeMap::new()),
+        }
+    }
+    
+    pub fn inc(&self, labels: &[&str]) {
+        self.inc_by(labels, 1.0);
+    }
+    
+    pub fn inc_by(&self, labels: &[&str], amount: f64) {
+        *self.values.lock().unwrap().entry(self.desc.key(labels)).or_default() += amount;
+    }
+    
+    pub fn get(&self, labels: &[&str]) -> f64 {
+        self.values.lock().unwrap().get(&self.desc.key(labels)).copied().unwrap_or_default()
+    }
+}
+
+impl Metric for Counter {
+    fn encode(&self, out: &mut String) {
+        self.desc.header(out, "counter");
+        for (labels, value) in self.values.lock().unwrap().iter() {
+            let _ = writeln!(out, "{}{} {}", self.desc.name, self.desc.labels(labels, None), value);
+        }
+    }
+}
+
+// Current value per label set
+pub struct Gauge {
+    desc: Desc,
+    values: Mutex<BTreeMap<Vec<String>, f64>>,
+}
+
+impl Gauge {
+    pub fn new(name: &str, help: &str, labels: &[&'static str]) -> Self {
+        Self {
+            desc: Desc::new(name, help, labels),
+            values: Mutex::new(BTreeMap::new()),
+        }
+    }
+    
+    pub fn set(&self, labels: &[&str], value: f64) {
+        self.values.lock().unwrap().insert(self.desc.key(labels), value);
+    }
+    
+    pub fn get(&self, labels: &[&str]) -> Option<f64> {
+        self.values.lock().unwrap().get(&self.desc.key(labels)).copied()
+    }
+}
+
+impl Metric for Gauge {
+    fn encode(&self, out: &mut String) {
+        self.desc.header(out, "gauge");
+        for (labels, value) in self.values.lock().unwrap().iter() {
+            let _ = writeln!(out, "{}{} {}", self.desc.name, self.desc.labels(labels, None), value);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Buckets {
+    // Non-cumulative; summed up when encoded
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+// Distribution of observations per label set over fixed upper bounds
+pub struct Histogram {
+    desc: Desc,
+    bounds: Vec<f64>,
+    values: Mutex<BTreeMap<Vec<String>, Buckets>>,
+}
+
+impl Histogram {
+    pub fn new(name: &str, help: &str, labels: &[&'static str], bounds: &[f64]) -> Self {
+        Self {
+            desc: Desc::new(name, help, labels),
+            bounds: bounds.to_vec(),
+            values: Mutex::new(BTreeMap::new()),
+        }
+    }
+    
+    pub fn observe(&self, labels: &[&str], value: f64) {
+        let mut values = self.values.lock().unwrap();
+        let buckets = values.entry(self.desc.key(labels)).or_insert_with(|| Buckets {
+            counts: vec![0; self.bounds.len()],
+            ..Buckets::default()
+        });
+        
+        if let Some(bucket) = self.bounds.iter().position(|bound| value <= *bound) {
+            buckets.counts[bucket] += 1;
+        }
+        buckets.sum += value;
+        buckets.count += 1;
+    }
+    
+    pub fn count(&self, labels: &[&str]) -> u64 {
+        self.values
+            .lock()
+            .unwrap()
+            .get(&self.desc.key(labels))
+            .map(|buckets| buckets.count)
+            .unwrap_or_default()
+    }
+}
+
+impl Metric for Histogram {
+    fn encode(&self, out: &mut String) {
+        self.desc.header(out, "histogram");
+        for (labels, buckets) in self.values.lock().unwrap().iter() {
+            let mut cumulative = 0;
+            for (bound, count) in self.bounds.iter().zip(&buckets.counts) {
+                cumulative += count;
+                let le = self.desc.labels(labels, Some(("le", &bound.to_string())));
+                let _ = writeln!(out, "{}_bucket{} {}", self.desc.name, le, cumulative);
+            }
+            let le = self.desc.labels(labels, Some(("le", "+Inf")));
+            let _ = writeln!(out, "{}_bucket{} {}", self.desc.name, le, buckets.count);
+            
+            let labels = self.desc.labels(labels, None);
+            let _ = writeln!(out, "{}_sum{} {}", self.desc.name, labels, buckets.sum);
+            let _ = writeln!(out, "{}_count{} {}", self.desc.name, labels, buckets.count);
+        }
+    }
+}
+
+// The metric families one process exposes on /metrics
+#[derive(Default)]
+pub struct Registry {
+    metrics: Mutex<Vec<Arc<dyn Metric>>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn register<M: Metric + 'static>(&self, metric: M) -> Arc<M> {
+        let metric = Arc::new(metric);
+        self.metrics.lock().unwrap().push(metric.clone());
+        metric
+    }
+    
+    pub fn encode(&self) -> String {
+        let mut out = String::new();
+        for metric in self.metrics.lock().unwrap().iter() {
+            metric.encode(&mut out);
+        }
+        out
+    }
+}
@@ -0,0 +1,288 @@
+use async_trait::async_trait;
+use ethers::prelude::*;
+use ethers::providers::{JsonRpcClient, RpcError};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{header, Body, Method, Request, Response, Server, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::convert::Infallible;
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::metrics::{Counter, Gauge, Registry};
+
+// How often the chain head and wallet balance are polled. The RPC endpoint
+// counts as unreachable once no poll has succeeded for three intervals.
+pub const PROBE_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub rpc_connected: bool,
+    pub head_block: Option<u64>,
+    pub processed_block: Option<u64>,
+    pub lag_blocks: Option<u64>,
+    pub max_lag_blocks: u64,
+    pub error: Option<String>,
+}
+
+impl HealthReport {
+    // Live: the RPC endpoint answers
+    pub fn healthy(&self) -> bool {
+        self.rpc_connected
+    }
+    
+    // Ready: live, and the daemon has caught up with the chain head
+    pub fn ready(&self) -> bool {
+        self.healthy() && self.lag_blocks.is_some_and(|lag| lag <= self.max_lag_blocks)
+    }
+}
+
+#[derive(Debug, Default)]
+struct HealthState {
+    last_ok: Option<Instant>,
+    error: Option<String>,
+    head_block: Option<u64>,
+    processed_block: Option<u64>,
+}
+
+pub struct Health {
+    max_lag_blocks: u64,
+    stale_after: Duration,
+    state: Mutex<HealthState>,
+}
+
+impl Health {
+    pub fn new(max_lag_blocks: u64, stale_after: Duration) -> Self {
+        Self {
+            max_lag_blocks,
+            stale_after,
+            state: Mutex::new(HealthState::default()),
+        }
+    }
+    
+    pub fn rpc_ok(&self, head_block: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.last_ok = Some(Instant::now());
+        state.error = None;
+        state.head_block = Some(head_block);
+    }
+    
+    pub fn rpc_failed(&self, error: impl fmt::Display) {
+        let mut state = self.state.lock().unwrap();
+        state.last_ok = None;
+        state.error = Some(error.to_string());
+    }
+    
+    // Last block the daemon has fully handled; never moves backwards
+    pub fn processed(&self, block: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.processed_block = state.processed_block.max(Some(block));
+    }
+    
+    pub fn report(&self) -> HealthReport {
+        let state = self.state.lock().unwrap();
+        let rpc_connected = state.last_ok.is_some_and(|at| at.elapsed() <= self.stale_after);
+        
+        let error = match (&state.error, rpc_connected) {
+            (Some(error), _) => Some(error.clone()),
+            (None, false) if state.last_ok.is_some() => Some("no answer from the RPC endpoint".to_string()),
+            (None, false) => Some("RPC endpoint not probed yet".to_string()),
+            (None, true) => None,
+        };
+        
+        HealthReport {
+            rpc_connected,
+            head_block: state.head_block,
+            processed_block: state.processed_block,
+            lag_blocks: state
+                .head_block
+                .zip(state.processed_block)
+                .map(|(head, processed)| head.saturating_sub(processed)),
+            max_lag_blocks: self.max_lag_blocks,
+            error,
+        }
+    }
+}
+
+pub type MeteredProvider = Provider<Metered<Http>>;
+
+// JSON-RPC transport that counts failed requests per method. `kind` is
+// `response` for errors returned by the node (including reverts) and
+// `transport` for requests that never got an answer.
+#[derive(Clone)]
+pub struct Metered<T> {
+    inner: T,
+    errors: Arc<Counter>,
+}
+
+impl<T: fmt::Debug> fmt::Debug for Metered<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Metered").field("inner", &self.inner).finish()
+    }
+}
+
+#[async_trait]
+impl<T: JsonRpcClient> JsonRpcClient for Metered<T> {
+    type Error = T::Error;
+    
+    async fn request<P, R>(&self, method: &str, params: P) -> Result<R, Self::Error>
+    where
+        P: fmt::Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        let result = self.inner.request(method, params).await;
+        if let Err(e) = &result {
+            let kind = if e.as_error_response().is_some() { "response" } else { "transport" };
+            self.errors.inc(&[method, kind]);
+        }
+        result
+    }
+}
+
+// Metrics and health shared by both daemons, served over HTTP on
+// /metrics, /healthz and /readyz. Daemon-specific metrics are registered
+// on `registry()`.
+pub struct Telemetry {
+    registry: Registry,
+    health: Health,
+    rpc_errors: Arc<Counter>,
+    head_block: Arc<Gauge>,
+    processed_block: Arc<Gauge>,
+    wallet_balance: Arc<Gauge>,
+}
+
+impl Telemetry {
+    pub fn new(max_lag_blocks: u64) -> Self {
+        let registry = Registry::new();
+        
+        Self {
+            health: Health::new(max_lag_blocks, PROBE_INTERVAL * 3),
+            rpc_errors: registry.register(Counter::new(
+                "polyneurons_rpc_errors_total",
+                "Failed JSON-RPC requests",
+                &["method", "kind"],
+            )),
+            head_block: registry.register(Gauge::new("polyneurons_chain_head_block", "Latest block of the RPC endpoint", &[])),
+            processed_block: registry.register(Gauge::new(
+                "polyneurons_last_processed_block",
+                "Last block whose events have been handled",
+                &[],
+            )),
+            wallet_balance: registry.register(Gauge::new(
+                "polyneurons_wallet_balance",
+                "Balance of the signing account in the native token",
+                &["account"],
+            )),
+            registry,
+        }
+    }
+    
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+    
+    pub fn report(&self) -> HealthReport {
+        self.health.report()
+    }
+    
+    // Wraps an RPC transport so its failures show up in rpc_errors_total
+    pub fn metered<T>(&self, transport: T) -> Metered<T> {
+        Metered {
+            inner: transport,
+            errors: self.rpc_errors.clone(),
+        }
+    }
+    
+    pub fn processed(&self, block: u64) {
+        self.health.processed(block);
+        self.processed_block.set(&[], block as f64);
+    }
+    
+    pub async fn probe<M: Middleware>(&self, client: &M, account: Address) {
+        match client.get_block_number().await {
+            Ok(head) => {
+                self.health.rpc_ok(head.as_u64());
+                self.head_block.set(&[], head.as_u64() as f64);
+            }
+            Err(e) => {
+                self.health.rpc_failed(e);
+                return;
+            }
+        }
+        
+        if let Ok(balance) = client.get_balance(account, None).await {
+            let balance = ethers::utils::format_ether(balance).parse().unwrap_or(f64::NAN);
+            self.wallet_balance.set(&[&format!("{:?}", account)], balance);
+        }
+    }
+    
+    pub fn spawn_probe<M: Middleware + 'static>(self: Arc<Self>, client: Arc<M>, account: Address) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(PROBE_INTERVAL);
+            
+            loop {
+                ticker.tick().await;
+                self.probe(client.as_ref(), account).await;
+            }
+        });
+    }
+    
+    // Binds `addr` and serves in the background; returns the bound address
+    pub fn serve(self: Arc<Self>, addr: SocketAddr) -> Result<SocketAddr, hyper::Error> {
+        let make_service = make_service_fn(move |_| {
+            let telemetry = self.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                    let response = telemetry.respond(request.method(), request.uri().path());
+                    async move { Ok::<_, Infallible>(response) }
+                }))
+            }
+        });
+        
+        let server = Server::try_bind(&addr)?.serve(make_service);
+        let local_addr = server.local_addr();
+        tokio::spawn(async move {
+            if let Err(e) = server.await {
+                tracing::warn!("Metrics server error: {}", e);
+            }
+        });
+        
+        Ok(local_addr)
+    }
+    
+    fn respond(&self, method: &Method, path: &str) -> Response<Body> {
+        if method != Method::GET {
+            return plain(StatusCode::METHOD_NOT_ALLOWED, "method not allowed\n");
+        }
+        
+        match path {
+            "/metrics" => Response::builder()
+                .header(header::CONTENT_TYPE, "text/plain; version=0.0.4")
+                .body(Body::from(self.registry.encode()))
+                .unwrap(),
+            "/healthz" => {
+                let report = self.report();
+                json(report.healthy(), &report)
+            }
+            "/readyz" => {
+                let report = self.report();
+                json(report.ready(), &report)
+            }
+            _ => plain(StatusCode::NOT_FOUND, "not found\n"),
+        }
+    }
+}
+
+fn plain(status: StatusCode, body: &'static str) -> Response<Body> {
+    Response::builder().status(status).body(Body::from(body)).unwrap()
+}
+
+fn json(ok: bool, report: &HealthReport) -> Response<Body> {
+    Response::builder()
+        .status(if ok { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE })
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(serde_json::to_vec(report).unwrap_or_default()))
+        .unwrap()
+}
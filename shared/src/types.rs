@@ -1,30 +1,57 @@
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::validation::Fields;
+pub use crate::validation::PayloadError;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReasoningTask {
     pub task_id: u64,
     pub task_type: String,
     pub data: Value,
+    #[serde(default)]
+    pub data_hash: String,
     pub requester: String,
     pub reward: u64,
     pub deadline: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ReasoningResult {
     pub prediction: Value,
     pub confidence_score: f64,
+    // Measured by `TaskProcessor`: wall-clock and CPU time of the module run
     pub computation_time_ms: u64,
+    #[serde(default)]
+    pub cpu_time_ms: u64,
+    // Deterministic cost of the run, the same on every machine; committed
+    // on chain as the proof's `computationCost`
+    #[serde(default)]
+    pub work_units: u64,
+}
+
+impl ReasoningResult {
+    pub fn output<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_value(self.prediction.clone())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CognitiveNode {
     pub validator_address: String,
     pub node_id: String,
+    // `stakedAmount`, in wei
+    #[serde(default)]
+    pub staked_amount: u128,
     pub reputation_score: u64,
     pub tasks_completed: u64,
     pub is_active: bool,
+    #[serde(default)]
+    pub registered_at: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,3 +63,773 @@ pub struct ProofOfReasoning {
     pub verified: bool,
     pub confirmations: u32,
 }
+
+// Typed view of `ReasoningTask.data` for one task type. Requesters build
+// these to publish a task, reasoners parse them back with `from_value`.
+pub trait TaskPayload: Serialize + Sized {
+    const TASK_TYPE: &'static str;
+    
+    fn from_value(value: &Value) -> Result<Self, PayloadError>;
+    
+    fn to_value(&self) -> Value {
+        serde_json::to_value(self).expect("payload types serialize to JSON")
+    }
+}
+
+// Longest forecast a market prediction task may ask for, in steps of the
+// price series
+pub const MAX_HORIZON: u64 = 100;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForecastModel {
+    // Mean of the last prices
+    Sma,
+    // Exponentially weighted mean of the prices
+    Ema,
+    // Holt's double exponential smoothing: a level plus a trend
+    #[default]
+    Holt,
+    // Least-squares line through the log prices, i.e. a constant expected
+    // log return; needs positive prices
+    LinearRegression,
+    // ARIMA(1,1,0) with drift: price changes follow an AR(1) process
+    Arima,
+}
+
+impl ForecastModel {
+    pub const ALL: [ForecastModel; 5] = [Self::Sma, Self::Ema, Self::Holt, Self::LinearRegression, Self::Arima];
+    
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Sma => "sma",
+            Self::Ema => "ema",
+            Self::Holt => "holt",
+            Self::LinearRegression => "linear_regression",
+            Self::Arima => "arima",
+        }
+    }
+    
+    // Fewest prices the model can be fitted on
+    pub fn min_history(self) -> usize {
+        match self {
+            Self::Sma | Self::Ema => 1,
+            Self::Holt | Self::LinearRegression => 2,
+            Self::Arima => 3,
+        }
+    }
+}
+
+impl FromStr for ForecastModel {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().find(|model| model.as_str() == s).ok_or_else(|| {
+            let known: Vec<_> = Self::ALL.iter().map(|model| model.as_str()).collect();
+            format!("unknown model `{}`, expected one of {}", s, known.join(", "))
+        })
+    }
+}
+
+// Most candles one asset may span once gaps are filled
+pub const MAX_CANDLES: u64 = 10_000;
+
+// Spacing of a candle series, written as a count and a unit: `30s`, `5m`,
+// `4h`, `1d` or `1w`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Interval(u64);
+
+impl Interval {
+    const UNITS: [(char, u64); 5] = [('w', 604_800), ('d', 86_400), ('h', 3_600), ('m', 60), ('s', 1)];
+    
+    pub fn from_secs(secs: u64) -> Option<Self> {
+        (secs > 0).then_some(Self(secs))
+    }
+    
+    pub fn secs(self) -> u64 {
+        self.0
+    }
+}
+
+impl FromStr for Interval {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("`{}` is not an interval like 5m, 1h or 1d", s);
+        
+        let unit = s.chars().last().ok_or_else(invalid)?;
+        let (_, secs) = Self::UNITS.into_iter().find(|(name, _)| *name == unit).ok_or_else(invalid)?;
+        let count: u64 = s[..s.len() - 1].parse().map_err(|_| invalid())?;
+        
+        count.checked_mul(secs).and_then(Self::from_secs).ok_or_else(invalid)
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (unit, secs) = Self::UNITS.into_iter().find(|(_, secs)| self.0.is_multiple_of(*secs)).unwrap_or(('s', 1));
+        write!(f, "{}{}", self.0 / secs, unit)
+    }
+}
+
+impl TryFrom<String> for Interval {
+    type Error = String;
+    
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Interval> for String {
+    fn from(interval: Interval) -> Self {
+        interval.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Candle {
+    // Unix seconds at which the candle opens
+    pub timestamp: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+impl Candle {
+    fn parse(fields: &Fields) -> Result<Self, PayloadError> {
+        let candle = Self {
+            timestamp: fields.u64("timestamp")?,
+            open: fields.f64("open")?,
+            high: fields.f64("high")?,
+            low: fields.f64("low")?,
+            close: fields.f64("close")?,
+            volume: fields.f64("volume")?,
+        };
+        
+        if candle.high < candle.open.max(candle.close).max(candle.low) {
+            return Err(fields.invalid("high", "must be at least open, close and low"));
+        }
+        if candle.low > candle.open.min(candle.close) {
+            return Err(fields.invalid("low", "must be at most open and close"));
+        }
+        if candle.volume < 0.0 {
+            return Err(fields.invalid("volume", "must not be negative"));
+        }
+        
+        Ok(candle)
+    }
+}
+
+// OHLCV history of one symbol. Candles are in time order and on one grid,
+// but the grid may have gaps.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetSeries {
+    pub symbol: String,
+    pub interval: Interval,
+    pub candles: Vec<Candle>,
+}
+
+impl AssetSeries {
+    fn parse(fields: &Fields) -> Result<Self, PayloadError> {
+        let symbol = fields.string("symbol")?;
+        if symbol.is_empty() {
+            return Err(fields.invalid("symbol", "must not be empty"));
+        }
+        
+        let interval = fields
+            .string("interval")?
+            .parse::<Interval>()
+            .map_err(|reason| fields.invalid("interval", reason))?;
+        
+        let candles = fields
+            .objects("candles")?
+            .iter()
+            .map(Candle::parse)
+            .collect::<Result<Vec<_>, PayloadError>>()?;
+        if candles.is_empty() {
+            return Err(fields.invalid("candles", "at least one candle is required"));
+        }
+        
+        for (idx, pair) in candles.windows(2).enumerate() {
+            let field = format!("candles[{}].timestamp", idx + 1);
+            if pair[1].timestamp <= pair[0].timestamp {
+                return Err(fields.invalid(&field, "must be after the previous candle"));
+            }
+            if !(pair[1].timestamp - pair[0].timestamp).is_multiple_of(interval.secs()) {
+                return Err(fields.invalid(&field, format!("is off the {} grid of the previous candle", interval)));
+            }
+        }
+        
+        Ok(Self {
+            symbol,
+            interval,
+            candles,
+        })
+    }
+    
+    // Candles the series spans once gaps are filled and, with `resample`,
+    // merged into buckets of that interval counted from the Unix epoch
+    pub fn steps(&self, resample: Option<Interval>) -> u64 {
+        let first = self.candles[0].timestamp;
+        let last = self.candles[self.candles.len() - 1].timestamp;
+        
+        match resample {
+            Some(to) => last / to.secs() - first / to.secs() + 1,
+            None => (last - first) / self.interval.secs() + 1,
+        }
+    }
+}
+
+fn default_horizon() -> u64 {
+    1
+}
+
+// Refuses histories `model` cannot be fitted on
+fn check_history(
+    fields: &Fields,
+    name: &str,
+    model: ForecastModel,
+    len: u64,
+    mut prices: impl Iterator<Item = f64>,
+) -> Result<(), PayloadError> {
+    if len < model.min_history() as u64 {
+        return Err(fields.invalid(
+            name,
+            format!("{} needs at least {} prices", model.as_str(), model.min_history()),
+        ));
+    }
+    if model == ForecastModel::LinearRegression && prices.any(|price| price <= 0.0) {
+        return Err(fields.invalid(name, "linear_regression needs positive prices"));
+    }
+    
+    Ok(())
+}
+
+// Either a bare `prices` series or OHLCV `assets`, which are forecast one
+// by one on their closing prices
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketPredictionInput {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prices: Vec<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assets: Vec<AssetSeries>,
+    // Interval every asset is merged into before forecasting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resample: Option<Interval>,
+    #[serde(default)]
+    pub model: ForecastModel,
+    // Steps ahead of the last price to forecast
+    #[serde(default = "default_horizon")]
+    pub horizon: u64,
+}
+
+impl Default for MarketPredictionInput {
+    fn default() -> Self {
+        Self {
+            symbol: None,
+            prices: Vec::new(),
+            assets: Vec::new(),
+            resample: None,
+            model: ForecastModel::default(),
+            horizon: default_horizon(),
+        }
+    }
+}
+
+impl TaskPayload for MarketPredictionInput {
+    const TASK_TYPE: &'static str = "market_prediction";
+    
+    fn from_value(value: &Value) -> Result<Self, PayloadError> {
+        let fields = Fields::root(value)?;
+        
+        let model = match fields.opt_string("model")? {
+            Some(model) => model.parse().map_err(|reason: String| fields.invalid("model", reason))?,
+            None => ForecastModel::default(),
+        };
+        
+        let horizon = fields.opt_u64("horizon")?.unwrap_or_else(default_horizon);
+        if !(1..=MAX_HORIZON).contains(&horizon) {
+            return Err(fields.invalid("horizon", format!("must be between 1 and {}", MAX_HORIZON)));
+        }
+        
+        let resample = fields
+            .opt_string("resample")?
+            .map(|resample| resample.parse::<Interval>().map_err(|reason| fields.invalid("resample", reason)))
+            .transpose()?;
+        
+        if !fields.contains("assets") {
+            if resample.is_some() {
+                return Err(fields.invalid("resample", "only applies to assets"));
+            }
+            
+            let prices = fields.f64_array("prices")?;
+            if prices.is_empty() {
+                return Err(fields.invalid("prices", "at least one price is required"));
+            }
+            check_history(&fields, "prices", model, prices.len() as u64, prices.iter().copied())?;
+            
+            return Ok(Self {
+                symbol: fields.opt_string("symbol")?,
+                prices,
+                model,
+                horizon,
+                ..Self::default()
+            });
+        }
+        
+        if fields.contains("prices") {
+            return Err(fields.invalid("prices", "give either prices or assets, not both"));
+        }
+        if fields.contains("symbol") {
+            return Err(fields.invalid("symbol", "assets carry their own symbols"));
+        }
+        
+        let mut assets = Vec::new();
+        for (idx, asset_fields) in fields.objects("assets")?.iter().enumerate() {
+            let asset = AssetSeries::parse(asset_fields)?;
+            
+            if assets.iter().any(|other: &AssetSeries| other.symbol == asset.symbol) {
+                return Err(asset_fields.invalid("symbol", format!("duplicate symbol `{}`", asset.symbol)));
+            }
+            if let Some(to) = resample {
+                if !to.secs().is_multiple_of(asset.interval.secs()) {
+                    return Err(fields.invalid(
+                        "resample",
+                        format!("{} is not a multiple of the {} interval of assets[{}]", to, asset.interval, idx),
+                    ));
+                }
+            }
+            
+            let steps = asset.steps(resample);
+            if steps > MAX_CANDLES {
+                return Err(asset_fields.invalid(
+                    "candles",
+                    format!("span {} candles once gaps are filled, at most {}", steps, MAX_CANDLES),
+                ));
+            }
+            check_history(asset_fields, "candles", model, steps, asset.candles.iter().map(|candle| candle.close))?;
+            
+            assets.push(asset);
+        }
+        if assets.is_empty() {
+            return Err(fields.invalid("assets", "at least one asset is required"));
+        }
+        
+        Ok(Self {
+            assets,
+            resample,
+            model,
+            horizon,
+            ..Self::default()
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Trend {
+    Bullish,
+    Bearish,
+    Sideways,
+}
+
+// Expected price `step` steps after the last one, with its prediction
+// interval
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForecastStep {
+    pub step: u64,
+    // Opening time of the forecast candle, for asset forecasts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+    pub price: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+// Errors of the forecasts the model would have made on the history itself
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Backtest {
+    pub samples: usize,
+    pub mae: f64,
+    pub rmse: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketPredictionOutput {
+    // Price at the end of the horizon
+    pub predicted_price: f64,
+    pub confidence: f64,
+    pub trend: Trend,
+    pub model: ForecastModel,
+    pub horizon: u64,
+    // Change per step the model currently estimates
+    pub slope: f64,
+    pub interval_level: f64,
+    pub forecast: Vec<ForecastStep>,
+    pub backtest: Backtest,
+}
+
+// Candles the grid of an asset was missing, from the first to the last
+// missing opening time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Gap {
+    pub from: u64,
+    pub to: u64,
+    pub missing: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetPrediction {
+    pub symbol: String,
+    // Spacing of the forecast steps, after any resampling
+    pub interval: Interval,
+    pub candles: usize,
+    pub gaps: Vec<Gap>,
+    #[serde(flatten)]
+    pub forecast: MarketPredictionOutput,
+}
+
+// Output for `assets` input, one entry per asset in input order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BasketPredictionOutput {
+    pub assets: Vec<AssetPrediction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionSample {
+    pub value: f64,
+    pub gas: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnomalyDetectionInput {
+    pub transactions: Vec<TransactionSample>,
+}
+
+impl TaskPayload for AnomalyDetectionInput {
+    const TASK_TYPE: &'static str = "anomaly_detection";
+    
+    fn from_value(value: &Value) -> Result<Self, PayloadError> {
+        let fields = Fields::root(value)?;
+        
+        let transactions = fields
+            .objects("transactions")?
+            .iter()
+            .map(|tx| {
+                Ok(TransactionSample {
+                    value: tx.f64("value")?,
+                    gas: tx.f64("gas")?,
+                })
+            })
+            .collect::<Result<_, PayloadError>>()?;
+        
+        Ok(Self { transactions })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Low,
+    High,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnomalyDetectionOutput {
+    pub anomalies_detected: usize,
+    pub anomaly_indices: Vec<usize>,
+    pub severity: Severity,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskScoringInput {
+    pub contract_address: String,
+    pub code_complexity: f64,
+    pub audited: bool,
+    pub tx_volume: f64,
+}
+
+impl TaskPayload for RiskScoringInput {
+    const TASK_TYPE: &'static str = "risk_scoring";
+    
+    fn from_value(value: &Value) -> Result<Self, PayloadError> {
+        let fields = Fields::root(value)?;
+        
+        let contract_address = fields.string("contract_address")?;
+        if contract_address.parse::<ethers::types::Address>().is_err() {
+            return Err(fields.invalid("contract_address", "expected a 20-byte hex address"));
+        }
+        
+        let code_complexity = fields.f64("code_complexity")?;
+        if !(0.0..=1.0).contains(&code_complexity) {
+            return Err(fields.invalid("code_complexity", "must be between 0 and 1"));
+        }
+        
+        let tx_volume = fields.f64("tx_volume")?;
+        if tx_volume < 0.0 {
+            return Err(fields.invalid("tx_volume", "must not be negative"));
+        }
+        
+        Ok(Self {
+            contract_address,
+            code_complexity,
+            audited: fields.bool("audited")?,
+            tx_volume,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RiskLevel {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskFactors {
+    pub code_complexity: f64,
+    pub audited: bool,
+    pub tx_volume: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskScoringOutput {
+    pub contract: String,
+    pub risk_score: f64,
+    pub risk_level: RiskLevel,
+    pub factors: RiskFactors,
+}
+
+// Indicator and its window sizes, each in prices. All of them read closing
+// prices; ATR and VWAP also read highs, lows and volumes.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "name", rename_all = "snake_case")]
+pub enum Indicator {
+    // Wilder's relative strength index
+    Rsi { period: u64 },
+    Macd { fast: u64, slow: u64, signal: u64 },
+    // Moving average with bands `std_dev` standard deviations away
+    Bollinger { period: u64, std_dev: f64 },
+    // Wilder's average true range
+    Atr { period: u64 },
+    // Volume-weighted typical price over the last `period` candles, or
+    // over all of them
+    Vwap {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        period: Option<u64>,
+    },
+    // Standard deviation of log returns, scaled to a year when
+    // `periods_per_year` is given
+    Volatility {
+        period: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        periods_per_year: Option<u64>,
+    },
+}
+
+impl Indicator {
+    const NAMES: [&'static str; 6] = ["rsi", "macd", "bollinger", "atr", "vwap", "volatility"];
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Rsi { .. } => "rsi",
+            Self::Macd { .. } => "macd",
+            Self::Bollinger { .. } => "bollinger",
+            Self::Atr { .. } => "atr",
+            Self::Vwap { .. } => "vwap",
+            Self::Volatility { .. } => "volatility",
+        }
+    }
+    
+    pub fn needs_candles(&self) -> bool {
+        matches!(self, Self::Atr { .. } | Self::Vwap { .. })
+    }
+    
+    // Prices needed before the indicator has a value; `None` for windows
+    // too large to count
+    pub fn min_len(&self) -> Option<u64> {
+        match *self {
+            Self::Rsi { period } | Self::Volatility { period, .. } => period.checked_add(1),
+            Self::Macd { slow, signal, .. } => slow.checked_add(signal - 1),
+            Self::Bollinger { period, .. } | Self::Atr { period } => Some(period),
+            Self::Vwap { period } => Some(period.unwrap_or(1)),
+        }
+    }
+    
+    fn parse(fields: &Fields) -> Result<Self, PayloadError> {
+        let window = |name: &str| -> Result<Option<u64>, PayloadError> {
+            match fields.opt_u64(name)? {
+                Some(0) => Err(fields.invalid(name, "must be at least 1")),
+                value => Ok(value),
+            }
+        };
+        
+        let indicator = match fields.string("name")?.as_str() {
+            "rsi" => Self::Rsi {
+                period: window("period")?.unwrap_or(14),
+            },
+            "macd" => Self::Macd {
+                fast: window("fast")?.unwrap_or(12),
+                slow: window("slow")?.unwrap_or(26),
+                signal: window("signal")?.unwrap_or(9),
+            },
+            "bollinger" => Self::Bollinger {
+                period: window("period")?.unwrap_or(20),
+                std_dev: fields.opt_f64("std_dev")?.unwrap_or(2.0),
+            },
+            "atr" => Self::Atr {
+                period: window("period")?.unwrap_or(14),
+            },
+            "vwap" => Self::Vwap {
+                period: window("period")?,
+            },
+            "volatility" => Self::Volatility {
+                period: window("period")?.unwrap_or(20),
+                periods_per_year: window("periods_per_year")?,
+            },
+            name => {
+                return Err(fields.invalid(
+                    "name",
+                    format!("unknown indicator `{}`, expected one of {}", name, Self::NAMES.join(", ")),
+                ))
+            }
+        };
+        
+        match indicator {
+            Self::Macd { fast, slow, .. } if fast >= slow => Err(fields.invalid("fast", "must be below slow")),
+            Self::Bollinger { std_dev, .. } if std_dev <= 0.0 => Err(fields.invalid("std_dev", "must be positive")),
+            Self::Volatility { period: 1, .. } => Err(fields.invalid("period", "must be at least 2")),
+            indicator => Ok(indicator),
+        }
+    }
+}
+
+// Most indicators one task may ask for
+pub const MAX_INDICATORS: usize = 32;
+
+// A bare `prices` series or OHLCV `candles` of one symbol, at most
+// `MAX_CANDLES` long
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TechnicalIndicatorsInput {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prices: Vec<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub candles: Vec<Candle>,
+    pub indicators: Vec<Indicator>,
+}
+
+impl TechnicalIndicatorsInput {
+    // `prices`, or the closing prices of `candles`
+    pub fn closes(&self) -> Vec<f64> {
+        if self.candles.is_empty() {
+            self.prices.clone()
+        } else {
+            self.candles.iter().map(|candle| candle.close).collect()
+        }
+    }
+}
+
+impl TaskPayload for TechnicalIndicatorsInput {
+    const TASK_TYPE: &'static str = "technical_indicators";
+    
+    fn from_value(value: &Value) -> Result<Self, PayloadError> {
+        let fields = Fields::root(value)?;
+        
+        let (series, prices, candles) = if fields.contains("candles") {
+            if fields.contains("prices") {
+                return Err(fields.invalid("prices", "give either prices or candles, not both"));
+            }
+            
+            let candles = fields
+                .objects("candles")?
+                .iter()
+                .map(Candle::parse)
+                .collect::<Result<Vec<_>, PayloadError>>()?;
+            for (idx, pair) in candles.windows(2).enumerate() {
+                if pair[1].timestamp <= pair[0].timestamp {
+                    return Err(fields.invalid(
+                        &format!("candles[{}].timestamp", idx + 1),
+                        "must be after the previous candle",
+                    ));
+                }
+            }
+            ("candles", Vec::new(), candles)
+        } else {
+            ("prices", fields.f64_array("prices")?, Vec::new())
+        };
+        
+        let input = Self {
+            symbol: fields.opt_string("symbol")?,
+            prices,
+            candles,
+            indicators: fields
+                .objects("indicators")?
+                .iter()
+                .map(Indicator::parse)
+                .collect::<Result<_, PayloadError>>()?,
+        };
+        
+        let closes = input.closes();
+        if closes.is_empty() {
+            return Err(fields.invalid(series, "at least one price is required"));
+        }
+        if closes.len() as u64 > MAX_CANDLES {
+            return Err(fields.invalid(series, format!("at most {} {} are allowed", MAX_CANDLES, series)));
+        }
+        if input.indicators.is_empty() {
+            return Err(fields.invalid("indicators", "at least one indicator is required"));
+        }
+        if input.indicators.len() > MAX_INDICATORS {
+            return Err(fields.invalid("indicators", format!("at most {} indicators are allowed", MAX_INDICATORS)));
+        }
+        
+        for (idx, indicator) in input.indicators.iter().enumerate() {
+            let field = format!("indicators[{}]", idx);
+            if indicator.needs_candles() && input.candles.is_empty() {
+                return Err(fields.invalid(&field, format!("{} needs candles", indicator.name())));
+            }
+            match indicator.min_len() {
+                Some(min_len) if closes.len() as u64 >= min_len => {}
+                Some(min_len) => {
+                    return Err(fields.invalid(
+                        &field,
+                        format!("{} needs at least {} {}", indicator.name(), min_len, series),
+                    ));
+                }
+                None => return Err(fields.invalid(&field, format!("{} window is too large", indicator.name()))),
+            }
+            if matches!(indicator, Indicator::Volatility { .. }) && closes.iter().any(|close| *close <= 0.0) {
+                return Err(fields.invalid(series, "volatility needs positive prices"));
+            }
+        }
+        
+        Ok(input)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndicatorResult {
+    #[serde(flatten)]
+    pub indicator: Indicator,
+    // Each line at the last price
+    pub latest: BTreeMap<String, Option<f64>>,
+    // Each line at every price, null until its window has filled
+    pub series: BTreeMap<String, Vec<Option<f64>>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TechnicalIndicatorsOutput {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    pub indicators: Vec<IndicatorResult>,
+}
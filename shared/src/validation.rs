@@ -0,0 +1,157 @@
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PayloadError {
+    #[error("missing field `{0}`")]
+    MissingField(String),
+    #[error("field `{field}` must be {expected}")]
+    WrongType { field: String, expected: &'static str },
+    #[error("field `{field}` is invalid: {reason}")]
+    Invalid { field: String, reason: String },
+}
+
+impl PayloadError {
+    pub fn field(&self) -> &str {
+        match self {
+            Self::MissingField(field) => field,
+            Self::WrongType { field, .. } | Self::Invalid { field, .. } => field,
+        }
+    }
+}
+
+// Field accessor that keeps track of where it is in the payload, so errors
+// read `transactions[3].gas` instead of a bare serde message.
+pub(crate) struct Fields<'a> {
+    map: &'a Map<String, Value>,
+    path: String,
+}
+
+impl<'a> Fields<'a> {
+    pub fn root(value: &'a Value) -> Result<Self, PayloadError> {
+        Self::at(value, String::new())
+    }
+    
+    pub fn at(value: &'a Value, path: String) -> Result<Self, PayloadError> {
+        match value.as_object() {
+            Some(map) => Ok(Self { map, path }),
+            None => Err(PayloadError::WrongType {
+                field: if path.is_empty() { "$".to_string() } else { path },
+                expected: "an object",
+            }),
+        }
+    }
+    
+    pub fn path(&self, name: &str) -> String {
+        if self.path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", self.path, name)
+        }
+    }
+    
+    fn get(&self, name: &str) -> Option<&'a Value> {
+        self.map.get(name).filter(|value| !value.is_null())
+    }
+    
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+    
+    fn required(&self, name: &str) -> Result<&'a Value, PayloadError> {
+        self.get(name).ok_or_else(|| PayloadError::MissingField(self.path(name)))
+    }
+    
+    pub fn f64(&self, name: &str) -> Result<f64, PayloadError> {
+        number(self.required(name)?, self.path(name))
+    }
+    
+    pub fn opt_f64(&self, name: &str) -> Result<Option<f64>, PayloadError> {
+        match self.get(name) {
+            Some(_) => self.f64(name).map(Some),
+            None => Ok(None),
+        }
+    }
+    
+    pub fn bool(&self, name: &str) -> Result<bool, PayloadError> {
+        self.required(name)?.as_bool().ok_or_else(|| PayloadError::WrongType {
+            field: self.path(name),
+            expected: "a boolean",
+        })
+    }
+    
+    pub fn string(&self, name: &str) -> Result<String, PayloadError> {
+        self.required(name)?
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| PayloadError::WrongType {
+                field: self.path(name),
+                expected: "a string",
+            })
+    }
+    
+    pub fn opt_string(&self, name: &str) -> Result<Option<String>, PayloadError> {
+        match self.get(name) {
+            Some(_) => self.string(name).map(Some),
+            None => Ok(None),
+        }
+    }
+    
+    pub fn u64(&self, name: &str) -> Result<u64, PayloadError> {
+        self.required(name)?.as_u64().ok_or_else(|| PayloadError::WrongType {
+            field: self.path(name),
+            expected: "a non-negative integer",
+        })
+    }
+    
+    pub fn opt_u64(&self, name: &str) -> Result<Option<u64>, PayloadError> {
+        match self.get(name) {
+            Some(_) => self.u64(name).map(Some),
+            None => Ok(None),
+        }
+    }
+    
+    pub fn array(&self, name: &str) -> Result<&'a Vec<Value>, PayloadError> {
+        self.required(name)?.as_array().ok_or_else(|| PayloadError::WrongType {
+            field: self.path(name),
+            expected: "an array",
+        })
+    }
+    
+    pub fn f64_array(&self, name: &str) -> Result<Vec<f64>, PayloadError> {
+        let field = self.path(name);
+        
+        self.array(name)?
+            .iter()
+            .enumerate()
+            .map(|(idx, value)| number(value, format!("{}[{}]", field, idx)))
+            .collect()
+    }
+    
+    pub fn objects(&self, name: &str) -> Result<Vec<Fields<'a>>, PayloadError> {
+        let field = self.path(name);
+        
+        self.array(name)?
+            .iter()
+            .enumerate()
+            .map(|(idx, value)| Fields::at(value, format!("{}[{}]", field, idx)))
+            .collect()
+    }
+    
+    pub fn invalid(&self, name: &str, reason: impl Into<String>) -> PayloadError {
+        PayloadError::Invalid {
+            field: self.path(name),
+            reason: reason.into(),
+        }
+    }
+}
+
+fn number(value: &Value, field: String) -> Result<f64, PayloadError> {
+    match value.as_f64() {
+        Some(n) if n.is_finite() => Ok(n),
+        _ => Err(PayloadError::WrongType {
+            field,
+            expected: "a finite number",
+        }),
+    }
+}
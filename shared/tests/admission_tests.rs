@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use shared::admission::{AdmissionPolicy, Rejection};
+    use serde_json::json;
+    use shared::types::ReasoningTask;
+
+    const GWEI: u128 = 1_000_000_000;
+    const NOW: u64 = 1_700_000_000;
+
+    fn task(task_type: &str, reward: u64, deadline: u64) -> ReasoningTask {
+        ReasoningTask {
+            task_id: 1,
+            task_type: task_type.to_string(),
+            data: json!({ "prices": [1.0, 2.0, 3.0] }),
+            data_hash: String::new(),
+            requester: "0x0000000000000000000000000000000000000001".to_string(),
+            reward,
+            deadline,
+        }
+    }
+
+    #[test]
+    fn test_profitability() {
+        let policy = AdmissionPolicy::default();
+        // 150k gas at 100 gwei
+        let gas_price = 100 * GWEI;
+        assert_eq!(policy.gas_cost(gas_price), 15_000_000_000_000_000);
+        
+        let paying = task("market_prediction", 20_000_000_000_000_000, NOW + 3_600);
+        assert_eq!(policy.admit(&paying, gas_price, NOW), Ok(()));
+        
+        let cheap = task("market_prediction", 10_000_000_000_000_000, NOW + 3_600);
+        assert!(matches!(
+            policy.admit(&cheap, gas_price, NOW),
+            Err(Rejection::Unprofitable { .. })
+        ));
+        // Worth it again once gas is cheaper, so it is only put off
+        assert!(policy.admit(&cheap, gas_price, NOW).unwrap_err().is_transient());
+        assert_eq!(policy.admit(&cheap, 30 * GWEI, NOW), Ok(()));
+        
+        // Until the deadline is too close to wait any longer
+        let late = task("market_prediction", 10_000_000_000_000_000, NOW + 30);
+        let rejection = policy.admit(&late, gas_price, NOW).unwrap_err();
+        assert!(matches!(rejection, Rejection::DeadlineTooClose { .. }));
+        assert!(!rejection.is_transient());
+        
+        let margin = AdmissionPolicy {
+            min_profit: 10_000_000_000_000_000,
+            ..AdmissionPolicy::default()
+        };
+        assert!(margin.admit(&paying, gas_price, NOW).is_err());
+    }
+
+    #[test]
+    fn test_operator_rules() {
+        let policy: AdmissionPolicy = serde_json::from_value(json!({
+            "min_reward": 1_000_000,
+            "task_types": ["risk_scoring"],
+            "max_payload_bytes": 32
+        }))
+        .unwrap();
+        // Unlisted fields keep their defaults
+        assert_eq!(policy.claim_gas, AdmissionPolicy::default().claim_gas);
+        
+        assert_eq!(policy.admit(&task("risk_scoring", 1_000_000, NOW + 3_600), 0, NOW), Ok(()));
+        assert_eq!(
+            policy.admit(&task("market_prediction", 1_000_000, NOW + 3_600), 0, NOW),
+            Err(Rejection::TaskTypeNotAllowed("market_prediction".to_string()))
+        );
+        assert_eq!(
+            policy.admit(&task("risk_scoring", 999_999, NOW + 3_600), 0, NOW),
+            Err(Rejection::RewardTooLow {
+                reward: 999_999,
+                min: 1_000_000
+            })
+        );
+        
+        let mut large = task("risk_scoring", 1_000_000, NOW + 3_600);
+        assert_eq!(policy.admit_payload(&large), Ok(()));
+        large.data = json!({ "prices": vec![1.0; 16] });
+        assert!(matches!(policy.admit_payload(&large), Err(Rejection::PayloadTooLarge { .. })));
+    }
+
+    #[test]
+    fn test_deadline() {
+        let mut policy = AdmissionPolicy::default();
+        policy.compute_secs.insert("anomaly_detection".to_string(), 120);
+        
+        // 5s default compute plus 30s for the transactions
+        assert_eq!(policy.admit(&task("market_prediction", 0, NOW + 36), 0, NOW), Ok(()));
+        assert_eq!(
+            policy.admit(&task("market_prediction", 0, NOW + 35), 0, NOW),
+            Err(Rejection::DeadlineTooClose { left: 35, needed: 35 })
+        );
+        assert_eq!(
+            policy.admit(&task("anomaly_detection", 0, NOW + 100), 0, NOW),
+            Err(Rejection::DeadlineTooClose { left: 100, needed: 150 })
+        );
+        assert!(policy.admit(&task("market_prediction", 0, NOW - 10), 0, NOW).is_err());
+    }
+}
@@ -0,0 +1,209 @@
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use ethers::core::rand::thread_rng;
+    use ethers::signers::{LocalWallet, Signer};
+    use ethers::types::Address;
+    use shared::config::{Cli, ConfigError, NodeConfig, Role};
+    use std::collections::HashMap;
+
+    // First Hardhat development account
+    const DEV_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const DEV_ADDRESS: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+    const REGISTRY: &str = "0x5FbDB2315678afecb367f032d93F642f64180aa3";
+    const POR: &str = "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512";
+
+    fn apply(config: &mut NodeConfig, vars: &[(&str, &str)]) -> Result<(), ConfigError> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        config.apply_vars(|key| vars.get(key).cloned())
+    }
+
+    fn configured() -> NodeConfig {
+        let mut config = NodeConfig::default();
+        apply(&mut config, &[("PRIVATE_KEY", DEV_KEY), ("REGISTRY_ADDRESS", REGISTRY)]).unwrap();
+        config
+    }
+
+    #[test]
+    fn test_layering() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("polyneurons.toml");
+        std::fs::write(
+            &path,
+            r#"
+                [chain]
+                rpc_url = "https://rpc-amoy.polygon.technology"
+                chain_id = 80002
+                registry_address = "0x5FbDB2315678afecb367f032d93F642f64180aa3"
+                
+                [engine]
+                concurrency = 8
+                poll_interval_secs = 30
+                
+                [engine.admission]
+                min_profit = 1000
+                compute_secs = { anomaly_detection = 20 }
+                
+                [modules]
+                enabled = ["market_prediction"]
+            "#,
+        )
+        .unwrap();
+        
+        let mut config = NodeConfig::from_file(&path).unwrap();
+        assert_eq!(config.chain.chain_id, Some(80002));
+        assert_eq!(config.engine.admission.min_profit, 1000);
+        assert_eq!(config.engine.admission.compute_secs["anomaly_detection"], 20);
+        // Not in the file
+        assert_eq!(config.engine.max_task_timeout_secs, 300);
+        assert_eq!(config.engine.admission.claim_gas, 60_000);
+        
+        // Environment beats the file, empty values are ignored
+        apply(&mut config, &[("ENGINE_CONCURRENCY", "2"), ("POLYGON_WS_URL", ""), ("PRIVATE_KEY", DEV_KEY)]).unwrap();
+        assert_eq!(config.engine.concurrency, 2);
+        assert_eq!(config.chain.ws_url, None);
+        
+        // Flags beat both
+        let cli = Cli::try_parse_from([
+            "cognitive-engine",
+            "--concurrency",
+            "16",
+            "--modules",
+            "risk_scoring, anomaly_detection",
+        ])
+        .unwrap();
+        cli.apply(&mut config);
+        assert_eq!(config.engine.concurrency, 16);
+        assert_eq!(config.engine.poll_interval_secs, 30);
+        assert_eq!(
+            config.modules.enabled.iter().map(String::as_str).collect::<Vec<_>>(),
+            vec!["anomaly_detection", "risk_scoring"]
+        );
+        
+        config.validate(Role::Engine).unwrap();
+        // The validator also needs the proof contract
+        assert!(matches!(config.validate(Role::Validator), Err(ConfigError::Missing("chain.por_address"))));
+        
+        let cli = Cli::try_parse_from(["validator-plugin", "--por-address", POR, "--metrics-addr", "0.0.0.0:9102"]).unwrap();
+        cli.apply(&mut config);
+        config.validate(Role::Validator).unwrap();
+        assert_eq!(config.validator.metrics_addr, Some("0.0.0.0:9102".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_fails_clearly() {
+        // No key is never replaced by a default one
+        let mut config = NodeConfig::default();
+        apply(&mut config, &[("REGISTRY_ADDRESS", REGISTRY)]).unwrap();
+        assert!(matches!(config.validate(Role::Engine), Err(ConfigError::NoKey)));
+        assert!(matches!(config.signer.wallet(1), Err(ConfigError::NoKey)));
+        
+        let mut config = configured();
+        config.chain.registry_address = Some(Address::zero());
+        assert!(matches!(config.validate(Role::Engine), Err(ConfigError::Invalid { key, .. }) if key == "chain.registry_address"));
+        
+        let mut config = configured();
+        config.signer.keystore = Some("node.json".into());
+        assert!(matches!(config.validate(Role::Engine), Err(ConfigError::AmbiguousKey)));
+        
+        let mut config = configured();
+        config.engine.concurrency = 0;
+        assert!(config.validate(Role::Engine).is_err());
+        
+        let err = apply(&mut NodeConfig::default(), &[("ENGINE_CONCURRENCY", "many")]).unwrap_err();
+        assert!(err.to_string().starts_with("ENGINE_CONCURRENCY is invalid"));
+        
+        // Typos in the file are reported rather than ignored
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("polyneurons.toml");
+        std::fs::write(&path, "[engine]\nconcurency = 8\n").unwrap();
+        assert!(matches!(NodeConfig::from_file(&path), Err(ConfigError::Parse { .. })));
+        std::fs::write(&path, "[engine.admission]\nmin_proft = 8\n").unwrap();
+        assert!(matches!(NodeConfig::from_file(&path), Err(ConfigError::Parse { .. })));
+        
+        // Keys are not allowed in the file at all
+        std::fs::write(&path, format!("[signer]\nprivate_key = \"{}\"\n", DEV_KEY)).unwrap();
+        assert!(NodeConfig::from_file(&path).is_err());
+        
+        // A placeholder key fails without being echoed back
+        let mut config = configured();
+        config.signer.private_key = Some("your_private_key_here".to_string());
+        let err = config.signer.wallet(1).unwrap_err();
+        assert!(!err.to_string().contains("your_private_key_here"));
+        assert!(!format!("{:?}", configured()).contains(&DEV_KEY[2..]));
+    }
+
+    #[test]
+    fn test_wallet() {
+        let config = configured();
+        assert_eq!(config.chain.check_chain_id(31337).unwrap(), 31337);
+        
+        let wallet = config.signer.wallet(31337).unwrap();
+        assert_eq!(wallet.address(), DEV_ADDRESS.parse::<Address>().unwrap());
+        assert_eq!(wallet.chain_id(), 31337);
+        
+        let mut config = configured();
+        config.chain.chain_id = Some(80002);
+        assert!(matches!(
+            config.chain.check_chain_id(137),
+            Err(ConfigError::ChainIdMismatch { configured: 80002, actual: 137 })
+        ));
+    }
+
+    #[test]
+    fn test_keystore() {
+        let dir = tempfile::tempdir().unwrap();
+        let (created, _) = LocalWallet::new_keystore(dir.path(), &mut thread_rng(), "hunter2", Some("node.json")).unwrap();
+        let password_file = dir.path().join("password");
+        std::fs::write(&password_file, "hunter2\n").unwrap();
+        
+        let mut config = NodeConfig::default();
+        apply(&mut config, &[("REGISTRY_ADDRESS", REGISTRY)]).unwrap();
+        let cli = Cli::try_parse_from([
+            "validator-plugin",
+            "--keystore",
+            dir.path().join("node.json").to_str().unwrap(),
+        ])
+        .unwrap();
+        cli.apply(&mut config);
+        
+        // A keystore without a password is refused up front
+        assert!(matches!(config.validate(Role::Engine), Err(ConfigError::Missing(_))));
+        
+        config.signer.password_file = Some(password_file);
+        config.validate(Role::Engine).unwrap();
+        assert_eq!(config.signer.wallet(1).unwrap().address(), created.address());
+        
+        config.signer.password = Some("wrong".to_string());
+        assert!(matches!(config.signer.wallet(1), Err(ConfigError::Keystore { .. })));
+    }
+
+    #[test]
+    fn test_remote_and_staking_signers() {
+        let mut config = NodeConfig::default();
+        apply(
+            &mut config,
+            &[
+                ("REGISTRY_ADDRESS", REGISTRY),
+                ("REMOTE_SIGNER_URL", "http://127.0.0.1:9000"),
+                ("SIGNER_ADDRESS", DEV_ADDRESS),
+            ],
+        )
+        .unwrap();
+        config.validate(Role::Engine).unwrap();
+        // The key stays with the remote signer
+        assert!(matches!(config.signer.wallet(1), Err(ConfigError::RemoteKey)));
+        
+        config.signer.remote_url = Some("127.0.0.1:9000".to_string());
+        assert!(matches!(config.validate(Role::Engine), Err(ConfigError::Invalid { key, .. }) if key == "signer.remote_url"));
+        
+        // The staking key is separate from the operator key the daemons use
+        let config = configured();
+        assert!(matches!(config.validate(Role::Staking), Err(ConfigError::NoKey)));
+        
+        let mut config = configured();
+        apply(&mut config, &[("STAKING_REMOTE_SIGNER_URL", "https://signer.internal")]).unwrap();
+        config.validate(Role::Staking).unwrap();
+        assert!(config.staking_signer.address.is_none());
+    }
+}
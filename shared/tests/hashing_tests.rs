@@ -0,0 +1,159 @@
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Number, Value};
+    use shared::canonical::{format_es_number, to_canonical_string, CanonicalError};
+    use shared::hashing::{hashes_equal, parse_hash, proof_input_hash, result_hash, task_data_hash, to_hex};
+    use shared::types::{ReasoningResult, ReasoningTask};
+
+    // Number serialization samples from RFC 8785, Appendix B
+    #[test]
+    fn test_es_number_formatting() {
+        let samples: [(u64, &str); 18] = [
+            (0x0000000000000000, "0"),
+            (0x8000000000000000, "0"),
+            (0x0000000000000001, "5e-324"),
+            (0x8000000000000001, "-5e-324"),
+            (0x7fefffffffffffff, "1.7976931348623157e+308"),
+            (0x4340000000000000, "9007199254740992"),
+            (0x4430000000000000, "295147905179352830000"),
+            (0x44b52d02c7e14af5, "9.999999999999997e+22"),
+            (0x44b52d02c7e14af6, "1e+23"),
+            (0x444b1ae4d6e2ef4e, "999999999999999700000"),
+            (0x444b1ae4d6e2ef4f, "999999999999999900000"),
+            (0x444b1ae4d6e2ef50, "1e+21"),
+            (0x3eb0c6f7a0b5ed8c, "9.999999999999997e-7"),
+            (0x3eb0c6f7a0b5ed8d, "0.000001"),
+            (0x41b3de4355555553, "333333333.3333332"),
+            (0x41b3de4355555554, "333333333.33333325"),
+            (0x41b3de4355555557, "333333333.33333343"),
+            (0xbecbf647612f3696, "-0.0000033333333333333333"),
+        ];
+        
+        for (bits, expected) in samples {
+            assert_eq!(format_es_number(f64::from_bits(bits)).unwrap(), expected, "bits {:016x}", bits);
+        }
+        
+        assert!(format_es_number(f64::NAN).is_err());
+    }
+
+    // Example from RFC 8785, section 3.2.2
+    #[test]
+    fn test_canonical_json() {
+        let value: Value = serde_json::from_str(
+            r#"{
+                "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+                "string": "€$\u000F\u000aA'B\"\\\\\"\/",
+                "literals": [null, true, false]
+            }"#,
+        )
+        .unwrap();
+        
+        assert_eq!(
+            to_canonical_string(&value).unwrap(),
+            r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#
+        );
+    }
+
+    // Property ordering example from RFC 8785, section 3.2.3
+    #[test]
+    fn test_keys_sorted_by_utf16() {
+        let value: Value = serde_json::from_str(
+            r#"{
+                "€": "Euro Sign",
+                "\r": "Carriage Return",
+                "דּ": "Hebrew Letter Dalet With Dagesh",
+                "1": "One",
+                "😀": "Emoji: Grinning Face",
+                "\u0080": "Control",
+                "ö": "Latin Small Letter O With Diaeresis"
+            }"#,
+        )
+        .unwrap();
+        
+        let canonical = to_canonical_string(&value).unwrap();
+        let order: Vec<usize> = ["\\r", "\"1\"", "\u{80}", "\u{f6}", "\u{20ac}", "\u{1f600}", "\u{fb33}"]
+            .iter()
+            .map(|key| canonical.find(key).unwrap())
+            .collect();
+        
+        assert!(order.windows(2).all(|w| w[0] < w[1]), "{}", canonical);
+    }
+
+    #[test]
+    fn test_unsafe_integers_rejected() {
+        let err = to_canonical_string(&json!({ "reward": 10_000_000_000_000_000u64 })).unwrap_err();
+        assert!(matches!(err, CanonicalError::UnsafeInteger(_)));
+        
+        let max_safe = Value::Number(Number::from(9_007_199_254_740_991u64));
+        assert_eq!(to_canonical_string(&max_safe).unwrap(), "9007199254740991");
+    }
+
+    #[test]
+    fn test_task_data_hash_ignores_formatting() {
+        let compact: Value = serde_json::from_str(r#"{"symbol":"MATIC/USD","prices":[1.2,1.30,1.25]}"#).unwrap();
+        let pretty: Value = serde_json::from_str(
+            r#"{
+                "prices": [1.2, 1.3, 1.25],
+                "symbol": "MATIC/USD"
+            }"#,
+        )
+        .unwrap();
+        
+        assert_eq!(task_data_hash(&compact).unwrap(), task_data_hash(&pretty).unwrap());
+    }
+
+    #[test]
+    fn test_result_hash_covers_only_deterministic_fields() {
+        let result = ReasoningResult {
+            prediction: json!({ "predicted_price": 1.3166666666666667, "trend": "bullish" }),
+            confidence_score: 0.85,
+            computation_time_ms: 150,
+            ..ReasoningResult::default()
+        };
+        
+        let mut other_node = result.clone();
+        other_node.computation_time_ms = 97;
+        other_node.cpu_time_ms = 95;
+        other_node.work_units = 12;
+        other_node.prediction["predicted_price"] = json!(1.3166666666666669);
+        assert_eq!(result_hash(&result).unwrap(), result_hash(&other_node).unwrap());
+        
+        let mut different = result.clone();
+        different.prediction["trend"] = json!("bearish");
+        assert_ne!(result_hash(&result).unwrap(), result_hash(&different).unwrap());
+    }
+
+    #[test]
+    fn test_hex_roundtrip() {
+        let hash = task_data_hash(&json!({ "prices": [1.0] })).unwrap();
+        let hex = to_hex(&hash);
+        
+        assert_eq!(parse_hash(&hex), Some(hash));
+        assert!(hashes_equal(&hex, &hex.to_uppercase().replacen("0X", "0x", 1)));
+        assert_eq!(parse_hash("0xabc"), None);
+    }
+
+    #[test]
+    fn test_proof_input_hash_binds_task() {
+        let task = ReasoningTask {
+            task_id: 5,
+            task_type: "market_prediction".to_string(),
+            data: json!({ "prices": [1.0] }),
+            data_hash: format!("0x{}", "AB".repeat(32)),
+            requester: "0x0".to_string(),
+            reward: 0,
+            deadline: 0,
+        };
+        
+        // Hex case does not matter, the payload itself is covered by data_hash
+        let mut lower = task.clone();
+        lower.data_hash = lower.data_hash.to_lowercase();
+        lower.data = Value::Null;
+        assert_eq!(proof_input_hash(&task).unwrap(), proof_input_hash(&lower).unwrap());
+        
+        let mut other_task = task.clone();
+        other_task.task_id = 6;
+        assert_ne!(proof_input_hash(&task).unwrap(), proof_input_hash(&other_task).unwrap());
+    }
+
+}
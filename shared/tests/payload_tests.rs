@@ -0,0 +1,248 @@
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use shared::types::{
+        AnomalyDetectionInput, ForecastModel, Indicator, Interval, MarketPredictionInput, PayloadError,
+        RiskScoringInput, TaskPayload, TechnicalIndicatorsInput, MAX_CANDLES, MAX_INDICATORS,
+    };
+
+    fn candle(timestamp: u64, close: f64) -> Value {
+        json!({ "timestamp": timestamp, "open": close, "high": close + 1.0, "low": close - 1.0, "close": close, "volume": 10.0 })
+    }
+
+    #[test]
+    fn test_market_prediction_roundtrip() {
+        let input = MarketPredictionInput {
+            symbol: Some("MATIC/USD".to_string()),
+            prices: vec![1.2, 1.3, 1.25],
+            model: ForecastModel::Arima,
+            horizon: 5,
+            ..MarketPredictionInput::default()
+        };
+        
+        let parsed = MarketPredictionInput::from_value(&input.to_value()).unwrap();
+        assert_eq!(parsed, input);
+        
+        // Payloads from before models could be chosen still parse
+        let parsed = MarketPredictionInput::from_value(&json!({ "prices": [1.2, 1.3] })).unwrap();
+        assert_eq!((parsed.model, parsed.horizon), (ForecastModel::Holt, 1));
+    }
+
+    #[test]
+    fn test_errors_name_the_field() {
+        let err = MarketPredictionInput::from_value(&json!({ "symbol": "MATIC/USD" })).unwrap_err();
+        assert_eq!(err, PayloadError::MissingField("prices".to_string()));
+        
+        let err = MarketPredictionInput::from_value(&json!({ "prices": [1.0, "2.0"] })).unwrap_err();
+        assert_eq!(err.field(), "prices[1]");
+        
+        let err = AnomalyDetectionInput::from_value(&json!({
+            "transactions": [
+                { "value": 100.0, "gas": 21000.0 },
+                { "value": 5000.0 }
+            ]
+        }))
+        .unwrap_err();
+        assert_eq!(err, PayloadError::MissingField("transactions[1].gas".to_string()));
+        assert_eq!(err.to_string(), "missing field `transactions[1].gas`");
+        
+        let err = RiskScoringInput::from_value(&json!({
+            "contract_address": "0x1234567890123456789012345678901234567890",
+            "code_complexity": 0.7,
+            "audited": "no",
+            "tx_volume": 50.0
+        }))
+        .unwrap_err();
+        assert!(matches!(err, PayloadError::WrongType { ref field, .. } if field == "audited"));
+    }
+
+    #[test]
+    fn test_ohlcv_assets() {
+        let payload = json!({
+            "assets": [
+                { "symbol": "MATIC/USD", "interval": "1h", "candles": [candle(3_600, 1.0), candle(7_200, 1.1), candle(18_000, 1.2)] },
+                { "symbol": "ETH/USD", "interval": "30m", "candles": [candle(0, 2_000.0), candle(1_800, 2_010.0)] }
+            ],
+            "resample": "2h",
+            "model": "sma"
+        });
+        
+        let input = MarketPredictionInput::from_value(&payload).unwrap();
+        assert_eq!(input.assets.len(), 2);
+        assert_eq!(input.assets[0].interval, Interval::from_secs(3_600).unwrap());
+        assert_eq!(input.resample, Interval::from_secs(7_200));
+        // 1h..5h spans three 2h buckets, the gap at 2h..4h included
+        assert_eq!(input.assets[0].steps(None), 5);
+        assert_eq!(input.assets[0].steps(input.resample), 3);
+        assert_eq!(MarketPredictionInput::from_value(&input.to_value()).unwrap(), input);
+        
+        assert_eq!("90s".parse::<Interval>().unwrap().to_string(), "90s");
+        assert_eq!("60m".parse::<Interval>().unwrap().to_string(), "1h");
+        for interval in ["", "0m", "5", "m", "1y", "-1h"] {
+            assert!(interval.parse::<Interval>().is_err(), "{}", interval);
+        }
+        
+        let asset = |interval: &str, candles: Vec<Value>| {
+            MarketPredictionInput::from_value(&json!({
+                "assets": [{ "symbol": "MATIC/USD", "interval": interval, "candles": candles }],
+                "model": "sma"
+            }))
+        };
+        
+        let err = asset("1h", vec![candle(3_600, 1.0), candle(3_600, 1.1)]).unwrap_err();
+        assert_eq!(err.field(), "assets[0].candles[1].timestamp");
+        let err = asset("1h", vec![candle(3_600, 1.0), candle(5_400, 1.1)]).unwrap_err();
+        assert!(err.to_string().contains("off the 1h grid"));
+        let err = asset("1fortnight", vec![candle(0, 1.0)]).unwrap_err();
+        assert_eq!(err.field(), "assets[0].interval");
+        let err = asset("1s", vec![candle(0, 1.0), candle(86_400, 1.0)]).unwrap_err();
+        assert_eq!(err.field(), "assets[0].candles");
+        
+        let mut inverted = candle(0, 1.0);
+        inverted["high"] = json!(0.5);
+        assert_eq!(asset("1h", vec![inverted]).unwrap_err().field(), "assets[0].candles[0].high");
+        let mut negative = candle(0, 1.0);
+        negative["volume"] = json!(-1.0);
+        assert_eq!(asset("1h", vec![negative]).unwrap_err().field(), "assets[0].candles[0].volume");
+        
+        // Basket-level rules
+        let series = json!({ "symbol": "MATIC/USD", "interval": "1h", "candles": [candle(0, 1.0), candle(3_600, 1.1)] });
+        let err = MarketPredictionInput::from_value(&json!({ "assets": [series, series] })).unwrap_err();
+        assert_eq!(err.field(), "assets[1].symbol");
+        let err = MarketPredictionInput::from_value(&json!({ "assets": [series], "resample": "90m" })).unwrap_err();
+        assert_eq!(err.field(), "resample");
+        let err = MarketPredictionInput::from_value(&json!({ "assets": [series], "prices": [1.0] })).unwrap_err();
+        assert_eq!(err.field(), "prices");
+        let err = MarketPredictionInput::from_value(&json!({ "prices": [1.0, 2.0], "resample": "1h" })).unwrap_err();
+        assert_eq!(err.field(), "resample");
+        let err = MarketPredictionInput::from_value(&json!({ "assets": [] })).unwrap_err();
+        assert_eq!(err.field(), "assets");
+        
+        // Too little history once resampled
+        let err = MarketPredictionInput::from_value(&json!({ "assets": [series], "resample": "1d" })).unwrap_err();
+        assert_eq!(err.field(), "assets[0].candles");
+    }
+
+    #[test]
+    fn test_semantic_validation() {
+        let err = MarketPredictionInput::from_value(&json!({ "prices": [] })).unwrap_err();
+        assert!(matches!(err, PayloadError::Invalid { .. }));
+        
+        let err = MarketPredictionInput::from_value(&json!({ "prices": [1.0, 2.0], "model": "lstm" })).unwrap_err();
+        assert_eq!(err.field(), "model");
+        assert!(err.to_string().contains("expected one of sma, ema, holt, linear_regression, arima"));
+        
+        // Each model needs enough history to be fitted
+        assert!(MarketPredictionInput::from_value(&json!({ "prices": [1.0], "model": "sma" })).is_ok());
+        let err = MarketPredictionInput::from_value(&json!({ "prices": [1.0, 2.0], "model": "arima" })).unwrap_err();
+        assert_eq!(err.field(), "prices");
+        
+        let err = MarketPredictionInput::from_value(&json!({ "prices": [1.0, 0.0], "model": "linear_regression" })).unwrap_err();
+        assert_eq!(err.field(), "prices");
+        
+        for horizon in [json!(0), json!(101), json!(1.5)] {
+            let err = MarketPredictionInput::from_value(&json!({ "prices": [1.0, 2.0], "horizon": horizon })).unwrap_err();
+            assert_eq!(err.field(), "horizon");
+        }
+        
+        let err = RiskScoringInput::from_value(&json!({
+            "contract_address": "not-an-address",
+            "code_complexity": 0.7,
+            "audited": true,
+            "tx_volume": 50.0
+        }))
+        .unwrap_err();
+        assert_eq!(err.field(), "contract_address");
+        
+        let err = RiskScoringInput::from_value(&json!({
+            "contract_address": "0x1234567890123456789012345678901234567890",
+            "code_complexity": 1.5,
+            "audited": true,
+            "tx_volume": 50.0
+        }))
+        .unwrap_err();
+        assert_eq!(err.field(), "code_complexity");
+    }
+
+    #[test]
+    fn test_technical_indicators_payload() {
+        let prices: Vec<f64> = (1..=30).map(f64::from).collect();
+        let input = TechnicalIndicatorsInput::from_value(&json!({
+            "prices": prices,
+            "indicators": [
+                { "name": "rsi" },
+                { "name": "bollinger", "period": 10 },
+                { "name": "volatility", "period": 5, "periods_per_year": 365 }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(
+            input.indicators,
+            vec![
+                Indicator::Rsi { period: 14 },
+                Indicator::Bollinger { period: 10, std_dev: 2.0 },
+                Indicator::Volatility { period: 5, periods_per_year: Some(365) },
+            ]
+        );
+        assert_eq!(TechnicalIndicatorsInput::from_value(&input.to_value()).unwrap(), input);
+        
+        let candles = json!([candle(0, 1.0), candle(60, 2.0), candle(120, 3.0)]);
+        let input = TechnicalIndicatorsInput::from_value(&json!({
+            "candles": candles,
+            "indicators": [{ "name": "atr", "period": 3 }, { "name": "vwap" }]
+        }))
+        .unwrap();
+        assert_eq!(input.closes(), vec![1.0, 2.0, 3.0]);
+        
+        let indicators = |indicators: Value| {
+            TechnicalIndicatorsInput::from_value(&json!({ "prices": prices, "indicators": indicators })).unwrap_err()
+        };
+        
+        let err = indicators(json!([{ "name": "stochastic" }]));
+        assert_eq!(err.field(), "indicators[0].name");
+        assert!(err.to_string().contains("expected one of rsi, macd, bollinger, atr, vwap, volatility"));
+        assert_eq!(indicators(json!([{ "name": "rsi", "period": 0 }])).field(), "indicators[0].period");
+        assert_eq!(indicators(json!([{ "name": "macd", "fast": 26 }])).field(), "indicators[0].fast");
+        assert_eq!(indicators(json!([{ "name": "bollinger", "std_dev": -1 }])).field(), "indicators[0].std_dev");
+        assert_eq!(indicators(json!([{ "name": "volatility", "period": 1 }])).field(), "indicators[0].period");
+        assert_eq!(indicators(json!([])).field(), "indicators");
+        let err = indicators(json!(vec![json!({ "name": "rsi" }); MAX_INDICATORS + 1]));
+        assert_eq!(err.to_string(), "field `indicators` is invalid: at most 32 indicators are allowed");
+        let err = TechnicalIndicatorsInput::from_value(&json!({
+            "prices": vec![1.0; MAX_CANDLES as usize + 1],
+            "indicators": [{ "name": "rsi" }]
+        }))
+        .unwrap_err();
+        assert_eq!(err.field(), "prices");
+        
+        // Windows must fit in the series: MACD needs 26 + 9 - 1 prices
+        let err = indicators(json!([{ "name": "rsi" }, { "name": "macd" }]));
+        assert_eq!(err.to_string(), "field `indicators[1]` is invalid: macd needs at least 34 prices");
+        let err = indicators(json!([{ "name": "atr", "period": 3 }]));
+        assert_eq!(err.field(), "indicators[0]");
+        
+        // Windows too large to count are refused rather than wrapping around
+        let err = indicators(json!([{ "name": "rsi", "period": u64::MAX }]));
+        assert_eq!(err.to_string(), "field `indicators[0]` is invalid: rsi window is too large");
+        let err = indicators(json!([{ "name": "macd", "fast": 12, "slow": u64::MAX - 1, "signal": 9 }]));
+        assert_eq!(err.field(), "indicators[0]");
+        
+        let err = TechnicalIndicatorsInput::from_value(&json!({
+            "candles": [candle(60, 1.0), candle(0, 2.0)],
+            "indicators": [{ "name": "vwap" }]
+        }))
+        .unwrap_err();
+        assert_eq!(err.field(), "candles[1].timestamp");
+        
+        let err = TechnicalIndicatorsInput::from_value(&json!({
+            "prices": [1.0, -1.0, 2.0],
+            "indicators": [{ "name": "volatility", "period": 2 }]
+        }))
+        .unwrap_err();
+        assert_eq!(err.field(), "prices");
+        
+        let err = TechnicalIndicatorsInput::from_value(&json!({ "prices": [1.0], "candles": candles, "indicators": [] }))
+            .unwrap_err();
+        assert_eq!(err.field(), "prices");
+    }
+}
@@ -0,0 +1,174 @@
+#[cfg(test)]
+mod tests {
+    use ethers::providers::{Http, Middleware, Provider};
+    use ethers::types::Address;
+    use serde_json::{json, Value};
+    use shared::metrics::{Counter, Gauge, Histogram, Registry};
+    use shared::telemetry::{Health, Telemetry};
+    use std::str::FromStr;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    // JSON-RPC node at block 100 holding 1.5 of the native token for everyone
+    async fn spawn_node() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    let call: Value = loop {
+                        let n = socket.read(&mut buf).await.unwrap();
+                        request.extend_from_slice(&buf[..n]);
+                        let Some(pos) = request.windows(4).position(|w| w == b"\r\n\r\n") else {
+                            continue;
+                        };
+                        if let Ok(call) = serde_json::from_slice(&request[pos + 4..]) {
+                            break call;
+                        }
+                    };
+                    
+                    let result = match call["method"].as_str().unwrap() {
+                        "eth_blockNumber" => json!("0x64"),
+                        "eth_getBalance" => json!("0x14d1120d7b160000"),
+                        _ => Value::Null,
+                    };
+                    let response = json!({ "jsonrpc": "2.0", "id": call["id"], "result": result }).to_string();
+                    let header = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        response.len()
+                    );
+                    socket.write_all(header.as_bytes()).await.unwrap();
+                    socket.write_all(response.as_bytes()).await.unwrap();
+                });
+            }
+        });
+        
+        format!("http://{}", addr)
+    }
+
+    async fn get(addr: std::net::SocketAddr, path: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", path);
+        stream.write_all(request.as_bytes()).await.unwrap();
+        
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = response.split_once("\r\n\r\n").unwrap().1.to_string();
+        (status, body)
+    }
+
+    #[test]
+    fn test_text_format() {
+        let registry = Registry::new();
+        let claimed = registry.register(Counter::new("tasks_claimed_total", "Tasks claimed", &["task_type"]));
+        let head = registry.register(Gauge::new("head_block", "Chain head", &[]));
+        let latency = registry.register(Histogram::new("module_seconds", "Run time", &["task_type"], &[0.1, 1.0]));
+        
+        claimed.inc(&["risk_scoring"]);
+        claimed.inc(&["risk_scoring"]);
+        claimed.inc(&["say \"hi\""]);
+        head.set(&[], 42.0);
+        latency.observe(&["risk_scoring"], 0.05);
+        latency.observe(&["risk_scoring"], 0.5);
+        latency.observe(&["risk_scoring"], 5.0);
+        
+        assert_eq!(claimed.get(&["risk_scoring"]), 2.0);
+        assert_eq!(latency.count(&["risk_scoring"]), 3);
+        
+        let text = registry.encode();
+        for line in [
+            "# TYPE tasks_claimed_total counter",
+            "tasks_claimed_total{task_type=\"risk_scoring\"} 2",
+            "tasks_claimed_total{task_type=\"say \\\"hi\\\"\"} 1",
+            "head_block 42",
+            "# TYPE module_seconds histogram",
+            "module_seconds_bucket{task_type=\"risk_scoring\",le=\"0.1\"} 1",
+            "module_seconds_bucket{task_type=\"risk_scoring\",le=\"1\"} 2",
+            "module_seconds_bucket{task_type=\"risk_scoring\",le=\"+Inf\"} 3",
+            "module_seconds_sum{task_type=\"risk_scoring\"} 5.55",
+            "module_seconds_count{task_type=\"risk_scoring\"} 3",
+        ] {
+            assert!(text.lines().any(|l| l == line), "missing `{}` in\n{}", line, text);
+        }
+    }
+
+    #[test]
+    fn test_health() {
+        let health = Health::new(10, Duration::from_secs(30));
+        assert!(!health.report().healthy());
+        
+        // Connected, but nothing processed yet
+        health.rpc_ok(100);
+        assert!(health.report().healthy());
+        assert!(!health.report().ready());
+        
+        health.processed(95);
+        let report = health.report();
+        assert_eq!(report.lag_blocks, Some(5));
+        assert!(report.ready());
+        
+        // Falling behind the head
+        health.rpc_ok(120);
+        assert!(health.report().healthy());
+        assert!(!health.report().ready());
+        
+        health.processed(120);
+        health.processed(80);
+        assert_eq!(health.report().processed_block, Some(120));
+        
+        health.rpc_failed("connection refused");
+        let report = health.report();
+        assert!(!report.healthy() && !report.ready());
+        assert_eq!(report.error.as_deref(), Some("connection refused"));
+        
+        // No successful probe for too long
+        let health = Health::new(10, Duration::ZERO);
+        health.rpc_ok(1);
+        health.processed(1);
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(!health.report().healthy());
+    }
+
+    #[tokio::test]
+    async fn test_endpoints() {
+        let telemetry = Arc::new(Telemetry::new(10));
+        let addr = telemetry.clone().serve("127.0.0.1:0".parse().unwrap()).unwrap();
+        
+        let (status, body) = get(addr, "/healthz").await;
+        assert_eq!(status, 503);
+        assert_eq!(serde_json::from_str::<Value>(&body).unwrap()["rpc_connected"], false);
+        
+        let account = Address::repeat_byte(7);
+        let node = Provider::new(telemetry.metered(Http::from_str(&spawn_node().await).unwrap()));
+        telemetry.probe(&node, account).await;
+        assert_eq!(get(addr, "/healthz").await.0, 200);
+        assert_eq!(get(addr, "/readyz").await.0, 503);
+        
+        telemetry.processed(95);
+        let (status, body) = get(addr, "/readyz").await;
+        assert_eq!(status, 200);
+        assert_eq!(serde_json::from_str::<Value>(&body).unwrap()["lag_blocks"], 5);
+        
+        // Failed requests are counted per method
+        let down = Provider::new(telemetry.metered(Http::from_str("http://127.0.0.1:1").unwrap()));
+        assert!(down.get_block_number().await.is_err());
+        telemetry.probe(&down, account).await;
+        assert_eq!(get(addr, "/healthz").await.0, 503);
+        
+        let (status, metrics) = get(addr, "/metrics").await;
+        assert_eq!(status, 200);
+        assert!(metrics.contains("polyneurons_rpc_errors_total{method=\"eth_blockNumber\",kind=\"transport\"} 2"));
+        assert!(metrics.contains("polyneurons_chain_head_block 100"));
+        assert!(metrics.contains("polyneurons_last_processed_block 95"));
+        assert!(metrics.contains(&format!("polyneurons_wallet_balance{{account=\"{:?}\"}} 1.5", account)));
+        
+        assert_eq!(get(addr, "/nope").await.0, 404);
+    }
+}
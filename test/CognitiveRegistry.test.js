@@ -105,4 +105,52 @@ describe("CognitiveRegistry", function () {
             expect(nodeData.tasksCompleted).to.equal(1);
         });
     });
+
+    describe("Operators", function () {
+        const reward = ethers.parseEther("1");
+        const dataHash = ethers.keccak256(ethers.toUtf8Bytes("test data"));
+        const resultHash = ethers.keccak256(ethers.toUtf8Bytes("result"));
+
+        beforeEach(async function () {
+            await registry.connect(node1).registerCognitiveNode("node-1", { value: ethers.parseEther("100") });
+
+            const deadline = Math.floor(Date.now() / 1000) + 3600;
+            await registry.connect(requester).createReasoningTask("market_prediction", dataHash, deadline, { value: reward });
+        });
+
+        it("Should let an operator work for the node and pay the node", async function () {
+            await expect(registry.connect(node1).setOperator(node2.address))
+                .to.emit(registry, "OperatorSet")
+                .withArgs(node1.address, node2.address);
+            expect(await registry.nodeOf(node2.address)).to.equal(node1.address);
+
+            await expect(registry.connect(node2).assignTask(1))
+                .to.emit(registry, "TaskAssigned")
+                .withArgs(1, node1.address);
+
+            const balanceBefore = await ethers.provider.getBalance(node1.address);
+            await registry.connect(node2).submitTaskResult(1, resultHash);
+            const balanceAfter = await ethers.provider.getBalance(node1.address);
+            expect(balanceAfter - balanceBefore).to.equal(reward);
+        });
+
+        it("Should stop accepting a removed operator", async function () {
+            await registry.connect(node1).setOperator(node2.address);
+            await registry.connect(node1).setOperator(ethers.ZeroAddress);
+
+            expect(await registry.nodeOf(node2.address)).to.equal(node2.address);
+            await expect(registry.connect(node2).assignTask(1)).to.be.revertedWith("Not registered");
+        });
+
+        it("Should reject operators that are nodes or already in use", async function () {
+            await registry.connect(node2).registerCognitiveNode("node-2", { value: ethers.parseEther("100") });
+            await expect(registry.connect(node1).setOperator(node2.address)).to.be.revertedWith("Operator is a node");
+
+            await registry.connect(node1).setOperator(requester.address);
+            await expect(registry.connect(node2).setOperator(requester.address)).to.be.revertedWith("Operator in use");
+            await expect(
+                registry.connect(requester).registerCognitiveNode("node-3", { value: ethers.parseEther("100") })
+            ).to.be.revertedWith("Address is an operator");
+        });
+    });
 });
@@ -0,0 +1,4 @@
+pub mod metrics;
+pub mod plugin;
+pub mod por;
+pub mod verify;
@@ -1,19 +1,19 @@
 use anyhow::Result;
+use clap::Parser;
 use tracing::info;
-use tracing_subscriber;
 
-mod plugin;
-mod por;
-
-use plugin::ValidatorPlugin;
+use shared::config::{Cli, Role};
+use validator_plugin::plugin::ValidatorPlugin;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     tracing_subscriber::fmt::init();
     
+    let config = Cli::parse().load(Role::Validator)?;
+    
     info!("🔌 Starting PolyNeurons Validator Plugin...");
     
-    let plugin = ValidatorPlugin::new().await?;
+    let plugin = ValidatorPlugin::new(&config).await?;
     
     info!("✅ Validator Plugin initialized");
     info!("🔗 Connected to Polygon network");
@@ -0,0 +1,32 @@
+use cognitive_engine::metrics::module_latency;
+use shared::metrics::{Counter, Histogram};
+use shared::telemetry::Telemetry;
+use std::sync::Arc;
+
+pub struct ValidatorMetrics {
+    pub telemetry: Arc<Telemetry>,
+    pub proof_submissions: Arc<Counter>,
+    pub votes: Arc<Counter>,
+    pub module_latency: Arc<Histogram>,
+}
+
+impl ValidatorMetrics {
+    pub fn new(telemetry: Arc<Telemetry>) -> Self {
+        let registry = telemetry.registry();
+        
+        Self {
+            proof_submissions: registry.register(Counter::new(
+                "polyneurons_proof_submissions_total",
+                "Proofs of reasoning sent for assigned tasks, by outcome",
+                &["task_type", "outcome"],
+            )),
+            votes: registry.register(Counter::new(
+                "polyneurons_votes_cast_total",
+                "Votes cast on peer proofs",
+                &["vote"],
+            )),
+            module_latency: registry.register(module_latency()),
+            telemetry,
+        }
+    }
+}
@@ -1,45 +1,136 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use ethers::prelude::*;
-use std::sync::Arc;
-use tokio::time::{interval, Duration};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::time::{interval, Duration, Instant};
 use tracing::{info, warn};
 
-use crate::por::ProofOfReasoningValidator;
+use crate::metrics::ValidatorMetrics;
+use crate::por::{ProofError, ProofOfReasoningValidator};
+use crate::verify::{Verdict, VerificationPolicy};
+use cognitive_engine::datastore::DataStoreChain;
+use cognitive_engine::metrics::task_type_label;
+use cognitive_engine::registry::RegistryClient;
+use cognitive_engine::signer::NodeSigner;
+use cognitive_engine::tasks::TaskProcessor;
+use shared::config::NodeConfig;
+use shared::hashing::{to_hex, Hash32};
+use shared::telemetry::{MeteredProvider, Telemetry};
+use shared::types::ReasoningTask;
+
+pub type PluginClient = SignerMiddleware<MeteredProvider, NodeSigner>;
+
+// Attempts at proving an assigned task before it is given up on. Retries
+// wait one task interval, doubling after every failure.
+const MAX_PROOF_ATTEMPTS: u32 = 5;
+
+// An assigned task whose proof failed with an error that may go away
+struct Retry {
+    attempts: u32,
+    due: Instant,
+}
 
 pub struct ValidatorPlugin {
-    _provider: Arc<Provider<Http>>,
-    _wallet: LocalWallet,
-    por_validator: ProofOfReasoningValidator,
-    _node_address: Address,
+    provider: Arc<MeteredProvider>,
+    registry: RegistryClient<MeteredProvider>,
+    por_validator: ProofOfReasoningValidator<PluginClient>,
+    // Registered node tasks are assigned to
+    node_address: Address,
+    // Key proofs are submitted with; the node's operator if it has one
+    signer_address: Address,
+    block_interval: Duration,
+    task_interval: Duration,
+    next_block: AtomicU64,
+    next_proof_block: AtomicU64,
+    // Assigned tasks whose proof failed, retried once due
+    failed_tasks: Mutex<BTreeMap<u64, Retry>>,
+    // Peer proofs still to be voted on; they stay queued until the vote is
+    // cast or there is nothing left to vote on
+    unverified: Mutex<HashSet<Hash32>>,
+    // Tasks the registry assigned to each node, up to `next_assignment_block`
+    assignments: Mutex<HashMap<Address, Vec<ReasoningTask>>>,
+    next_assignment_block: AtomicU64,
+    metrics: ValidatorMetrics,
+    metrics_addr: Option<SocketAddr>,
 }
 
 impl ValidatorPlugin {
-    pub async fn new() -> Result<Self> {
-        let rpc_url = std::env::var("POLYGON_RPC_URL")
-            .unwrap_or_else(|_| "http://localhost:8545".to_string());
+    pub async fn new(config: &NodeConfig) -> Result<Self> {
+        let telemetry = Arc::new(Telemetry::new(config.validator.max_lag_blocks));
+        let metrics = ValidatorMetrics::new(telemetry.clone());
         
-        let provider = Provider::<Http>::try_from(rpc_url)?;
+        let provider = Provider::new(telemetry.metered(Http::from_str(&config.chain.rpc_url)?));
         let provider = Arc::new(provider);
         
-        let private_key = std::env::var("PRIVATE_KEY")
-            .expect("PRIVATE_KEY must be set");
-        let wallet: LocalWallet = private_key.parse()?;
+        let chain_id = config.chain.check_chain_id(provider.get_chainid().await?.as_u64())?;
+        let signer = NodeSigner::from_config(&config.signer, chain_id).await?;
+        let signer_address = signer.address();
+        info!("🔑 Signing as {:?} with {}", signer_address, signer.describe());
+        
+        let client = Arc::new(SignerMiddleware::new((*provider).clone(), signer));
         
-        let node_address = wallet.address();
+        let registry_address = config
+            .chain
+            .registry_address
+            .ok_or_else(|| anyhow!("chain.registry_address must be set"))?;
+        let registry = RegistryClient::new(registry_address, provider.clone());
+        let node_address = registry.node_of(signer_address).await?;
         
-        let por_validator = ProofOfReasoningValidator::new();
+        let start_block = config.chain.start_block;
+        
+        let por_address = config
+            .chain
+            .por_address
+            .ok_or_else(|| anyhow!("chain.por_address must be set"))?;
+        
+        let policy = match &config.validator.tolerance_file {
+            Some(path) => VerificationPolicy::from_file(path)?,
+            None => VerificationPolicy::default(),
+        };
+        
+        let por_start_block = config.chain.por_start_block.unwrap_or(start_block);
+        
+        let data_store = Arc::new(DataStoreChain::from_config(&config.data));
+        let processor = TaskProcessor::new().with_latency(metrics.module_latency.clone());
+        let por_validator = ProofOfReasoningValidator::new(por_address, client, data_store)
+            .with_processor(processor)
+            .with_policy(policy)
+            .with_task_timeout(Duration::from_secs(config.validator.max_task_timeout_secs))
+            .with_confirmations(config.chain.confirmations)
+            .with_submitted_path(&config.validator.proofs_path)?;
         
         Ok(Self {
-            _provider: provider,
-            _wallet: wallet,
+            provider,
+            registry,
             por_validator,
-            _node_address: node_address,
+            node_address,
+            signer_address,
+            block_interval: Duration::from_secs(config.validator.block_interval_secs),
+            task_interval: Duration::from_secs(config.validator.task_interval_secs),
+            next_block: AtomicU64::new(start_block),
+            next_proof_block: AtomicU64::new(por_start_block),
+            failed_tasks: Mutex::new(BTreeMap::new()),
+            unverified: Mutex::new(HashSet::new()),
+            assignments: Mutex::new(HashMap::new()),
+            next_assignment_block: AtomicU64::new(start_block),
+            metrics,
+            metrics_addr: config.validator.metrics_addr,
         })
     }
     
     pub async fn run(&self) -> Result<()> {
-        let mut block_ticker = interval(Duration::from_secs(2));
-        let mut reasoning_ticker = interval(Duration::from_secs(15));
+        let telemetry = self.metrics.telemetry.clone();
+        if let Some(addr) = self.metrics_addr {
+            let addr = telemetry.clone().serve(addr)?;
+            info!("📈 Serving /metrics, /healthz and /readyz on {}", addr);
+        }
+        telemetry.spawn_probe(self.provider.clone(), self.signer_address);
+        
+        let mut block_ticker = interval(self.block_interval);
+        let mut reasoning_ticker = interval(self.task_interval);
         
         loop {
             tokio::select! {
@@ -52,13 +143,19 @@ impl ValidatorPlugin {
                     if let Err(e) = self.process_reasoning_tasks().await {
                         warn!("Reasoning task error: {}", e);
                     }
+                    
+                    if let Err(e) = self.validate_peer_proofs().await {
+                        warn!("Peer proof validation error: {}", e);
+                    }
+                    
+                    self.report_progress();
                 }
             }
         }
     }
     
     async fn validate_blocks(&self) -> Result<()> {
-        let block_number = self._provider.get_block_number().await?;
+        let block_number = self.provider.get_block_number().await?;
         info!("📦 Validating block: {}", block_number);
         
         // Standard block validation
@@ -70,22 +167,212 @@ impl ValidatorPlugin {
     async fn process_reasoning_tasks(&self) -> Result<()> {
         info!("🧠 Processing cognitive tasks...");
         
-        // Check for assigned reasoning tasks
-        let tasks = self.fetch_assigned_tasks().await?;
+        for (task_id, retry) in self.due_retries() {
+            self.prove(task_id, retry.attempts).await;
+        }
         
-        for task in tasks {
-            info!("🎯 Executing reasoning task: {}", task);
+        // Tasks assigned to this node, one log range at a time. The cursor
+        // passes a range once each of its tasks is proven, queued to retry
+        // or given up on.
+        let head = self.provider.get_block_number().await?.as_u64();
+        let mut start = self.next_block.load(Ordering::SeqCst);
+        
+        while start <= head {
+            let end = head.min(start + self.registry.max_block_range() - 1);
             
-            // Execute cognitive task
-            // Submit proof of reasoning
-            self.por_validator.submit_proof(&task).await?;
+            let assigned = self.registry.task_assignments(start, end, Some(self.node_address)).await?;
+            for (task_id, _) in assigned {
+                self.prove(task_id, 0).await;
+            }
+            
+            self.next_block.store(end + 1, Ordering::SeqCst);
+            start = end + 1;
         }
         
         Ok(())
     }
     
-    async fn fetch_assigned_tasks(&self) -> Result<Vec<String>> {
-        // Query smart contract for tasks assigned to this validator
-        Ok(vec![])
+    // Takes the retries whose backoff has passed off the queue
+    fn due_retries(&self) -> BTreeMap<u64, Retry> {
+        let now = Instant::now();
+        let mut failed = self.failed_tasks.lock().unwrap();
+        
+        let (due, waiting) = std::mem::take(&mut *failed)
+            .into_iter()
+            .partition(|(_, retry)| retry.due <= now);
+        *failed = waiting;
+        due
+    }
+    
+    // Proves a task that already failed `attempts` times. Errors that a
+    // retry cannot fix drop the task; others back off until the attempts
+    // run out.
+    async fn prove(&self, task_id: u64, attempts: u32) {
+        let e = match self.prove_task(task_id).await {
+            Ok(()) => return,
+            Err(e) => e,
+        };
+        
+        let attempts = attempts + 1;
+        if e.is_permanent() {
+            warn!("🚫 Giving up on task {}: {}", task_id, e);
+            return;
+        }
+        if attempts >= MAX_PROOF_ATTEMPTS {
+            warn!("🚫 Giving up on task {} after {} attempts: {}", task_id, attempts, e);
+            return;
+        }
+        
+        let backoff = self.task_interval * 2u32.pow(attempts - 1);
+        warn!("❌ Proof for task {} failed, retrying in {}s: {}", task_id, backoff.as_secs(), e);
+        self.failed_tasks.lock().unwrap().insert(
+            task_id,
+            Retry {
+                attempts,
+                due: Instant::now() + backoff,
+            },
+        );
+    }
+    
+    async fn prove_task(&self, task_id: u64) -> Result<(), ProofError> {
+        let task = self.registry.get_task(task_id).await?;
+        if task.completed {
+            return Ok(());
+        }
+        
+        let task = task.into_reasoning_task();
+        info!("🎯 Executing reasoning task: {} (type: {})", task.task_id, task.task_type);
+        
+        let task_type = task_type_label(self.por_validator.processor().modules(), &task.task_type).to_string();
+        match self.por_validator.submit_proof(task).await {
+            Ok(_) => {
+                self.metrics.proof_submissions.inc(&[&task_type, "submitted"]);
+                Ok(())
+            }
+            Err(e) => {
+                self.metrics.proof_submissions.inc(&[&task_type, "failed"]);
+                Err(e)
+            }
+        }
+    }
+    
+    // Every scan has handled each block before the lowest of their cursors
+    fn report_progress(&self) {
+        let next = self
+            .next_block
+            .load(Ordering::SeqCst)
+            .min(self.next_proof_block.load(Ordering::SeqCst))
+            .min(self.next_assignment_block.load(Ordering::SeqCst));
+        if let Some(last) = next.checked_sub(1) {
+            self.metrics.telemetry.processed(last);
+        }
+    }
+    
+    async fn validate_peer_proofs(&self) -> Result<()> {
+        self.fetch_submitted_proofs().await?;
+        self.index_assignments().await?;
+        
+        let proof_ids: Vec<Hash32> = self.unverified.lock().unwrap().iter().copied().collect();
+        for proof_id in proof_ids {
+            match self.validate_peer_proof(proof_id).await {
+                Ok(true) => {
+                    self.unverified.lock().unwrap().remove(&proof_id);
+                }
+                Ok(false) => {}
+                Err(e) => warn!("❌ Could not validate proof {}: {}", to_hex(&proof_id), e),
+            }
+        }
+        
+        Ok(())
+    }
+    
+    // Votes on a peer proof; false if it has to be checked again later
+    async fn validate_peer_proof(&self, proof_id: Hash32) -> Result<bool> {
+        let Some(proof) = self.por_validator.peer_proof(proof_id).await? else {
+            return Ok(true);
+        };
+        
+        let candidates = self.tasks_assigned_to(proof.prover).await?;
+        
+        match self.por_validator.validate_peer_proof(&proof, candidates).await? {
+            Verdict::Unverifiable(reason) => {
+                info!("⏸️  Proof {} deferred: {}", to_hex(&proof_id), reason);
+                Ok(false)
+            }
+            verdict => {
+                if let Some(approved) = verdict.approved() {
+                    self.metrics.votes.inc(&[if approved { "approve" } else { "reject" }]);
+                }
+                Ok(true)
+            }
+        }
+    }
+    
+    // Queues peer proofs submitted since the last scan, one log range at a
+    // time. A range is queued before the cursor moves past it.
+    async fn fetch_submitted_proofs(&self) -> Result<()> {
+        let head = self.provider.get_block_number().await?.as_u64();
+        let mut start = self.next_proof_block.load(Ordering::SeqCst);
+        
+        while start <= head {
+            let end = head.min(start + self.registry.max_block_range() - 1);
+            
+            let submitted = self
+                .por_validator
+                .contract()
+                .proof_submitted_filter()
+                .from_block(start)
+                .to_block(end)
+                .query()
+                .await
+                .map_err(|e| anyhow!("eth_getLogs failed for blocks {}-{}: {}", start, end, e))?;
+            
+            self.unverified.lock().unwrap().extend(
+                submitted
+                    .into_iter()
+                    .filter(|event| event.prover != self.signer_address)
+                    .map(|event| event.proof_id),
+            );
+            
+            self.next_proof_block.store(end + 1, Ordering::SeqCst);
+            start = end + 1;
+        }
+        
+        Ok(())
+    }
+    
+    // Catches `assignments` up with the chain, one log range at a time.
+    // Assignments are final, so every task is only read once.
+    async fn index_assignments(&self) -> Result<()> {
+        let head = self.provider.get_block_number().await?.as_u64();
+        let mut start = self.next_assignment_block.load(Ordering::SeqCst);
+        
+        while start <= head {
+            let end = head.min(start + self.registry.max_block_range() - 1);
+            
+            let mut assigned = Vec::new();
+            for (task_id, node) in self.registry.task_assignments(start, end, None).await? {
+                assigned.push((node, self.registry.get_task(task_id).await?.into_reasoning_task()));
+            }
+            
+            let mut assignments = self.assignments.lock().unwrap();
+            for (node, task) in assigned {
+                assignments.entry(node).or_default().push(task);
+            }
+            drop(assignments);
+            
+            self.next_assignment_block.store(end + 1, Ordering::SeqCst);
+            start = end + 1;
+        }
+        
+        Ok(())
+    }
+    
+    // Every task the registry assigned to the node `prover` works for; a
+    // proof's input hash has to commit to one of them
+    async fn tasks_assigned_to(&self, prover: Address) -> Result<Vec<ReasoningTask>> {
+        let node = self.registry.node_of(prover).await?;
+        
+        Ok(self.assignments.lock().unwrap().get(&node).cloned().unwrap_or_default())
     }
 }
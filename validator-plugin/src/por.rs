@@ -1,70 +1,404 @@
-use anyhow::Result;
-use sha2::{Sha256, Digest};
-use tracing::info;
+use anyhow::{anyhow, Result};
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use thiserror::Error;
+use tracing::{debug, info, warn};
 
-pub struct ProofOfReasoningValidator;
+use crate::verify::{Verdict, VerificationPolicy};
+use bindings::proof_of_reasoning::{ProofOfReasoning, ProofSubmittedFilter};
+use cognitive_engine::cursor::write_atomic;
+use cognitive_engine::datastore::{resolve_task_data, DataStoreError, TaskDataStore};
+use cognitive_engine::tasks::{TaskProcessor, TimedOut};
+use shared::hashing::{committed_result, proof_input_hash, result_hash, to_hex, Hash32};
+use shared::types::{ReasoningResult, ReasoningTask};
 
-impl ProofOfReasoningValidator {
-    pub fn new() -> Self {
-        Self
+const DEFAULT_TASK_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Error)]
+pub enum ProofError {
+    // Retrying cannot change the outcome: the payload is invalid, no module
+    // handles the task type, the run fails on its input or the contract
+    // refused the proof
+    #[error("{0}")]
+    Rejected(String),
+    // RPC or data store trouble; a later attempt may succeed
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl ProofError {
+    pub fn is_permanent(&self) -> bool {
+        matches!(self, Self::Rejected(_))
+    }
+}
+
+// A reasoning run ready to be committed on chain
+#[derive(Debug, Clone)]
+pub struct PreparedProof {
+    pub task_id: u64,
+    pub input_hash: Hash32,
+    pub output_hash: Hash32,
+    // Work units of the run, which validators reproduce by re-executing it
+    pub computation_cost: u64,
+    pub result: ReasoningResult,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmittedProof {
+    pub proof_id: Hash32,
+    pub task_id: u64,
+    pub input_hash: Hash32,
+    pub output_hash: Hash32,
+    pub computation_cost: u64,
+    pub tx_hash: TxHash,
+}
+
+// Another prover's proof that still needs our vote
+#[derive(Debug, Clone)]
+pub struct PeerProof {
+    pub proof_id: Hash32,
+    pub input_hash: Hash32,
+    pub output_hash: Hash32,
+    pub computation_cost: u64,
+    pub prover: Address,
+}
+
+pub struct ProofOfReasoningValidator<M> {
+    contract: ProofOfReasoning<M>,
+    processor: TaskProcessor,
+    // Upper bound on a single reasoning run; payloads come from requesters
+    // and proofs from peers, neither of them trusted
+    task_timeout: Duration,
+    data_store: Arc<dyn TaskDataStore>,
+    policy: VerificationPolicy,
+    confirmations: usize,
+    submitted: Mutex<HashMap<u64, SubmittedProof>>,
+    // Where `submitted` is kept across restarts, if anywhere
+    submitted_path: Option<PathBuf>,
+}
+
+impl<M: Middleware + 'static> ProofOfReasoningValidator<M> {
+    pub fn new(address: Address, client: Arc<M>, data_store: Arc<dyn TaskDataStore>) -> Self {
+        Self {
+            contract: ProofOfReasoning::new(address, client),
+            processor: TaskProcessor::new(),
+            task_timeout: DEFAULT_TASK_TIMEOUT,
+            data_store,
+            policy: VerificationPolicy::default(),
+            confirmations: 1,
+            submitted: Mutex::new(HashMap::new()),
+            submitted_path: None,
+        }
     }
     
-    pub async fn submit_proof(&self, task_id: &str) -> Result<()> {
-        info!("📝 Generating Proof of Reasoning for task: {}", task_id);
+    // Keeps the proofs submitted so far in `path`, starting from the ones a
+    // previous run left there
+    pub fn with_submitted_path(mut self, path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        if path.exists() {
+            *self.submitted.get_mut().unwrap() = serde_json::from_slice(&std::fs::read(&path)?)?;
+        }
         
-        // Generate input hash
-        let input_hash = self.hash_input(task_id);
+        self.submitted_path = Some(path);
+        Ok(self)
+    }
+    
+    pub fn with_processor(mut self, processor: TaskProcessor) -> Self {
+        self.processor = processor;
+        self
+    }
+    
+    pub fn with_task_timeout(mut self, task_timeout: Duration) -> Self {
+        self.task_timeout = task_timeout;
+        self
+    }
+    
+    pub fn with_policy(mut self, policy: VerificationPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+    
+    pub fn with_confirmations(mut self, confirmations: usize) -> Self {
+        self.confirmations = confirmations;
+        self
+    }
+    
+    pub fn contract(&self) -> &ProofOfReasoning<M> {
+        &self.contract
+    }
+    
+    pub fn processor(&self) -> &TaskProcessor {
+        &self.processor
+    }
+    
+    // Proof recorded for a task by this validator, if any
+    pub fn submitted(&self, task_id: u64) -> Option<SubmittedProof> {
+        self.submitted.lock().unwrap().get(&task_id).cloned()
+    }
+    
+    pub async fn submit_proof(&self, task: ReasoningTask) -> Result<SubmittedProof, ProofError> {
+        if let Some(existing) = self.submitted(task.task_id) {
+            info!("⏭️  Proof for task {} already submitted ({})", task.task_id, to_hex(&existing.proof_id));
+            return Ok(existing);
+        }
         
-        // Simulate reasoning computation
-        let output = self.compute_reasoning(task_id).await?;
+        info!("📝 Generating Proof of Reasoning for task: {}", task.task_id);
         
-        // Generate output hash
-        let output_hash = self.hash_output(&output);
+        let proof = self.prepare(task).await?;
         
         info!("✅ Proof generated:");
-        info!("   Input Hash:  {}", input_hash);
-        info!("   Output Hash: {}", output_hash);
+        info!("   Input Hash:  {}", to_hex(&proof.input_hash));
+        info!("   Output Hash: {}", to_hex(&proof.output_hash));
+        info!("   Cost:        {} work units ({}ms)", proof.computation_cost, proof.result.computation_time_ms);
+        
+        // Published under the output hash so validators can apply tolerance
+        // rules when their own run does not reproduce the hash exactly
+        if let Err(e) = self.data_store.put(&committed_result(&proof.result)).await {
+            warn!("Could not publish output of task {}: {}", proof.task_id, e);
+        }
         
-        // Submit to blockchain
-        self.submit_to_chain(&input_hash, &output_hash).await?;
+        let submitted = self.submit_to_chain(&proof).await?;
+        
+        let snapshot = {
+            let mut proofs = self.submitted.lock().unwrap();
+            proofs.insert(submitted.task_id, submitted.clone());
+            serde_json::to_vec(&*proofs).map_err(anyhow::Error::from)?
+        };
+        // The proof is on chain whatever happens here
+        if let Err(e) = self.save_submitted(snapshot).await {
+            warn!("Could not record proof for task {}, a restart may submit it again: {}", proof.task_id, e);
+        }
+        
+        Ok(submitted)
+    }
+    
+    async fn save_submitted(&self, snapshot: Vec<u8>) -> Result<()> {
+        let Some(path) = self.submitted_path.clone() else {
+            return Ok(());
+        };
         
+        tokio::task::spawn_blocking(move || write_atomic(&path, &snapshot)).await??;
         Ok(())
     }
     
-    fn hash_input(&self, input: &str) -> String {
-        let mut hasher = Sha256::new();
-        hasher.update(input.as_bytes());
-        format!("0x{}", hex::encode(hasher.finalize()))
+    // Resolves the payload, runs the matching reasoning module and hashes
+    // both sides of the computation
+    pub async fn prepare(&self, mut task: ReasoningTask) -> Result<PreparedProof, ProofError> {
+        resolve_task_data(self.data_store.as_ref(), &mut task)
+            .await
+            .map_err(|e| match e.is_transient() {
+                true => ProofError::Other(e.into()),
+                false => ProofError::Rejected(format!("task data unusable: {}", e)),
+            })?;
+        
+        let input_hash = proof_input_hash(&task).map_err(|e| ProofError::Rejected(e.to_string()))?;
+        
+        // Modules are deterministic, so a run that fails or runs out of
+        // time does so every time
+        let result = self
+            .processor
+            .process_with_timeout(&task, self.task_timeout)
+            .await
+            .map_err(|e| ProofError::Rejected(format!("reasoning failed: {}", e)))?;
+        
+        Ok(PreparedProof {
+            task_id: task.task_id,
+            input_hash,
+            output_hash: result_hash(&result).map_err(|e| ProofError::Rejected(e.to_string()))?,
+            computation_cost: result.work_units,
+            result,
+        })
+    }
+    
+    async fn submit_to_chain(&self, proof: &PreparedProof) -> Result<SubmittedProof, ProofError> {
+        info!("📤 Submitting proof for task {} to blockchain...", proof.task_id);
+        
+        let call = self
+            .contract
+            .submit_proof(proof.input_hash, proof.output_hash, U256::from(proof.computation_cost));
+        
+        let receipt = self.send_and_confirm(call).await?;
+        let tx_hash = receipt.transaction_hash;
+        
+        let proof_id = proof_id_from_receipt(&receipt)
+            .ok_or_else(|| anyhow!("no ProofSubmitted event in transaction {:?}", tx_hash))?;
+        
+        info!("🧾 Proof {} recorded for task {} (tx {:?})", to_hex(&proof_id), proof.task_id, tx_hash);
+        
+        Ok(SubmittedProof {
+            proof_id,
+            task_id: proof.task_id,
+            input_hash: proof.input_hash,
+            output_hash: proof.output_hash,
+            computation_cost: proof.computation_cost,
+            tx_hash,
+        })
     }
     
-    fn hash_output(&self, output: &str) -> String {
-        let mut hasher = Sha256::new();
-        hasher.update(output.as_bytes());
-        format!("0x{}", hex::encode(hasher.finalize()))
+    // Loads a proof and returns it if this validator still has to vote on it
+    pub async fn peer_proof(&self, proof_id: Hash32) -> Result<Option<PeerProof>> {
+        let (input_hash, output_hash, prover, _, computation_cost, verified, _) = self.contract.proofs(proof_id).call().await?;
+        
+        if prover.is_zero() || verified {
+            return Ok(None);
+        }
+        
+        if let Some(me) = self.contract.client().default_sender() {
+            if prover == me || self.contract.has_voted(proof_id, me).call().await? {
+                return Ok(None);
+            }
+        }
+        
+        Ok(Some(PeerProof {
+            proof_id,
+            input_hash,
+            output_hash,
+            computation_cost: computation_cost.min(U256::from(u64::MAX)).as_u64(),
+            prover,
+        }))
     }
     
-    async fn compute_reasoning(&self, task_id: &str) -> Result<String> {
-        // Actual reasoning computation happens here
-        Ok(format!("reasoning_result_{}", task_id))
+    // Re-executes the proof against the prover's assigned tasks and, unless
+    // the outcome is unverifiable, casts the vote with `validateProof`
+    pub async fn validate_peer_proof(&self, proof: &PeerProof, candidates: Vec<ReasoningTask>) -> Result<Verdict> {
+        info!("🔍 Validating peer proof {} from {:?}...", to_hex(&proof.proof_id), proof.prover);
+        
+        let verdict = self.verify_proof(proof, candidates).await?;
+        
+        match verdict.approved() {
+            Some(approved) => {
+                let receipt = self.send_and_confirm(self.contract.validate_proof(proof.proof_id, approved)).await?;
+                info!(
+                    "🗳️  Voted {} on proof {}: {:?} (tx {:?})",
+                    if approved { "for" } else { "against" },
+                    to_hex(&proof.proof_id),
+                    verdict,
+                    receipt.transaction_hash
+                );
+            }
+            None => debug!("Proof {} not verifiable yet: {:?}", to_hex(&proof.proof_id), verdict),
+        }
+        
+        Ok(verdict)
     }
     
-    async fn submit_to_chain(&self, _input_hash: &str, _output_hash: &str) -> Result<()> {
-        info!("📤 Submitting proof to blockchain...");
-        // Submit to ProofOfReasoning contract
-        Ok(())
+    // Like `verify`, and the claimed computationCost has to match the work
+    // units of the re-run as well
+    pub async fn verify_proof(&self, proof: &PeerProof, candidates: Vec<ReasoningTask>) -> Result<Verdict> {
+        self.verify_run(proof.input_hash, proof.output_hash, Some(proof.computation_cost), candidates)
+            .await
     }
     
-    #[allow(dead_code)]
-    pub async fn validate_peer_proof(
+    // Verdict for a proof without touching the chain. `candidates` are the
+    // tasks assigned to the prover; the one the input hash commits to is re-run.
+    pub async fn verify(&self, input_hash: Hash32, output_hash: Hash32, candidates: Vec<ReasoningTask>) -> Result<Verdict> {
+        self.verify_run(input_hash, output_hash, None, candidates).await
+    }
+    
+    async fn verify_run(
         &self,
-        _input_hash: &str,
-        _output_hash: &str,
-    ) -> Result<bool> {
-        info!("🔍 Validating peer proof...");
+        input_hash: Hash32,
+        output_hash: Hash32,
+        computation_cost: Option<u64>,
+        candidates: Vec<ReasoningTask>,
+    ) -> Result<Verdict> {
+        let mut task = None;
+        for candidate in candidates {
+            if proof_input_hash(&candidate)? == input_hash {
+                task = Some(candidate);
+                break;
+            }
+        }
+        
+        let Some(mut task) = task else {
+            return Ok(Verdict::Mismatch("input hash does not match any task assigned to the prover".to_string()));
+        };
+        
+        if !self.processor.modules().supports(&task) {
+            return Ok(Verdict::Unverifiable(format!("no module for task type {}", task.task_type)));
+        }
+        
+        if let Err(e) = resolve_task_data(self.data_store.as_ref(), &mut task).await {
+            return Ok(Verdict::Unverifiable(format!("task data unavailable: {}", e)));
+        }
+        
+        // Running out of time says nothing about the proof, this machine may
+        // just be slower than the prover's
+        let result = match self.processor.process_with_timeout(&task, self.task_timeout).await {
+            Ok(result) => result,
+            Err(e) if e.is::<TimedOut>() => return Ok(Verdict::Unverifiable(e.to_string())),
+            Err(e) => return Ok(Verdict::Mismatch(format!("reasoning failed on task input: {}", e))),
+        };
         
-        // Verify proof validity
-        // Check computation correctness
+        if let Some(claimed) = computation_cost.filter(|claimed| *claimed != result.work_units) {
+            return Ok(Verdict::Mismatch(format!(
+                "claimed computation cost {} differs from {} work units on re-execution",
+                claimed, result.work_units
+            )));
+        }
         
-        Ok(true)
+        if result_hash(&result)? == output_hash {
+            return Ok(Verdict::Match);
+        }
+        
+        let Some(rules) = self.policy.rules(&task.task_type) else {
+            return Ok(Verdict::Mismatch("output hash differs".to_string()));
+        };
+        
+        let submitted = match self.data_store.fetch(&output_hash).await {
+            Ok(submitted) => submitted,
+            Err(DataStoreError::NotFound(_)) => {
+                return Ok(Verdict::Mismatch("output hash differs and the submitted output was not published".to_string()))
+            }
+            Err(e) => return Ok(Verdict::Unverifiable(format!("submitted output unavailable: {}", e))),
+        };
+        
+        Ok(match rules.compare(&committed_result(&result), &submitted) {
+            Ok(()) => Verdict::WithinTolerance,
+            Err(reason) => Verdict::Mismatch(reason),
+        })
     }
+    
+    async fn send_and_confirm<D: ethers::abi::Detokenize>(
+        &self,
+        call: ContractCall<M, D>,
+    ) -> Result<TransactionReceipt, ProofError> {
+        // Dry-run first so a revert surfaces with its reason and costs no gas
+        if let Err(e) = call.call().await {
+            return Err(match e.decode_revert::<String>() {
+                Some(reason) => ProofError::Rejected(format!("reverted: {}", reason)),
+                None if e.is_revert() => ProofError::Rejected(format!("reverted: {}", e)),
+                None => ProofError::Other(e.into()),
+            });
+        }
+        
+        let pending = call.send().await.map_err(anyhow::Error::from)?;
+        let tx_hash = pending.tx_hash();
+        
+        let receipt = pending
+            .confirmations(self.confirmations)
+            .await
+            .map_err(anyhow::Error::from)?
+            .ok_or_else(|| anyhow!("transaction {:?} was dropped", tx_hash))?;
+        
+        // The state may have changed between the dry-run and inclusion; the
+        // next attempt's dry-run tells
+        if receipt.status != Some(U64::one()) {
+            return Err(anyhow!("transaction {:?} failed on-chain", tx_hash).into());
+        }
+        
+        Ok(receipt)
+    }
+}
+
+pub fn proof_id_from_receipt(receipt: &TransactionReceipt) -> Option<Hash32> {
+    receipt
+        .logs
+        .iter()
+        .find_map(|log| parse_log::<ProofSubmittedFilter>(log.clone()).ok())
+        .map(|event| event.proof_id)
 }
@@ -0,0 +1,186 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+// Outcome of re-executing a peer's reasoning run
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    // Recomputed output hash equals the submitted one
+    Match,
+    // Hashes differ but every value is within the task type's tolerance
+    WithinTolerance,
+    Mismatch(String),
+    // Nothing could be checked yet (payload or output unavailable); no vote is cast
+    Unverifiable(String),
+}
+
+impl Verdict {
+    pub fn approved(&self) -> Option<bool> {
+        match self {
+            Self::Match | Self::WithinTolerance => Some(true),
+            Self::Mismatch(_) => Some(false),
+            Self::Unverifiable(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Tolerance {
+    #[serde(default)]
+    pub absolute: f64,
+    #[serde(default)]
+    pub relative: f64,
+}
+
+impl Tolerance {
+    pub fn exact() -> Self {
+        Self::default()
+    }
+    
+    pub fn absolute(absolute: f64) -> Self {
+        Self { absolute, relative: 0.0 }
+    }
+    
+    pub fn relative(relative: f64) -> Self {
+        Self { absolute: 0.0, relative }
+    }
+    
+    pub fn accepts(&self, expected: f64, submitted: f64) -> bool {
+        let diff = (expected - submitted).abs();
+        diff <= self.absolute.max(self.relative * expected.abs().max(submitted.abs()))
+    }
+}
+
+// Tolerances for one task type. Fields are JSON pointers into the committed
+// result (`/confidence_score`, `/prediction/predicted_price`); a rule also
+// covers everything below it, and the closest one wins.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToleranceRules {
+    #[serde(default)]
+    pub default: Tolerance,
+    #[serde(default)]
+    pub fields: HashMap<String, Tolerance>,
+}
+
+impl ToleranceRules {
+    pub fn exact() -> Self {
+        Self::default()
+    }
+    
+    pub fn with_field(mut self, pointer: &str, tolerance: Tolerance) -> Self {
+        self.fields.insert(pointer.to_string(), tolerance);
+        self
+    }
+    
+    pub fn for_path(&self, pointer: &str) -> Tolerance {
+        let mut path = pointer;
+        
+        loop {
+            if let Some(tolerance) = self.fields.get(path) {
+                return *tolerance;
+            }
+            match path.rfind('/') {
+                Some(idx) => path = &path[..idx],
+                None => return self.default,
+            }
+        }
+    }
+    
+    // Compares two committed results. Numbers may differ within tolerance;
+    // everything else (strings, booleans, array lengths, keys) must be equal.
+    pub fn compare(&self, expected: &Value, submitted: &Value) -> Result<(), String> {
+        self.compare_at("", expected, submitted)
+    }
+    
+    fn compare_at(&self, path: &str, expected: &Value, submitted: &Value) -> Result<(), String> {
+        let at = if path.is_empty() { "/" } else { path };
+        
+        match (expected, submitted) {
+            (Value::Number(a), Value::Number(b)) => {
+                let (a, b) = (a.as_f64().unwrap_or(f64::NAN), b.as_f64().unwrap_or(f64::NAN));
+                if self.for_path(path).accepts(a, b) {
+                    Ok(())
+                } else {
+                    Err(format!("{}: expected {}, got {}", at, a, b))
+                }
+            }
+            (Value::Array(a), Value::Array(b)) => {
+                if a.len() != b.len() {
+                    return Err(format!("{}: expected {} items, got {}", at, a.len(), b.len()));
+                }
+                a.iter()
+                    .zip(b)
+                    .enumerate()
+                    .try_for_each(|(idx, (a, b))| self.compare_at(&format!("{}/{}", path, idx), a, b))
+            }
+            (Value::Object(a), Value::Object(b)) => {
+                if let Some(key) = a.keys().find(|key| !b.contains_key(*key)) {
+                    return Err(format!("{}/{}: missing", path, key));
+                }
+                if let Some(key) = b.keys().find(|key| !a.contains_key(*key)) {
+                    return Err(format!("{}/{}: unexpected", path, key));
+                }
+                a.iter()
+                    .try_for_each(|(key, value)| self.compare_at(&format!("{}/{}", path, key), value, &b[key]))
+            }
+            (a, b) if a == b => Ok(()),
+            (a, b) => Err(format!("{}: expected {}, got {}", at, a, b)),
+        }
+    }
+}
+
+// Tolerance rules per task type. Task types without an entry must reproduce
+// the submitted output hash exactly.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerificationPolicy {
+    #[serde(default)]
+    pub task_types: HashMap<String, ToleranceRules>,
+}
+
+impl Default for VerificationPolicy {
+    fn default() -> Self {
+        Self::exact()
+            .with_rules(
+                "market_prediction",
+                ToleranceRules::exact()
+                    .with_field("/prediction", Tolerance::relative(1e-6))
+                    .with_field("/confidence_score", Tolerance::absolute(1e-6)),
+            )
+            .with_rules(
+                "risk_scoring",
+                ToleranceRules::exact()
+                    .with_field("/prediction/risk_score", Tolerance::absolute(1e-6))
+                    .with_field("/confidence_score", Tolerance::absolute(1e-6)),
+            )
+            // Only logarithms and square roots may round differently
+            // across platforms
+            .with_rules(
+                "technical_indicators",
+                ToleranceRules::exact().with_field("/prediction", Tolerance::relative(1e-9)),
+            )
+    }
+}
+
+impl VerificationPolicy {
+    pub fn exact() -> Self {
+        Self {
+            task_types: HashMap::new(),
+        }
+    }
+    
+    pub fn with_rules(mut self, task_type: &str, rules: ToleranceRules) -> Self {
+        self.task_types.insert(task_type.to_string(), rules);
+        self
+    }
+    
+    pub fn rules(&self, task_type: &str) -> Option<&ToleranceRules> {
+        self.task_types.get(task_type)
+    }
+    
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+}
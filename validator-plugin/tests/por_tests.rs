@@ -0,0 +1,274 @@
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use bindings::proof_of_reasoning::ProofSubmittedFilter;
+    use cognitive_engine::datastore::{LocalDataStore, TaskDataStore};
+    use cognitive_engine::reasoning::market_prediction::MarketPredictor;
+    use cognitive_engine::reasoning::ReasoningModule;
+    use cognitive_engine::tasks::TaskProcessor;
+    use ethers::prelude::*;
+    use serde_json::{json, Value};
+    use shared::hashing::{committed_result, proof_input_hash, result_hash, to_hex, Hash32};
+    use shared::types::{MarketPredictionInput, ReasoningResult, ReasoningTask, TaskPayload};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use validator_plugin::por::{proof_id_from_receipt, PeerProof, ProofError, ProofOfReasoningValidator, SubmittedProof};
+    use validator_plugin::verify::{Verdict, VerificationPolicy};
+
+    fn task(data_hash: String) -> ReasoningTask {
+        ReasoningTask {
+            task_id: 5,
+            task_type: "market_prediction".to_string(),
+            data: Value::Null,
+            data_hash,
+            requester: "0x0".to_string(),
+            reward: 0,
+            deadline: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prepare_runs_reasoning_on_payload() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(LocalDataStore::new(dir.path()));
+        let payload = json!({ "symbol": "MATIC/USD", "prices": [1.2, 1.3, 1.25, 1.4, 1.35] });
+        let data_hash = store.put(&payload).await.unwrap();
+        
+        // Nothing is sent, so the provider never has to be reachable
+        let provider = Arc::new(Provider::<Http>::try_from("http://127.0.0.1:1").unwrap());
+        let validator = ProofOfReasoningValidator::new(Address::zero(), provider, store);
+        
+        let proof = validator.prepare(task(to_hex(&data_hash))).await.unwrap();
+        
+        let mut resolved = task(to_hex(&data_hash));
+        resolved.data = payload.clone();
+        assert_eq!(proof.input_hash, proof_input_hash(&resolved).unwrap());
+        
+        let expected = MarketPredictor.forecast(&MarketPredictionInput::from_value(&payload).unwrap());
+        assert_eq!(proof.result.output::<Value>().unwrap(), serde_json::to_value(expected).unwrap());
+        assert_eq!(proof.output_hash, result_hash(&proof.result).unwrap());
+        // Holt over five prices and backtest fits on the first 2, 3 and 4
+        let work = (81 * 5 + 1) + (81 * 2 + 1) + (81 * 3 + 1) + (81 * 4 + 1);
+        assert_eq!(proof.computation_cost, work);
+        assert_eq!(proof.result.work_units, work);
+        assert!(validator.submitted(5).is_none());
+    }
+
+    fn offline_validator(store: Arc<LocalDataStore>) -> ProofOfReasoningValidator<Provider<Http>> {
+        let provider = Arc::new(Provider::<Http>::try_from("http://127.0.0.1:1").unwrap());
+        ProofOfReasoningValidator::new(Address::zero(), provider, store)
+    }
+
+    // Runs the prover side and returns (task, input hash, output hash)
+    async fn honest_proof(validator: &ProofOfReasoningValidator<Provider<Http>>, store: &LocalDataStore) -> (ReasoningTask, Hash32, Hash32) {
+        let payload = json!({ "symbol": "MATIC/USD", "prices": [1.2, 1.3, 1.25, 1.4, 1.35] });
+        let data_hash = store.put(&payload).await.unwrap();
+        
+        let task = task(to_hex(&data_hash));
+        let proof = validator.prepare(task.clone()).await.unwrap();
+        (task, proof.input_hash, proof.output_hash)
+    }
+
+    #[tokio::test]
+    async fn test_verify_reproduced_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(LocalDataStore::new(dir.path()));
+        let validator = offline_validator(store.clone());
+        let (task, input_hash, output_hash) = honest_proof(&validator, &store).await;
+        
+        let other = ReasoningTask { task_id: 9, ..task.clone() };
+        let verdict = validator.verify(input_hash, output_hash, vec![other, task.clone()]).await.unwrap();
+        assert_eq!(verdict, Verdict::Match);
+        
+        // An input hash that commits to none of the prover's tasks
+        let verdict = validator.verify([1u8; 32], output_hash, vec![task.clone()]).await.unwrap();
+        assert!(matches!(verdict, Verdict::Mismatch(_)));
+        
+        // The claimed cost is reproduced along with the output
+        let mut proof = PeerProof {
+            proof_id: [2u8; 32],
+            input_hash,
+            output_hash,
+            computation_cost: (81 * 5 + 1) + (81 * 2 + 1) + (81 * 3 + 1) + (81 * 4 + 1),
+            prover: Address::repeat_byte(1),
+        };
+        assert_eq!(validator.verify_proof(&proof, vec![task.clone()]).await.unwrap(), Verdict::Match);
+        
+        proof.computation_cost = 6;
+        let verdict = validator.verify_proof(&proof, vec![task]).await.unwrap();
+        assert!(matches!(verdict, Verdict::Mismatch(reason) if reason.contains("computation cost")));
+    }
+
+    #[tokio::test]
+    async fn test_verify_with_tolerance() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(LocalDataStore::new(dir.path()));
+        let validator = offline_validator(store.clone());
+        let (task, input_hash, _) = honest_proof(&validator, &store).await;
+        
+        let mut result = validator.prepare(task.clone()).await.unwrap().result;
+        let price = result.prediction["predicted_price"].as_f64().unwrap();
+        
+        // Slightly different float math on the prover, output published
+        result.prediction["predicted_price"] = json!(price + 1e-7);
+        store.put(&committed_result(&result)).await.unwrap();
+        let verdict = validator.verify(input_hash, result_hash(&result).unwrap(), vec![task.clone()]).await.unwrap();
+        assert_eq!(verdict, Verdict::WithinTolerance);
+        
+        // The same output is rejected when the task type has no tolerance
+        let strict = offline_validator(store.clone()).with_policy(VerificationPolicy::exact());
+        let verdict = strict.verify(input_hash, result_hash(&result).unwrap(), vec![task.clone()]).await.unwrap();
+        assert!(matches!(verdict, Verdict::Mismatch(_)));
+        
+        // Outside the tolerance
+        result.prediction["predicted_price"] = json!(price * 1.01);
+        store.put(&committed_result(&result)).await.unwrap();
+        let verdict = validator.verify(input_hash, result_hash(&result).unwrap(), vec![task.clone()]).await.unwrap();
+        assert!(matches!(verdict, Verdict::Mismatch(reason) if reason.contains("/prediction/predicted_price")));
+        
+        // Different output that was never published
+        result.prediction["trend"] = json!("bearish");
+        let verdict = validator.verify(input_hash, result_hash(&result).unwrap(), vec![task]).await.unwrap();
+        assert!(matches!(verdict, Verdict::Mismatch(_)));
+    }
+
+    #[tokio::test]
+    async fn test_verify_without_payload_is_deferred() {
+        let dir = tempfile::tempdir().unwrap();
+        let validator = offline_validator(Arc::new(LocalDataStore::new(dir.path())));
+        
+        let task = task(to_hex(&[7u8; 32]));
+        let input_hash = proof_input_hash(&task).unwrap();
+        
+        let verdict = validator.verify(input_hash, [0u8; 32], vec![task]).await.unwrap();
+        assert!(matches!(verdict, Verdict::Unverifiable(_)));
+        assert_eq!(verdict.approved(), None);
+    }
+
+    // Takes far longer than any test is willing to wait
+    struct SlowModule;
+
+    #[async_trait]
+    impl ReasoningModule for SlowModule {
+        fn task_type(&self) -> &str {
+            "slow"
+        }
+        
+        fn input_schema(&self) -> Value {
+            json!({ "type": "object" })
+        }
+        
+        fn version(&self) -> &str {
+            "0.1.0"
+        }
+        
+        async fn run(&self, _data: &Value) -> anyhow::Result<ReasoningResult> {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(ReasoningResult::default())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_peer_task_runs_under_deadline() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut processor = TaskProcessor::new();
+        processor.register(SlowModule).unwrap();
+        let validator = offline_validator(Arc::new(LocalDataStore::new(dir.path())))
+            .with_processor(processor)
+            .with_task_timeout(Duration::from_millis(100));
+        
+        let mut slow = task("0x00".to_string());
+        slow.task_type = "slow".to_string();
+        slow.data = json!({ "n": 1 });
+        let input_hash = proof_input_hash(&slow).unwrap();
+        
+        // A slower machine than the prover's casts no vote either way
+        let verdict = tokio::time::timeout(Duration::from_secs(5), validator.verify(input_hash, [0u8; 32], vec![slow]))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(verdict, Verdict::Unverifiable(reason) if reason.contains("timed out")));
+    }
+
+    #[tokio::test]
+    async fn test_prepare_requires_payload() {
+        let dir = tempfile::tempdir().unwrap();
+        let provider = Arc::new(Provider::<Http>::try_from("http://127.0.0.1:1").unwrap());
+        let validator = ProofOfReasoningValidator::new(Address::zero(), provider, Arc::new(LocalDataStore::new(dir.path())));
+        
+        // Not published yet, so worth another try
+        let err = validator.prepare(task(to_hex(&[7u8; 32]))).await.unwrap_err();
+        assert!(!err.is_permanent());
+    }
+
+    #[tokio::test]
+    async fn test_prepare_rejects_unusable_tasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(LocalDataStore::new(dir.path()));
+        let validator = offline_validator(store.clone());
+        
+        let mut unknown = task("0x00".to_string());
+        unknown.task_type = "astrology".to_string();
+        unknown.data = json!({ "sign": "leo" });
+        assert!(matches!(validator.prepare(unknown).await, Err(ProofError::Rejected(_))));
+        
+        let data_hash = store.put(&json!({ "symbol": "MATIC/USD", "prices": [] })).await.unwrap();
+        assert!(validator.prepare(task(to_hex(&data_hash))).await.unwrap_err().is_permanent());
+        
+        assert!(validator.prepare(task("not a hash".to_string())).await.unwrap_err().is_permanent());
+    }
+
+    #[tokio::test]
+    async fn test_submitted_proofs_survive_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(LocalDataStore::new(dir.path()));
+        let path = dir.path().join("state").join("validator-proofs.json");
+        
+        // Nothing recorded yet
+        let validator = offline_validator(store.clone()).with_submitted_path(&path).unwrap();
+        assert!(validator.submitted(5).is_none());
+        
+        let proof = SubmittedProof {
+            proof_id: [0x42; 32],
+            task_id: 5,
+            input_hash: [1; 32],
+            output_hash: [2; 32],
+            computation_cost: 7,
+            tx_hash: TxHash::repeat_byte(0x33),
+        };
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, serde_json::to_vec(&json!({ "5": proof })).unwrap()).unwrap();
+        
+        // A restarted validator returns the recorded proof instead of
+        // running and submitting the task again
+        let restarted = offline_validator(store).with_submitted_path(&path).unwrap();
+        let recorded = restarted.submitted(5).unwrap();
+        assert_eq!(recorded.proof_id, [0x42; 32]);
+        assert_eq!(recorded.tx_hash, TxHash::repeat_byte(0x33));
+        
+        let submitted = restarted.submit_proof(task(to_hex(&[7u8; 32]))).await.unwrap();
+        assert_eq!(submitted.proof_id, [0x42; 32]);
+    }
+
+    #[test]
+    fn test_proof_id_from_receipt() {
+        let proof_id = [0x42u8; 32];
+        let prover = Address::repeat_byte(0x11);
+        
+        let log = Log {
+            topics: vec![
+                ProofSubmittedFilter::signature(),
+                H256::from(proof_id),
+                H256::from(prover),
+            ],
+            ..Default::default()
+        };
+        let receipt = TransactionReceipt {
+            logs: vec![Log::default(), log],
+            ..Default::default()
+        };
+        
+        assert_eq!(proof_id_from_receipt(&receipt), Some(proof_id));
+        assert_eq!(proof_id_from_receipt(&TransactionReceipt::default()), None);
+    }
+}
@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use validator_plugin::verify::{Tolerance, ToleranceRules, Verdict, VerificationPolicy};
+
+    #[test]
+    fn test_tolerance() {
+        assert!(Tolerance::exact().accepts(1.5, 1.5));
+        assert!(!Tolerance::exact().accepts(1.5, 1.5000001));
+        
+        assert!(Tolerance::absolute(0.01).accepts(1.0, 1.009));
+        assert!(!Tolerance::absolute(0.01).accepts(1.0, 1.02));
+        
+        assert!(Tolerance::relative(0.01).accepts(100.0, 100.9));
+        assert!(!Tolerance::relative(0.01).accepts(1.0, 1.02));
+    }
+
+    #[test]
+    fn test_rules_inherit_from_parent_fields() {
+        let rules = ToleranceRules::exact()
+            .with_field("/prediction", Tolerance::relative(0.1))
+            .with_field("/prediction/score", Tolerance::absolute(0.5));
+        
+        assert_eq!(rules.for_path("/prediction/price"), Tolerance::relative(0.1));
+        assert_eq!(rules.for_path("/prediction/values/3"), Tolerance::relative(0.1));
+        assert_eq!(rules.for_path("/prediction/score"), Tolerance::absolute(0.5));
+        assert_eq!(rules.for_path("/confidence_score"), Tolerance::exact());
+    }
+
+    #[test]
+    fn test_compare_outputs() {
+        let rules = ToleranceRules::exact().with_field("/prediction", Tolerance::absolute(0.01));
+        let expected = json!({ "prediction": { "price": 1.0, "trend": "bullish", "hits": [1, 2] }, "confidence_score": 0.8 });
+        
+        let close = json!({ "prediction": { "price": 1.005, "trend": "bullish", "hits": [1, 2] }, "confidence_score": 0.8 });
+        assert!(rules.compare(&expected, &close).is_ok());
+        
+        // Only numbers get tolerance
+        let other_trend = json!({ "prediction": { "price": 1.0, "trend": "bearish", "hits": [1, 2] }, "confidence_score": 0.8 });
+        assert!(rules.compare(&expected, &other_trend).unwrap_err().starts_with("/prediction/trend"));
+        
+        let fewer = json!({ "prediction": { "price": 1.0, "trend": "bullish", "hits": [1] }, "confidence_score": 0.8 });
+        assert!(rules.compare(&expected, &fewer).unwrap_err().starts_with("/prediction/hits"));
+        
+        let confidence = json!({ "prediction": { "price": 1.0, "trend": "bullish", "hits": [1, 2] }, "confidence_score": 0.81 });
+        assert!(rules.compare(&expected, &confidence).unwrap_err().starts_with("/confidence_score"));
+        
+        let extra = json!({ "prediction": { "price": 1.0, "trend": "bullish", "hits": [1, 2], "x": 1 }, "confidence_score": 0.8 });
+        assert!(rules.compare(&expected, &extra).is_err());
+    }
+
+    #[test]
+    fn test_policy_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tolerance.json");
+        std::fs::write(
+            &path,
+            r#"{ "task_types": { "market_prediction": { "fields": { "/prediction/predicted_price": { "relative": 0.001 } } } } }"#,
+        )
+        .unwrap();
+        
+        let policy = VerificationPolicy::from_file(&path).unwrap();
+        let rules = policy.rules("market_prediction").unwrap();
+        assert_eq!(rules.for_path("/prediction/predicted_price"), Tolerance::relative(0.001));
+        assert!(policy.rules("anomaly_detection").is_none());
+        
+        assert!(VerificationPolicy::default().rules("anomaly_detection").is_none());
+        let rules = VerificationPolicy::default().rules("technical_indicators").cloned().unwrap();
+        assert_eq!(rules.for_path("/prediction/indicators/0/latest/rsi"), Tolerance::relative(1e-9));
+        assert_eq!(Verdict::WithinTolerance.approved(), Some(true));
+    }
+}